@@ -0,0 +1,243 @@
+//! Setup and config: initialize, roles, timelocked changes and pausing.
+
+use alons_box::state::*;
+use alons_box::{accounts, instruction};
+use alons_box_client::{pda, tx};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::system_program;
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use solana_signer::Signer;
+
+use crate::args;
+use crate::Ctx;
+
+#[derive(Args)]
+pub struct Initialize {
+    #[arg(long, value_parser = args::pubkey)]
+    treasury: Pubkey,
+    #[arg(long, value_parser = args::pubkey)]
+    buyback_wallet: Pubkey,
+    /// Pause key; must differ from the authority
+    #[arg(long, value_parser = args::pubkey)]
+    guardian: Pubkey,
+}
+
+pub fn initialize(ctx: &Ctx, cmd: Initialize) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::Initialize {
+            authority: signer.pubkey(),
+            game_state: pda::game_state(),
+            vault: pda::vault(),
+            jackpot_pool: pda::jackpot_pool(),
+            roles: pda::roles(),
+            system_program: system_program::ID,
+        },
+        instruction::Initialize {
+            treasury: cmd.treasury,
+            buyback_wallet: cmd.buyback_wallet,
+            guardian: cmd.guardian,
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+/// Revoke capabilities at once; grants go through `queue-change role`
+#[derive(Args)]
+pub struct SetRole {
+    #[arg(value_parser = args::pubkey)]
+    member: Pubkey,
+    /// The member's remaining capabilities, a subset of the current ones.
+    /// Comma-separated: round-creator, settler, closer, config-admin,
+    /// buyback, all, none — or the raw bitmask
+    #[arg(value_parser = args::capabilities)]
+    capabilities: u8,
+}
+
+pub fn set_role(ctx: &Ctx, cmd: SetRole) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::SetRole {
+            admin: signer.pubkey(),
+            roles: pda::roles(),
+        },
+        instruction::SetRole {
+            member: cmd.member,
+            capabilities: cmd.capabilities,
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Subcommand)]
+pub enum QueueChange {
+    /// Treasury wallet
+    Treasury {
+        #[arg(value_parser = args::pubkey)]
+        treasury: Pubkey,
+    },
+    /// Buyback wallet
+    BuybackWallet {
+        #[arg(value_parser = args::pubkey)]
+        buyback_wallet: Pubkey,
+    },
+    /// Rollover cap and where overflow goes
+    RolloverCap {
+        /// Lamports, 0 = uncapped
+        rollover_cap: u64,
+        /// Where rollover above the cap goes: jackpot or buyback
+        #[arg(long, value_parser = args::overflow_policy)]
+        policy: OverflowPolicy,
+    },
+    /// Guardian key
+    Guardian {
+        #[arg(value_parser = args::pubkey)]
+        guardian: Pubkey,
+    },
+    /// Timelock delay
+    TimelockDelay {
+        /// Seconds
+        delay: i64,
+    },
+    /// New payout split; omitted shares keep their current value
+    Payout(PayoutArgs),
+    /// Referral share of the treasury cut on referred deposits
+    ReferralBps {
+        bps: u16,
+    },
+    /// A member's full capability set, replacing the current one
+    Role {
+        #[arg(value_parser = args::pubkey)]
+        member: Pubkey,
+        /// Comma-separated: round-creator, settler, closer, config-admin,
+        /// buyback, all, none — or the raw bitmask
+        #[arg(value_parser = args::capabilities)]
+        capabilities: u8,
+    },
+    /// Swap program init_buyback accepts
+    SwapProgram {
+        #[arg(value_parser = args::pubkey)]
+        swap_program: Pubkey,
+    },
+}
+
+#[derive(Args)]
+pub struct PayoutArgs {
+    #[arg(long)]
+    winner_bps: Option<u16>,
+    #[arg(long)]
+    evidence_bps: Option<u16>,
+    #[arg(long)]
+    treasury_bps: Option<u16>,
+    #[arg(long)]
+    expire_buyback_bps: Option<u16>,
+    #[arg(long)]
+    expire_treasury_bps: Option<u16>,
+}
+
+impl PayoutArgs {
+    /// `current` with the given shares replaced.
+    pub fn apply(&self, current: PayoutConfig) -> PayoutConfig {
+        PayoutConfig {
+            winner_bps: self.winner_bps.unwrap_or(current.winner_bps),
+            evidence_bps: self.evidence_bps.unwrap_or(current.evidence_bps),
+            treasury_bps: self.treasury_bps.unwrap_or(current.treasury_bps),
+            expire_buyback_bps: self.expire_buyback_bps.unwrap_or(current.expire_buyback_bps),
+            expire_treasury_bps: self.expire_treasury_bps.unwrap_or(current.expire_treasury_bps),
+        }
+    }
+}
+
+pub fn queue_change(ctx: &Ctx, cmd: QueueChange) -> Result<()> {
+    let signer = ctx.signer()?;
+    let gs = ctx.game_state()?;
+    let change = match cmd {
+        QueueChange::Treasury { treasury } => ConfigChange::Treasury(treasury),
+        QueueChange::BuybackWallet { buyback_wallet } => ConfigChange::BuybackWallet(buyback_wallet),
+        QueueChange::RolloverCap { rollover_cap, policy } => ConfigChange::RolloverCap {
+            rollover_cap,
+            overflow_policy: policy,
+        },
+        QueueChange::Guardian { guardian } => ConfigChange::Guardian(guardian),
+        QueueChange::TimelockDelay { delay } => ConfigChange::TimelockDelay(delay),
+        QueueChange::Payout(payout) => ConfigChange::Payout(payout.apply(gs.payout)),
+        QueueChange::ReferralBps { bps } => ConfigChange::ReferralBps(bps),
+        QueueChange::Role { member, capabilities } => ConfigChange::Role { member, capabilities },
+        QueueChange::SwapProgram { swap_program } => ConfigChange::SwapProgram(swap_program),
+    };
+    let change_id = gs.change_count + 1;
+    println!("Queueing change {}", change_id);
+
+    let ix = tx::instruction(
+        accounts::QueueChange {
+            admin: signer.pubkey(),
+            game_state: pda::game_state(),
+            roles: pda::roles(),
+            governance: pda::governance(),
+            pending_change: pda::pending_change(change_id),
+            system_program: system_program::ID,
+        },
+        instruction::QueueChange { change },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct ChangeId {
+    change_id: u64,
+}
+
+pub fn execute_change(ctx: &Ctx, cmd: ChangeId) -> Result<()> {
+    let signer = ctx.signer()?;
+    let key = pda::pending_change(cmd.change_id);
+    let pending: PendingChange = ctx.rpc.require_account(&key, "PendingChange")?;
+    let ix = tx::instruction(
+        accounts::ExecuteChange {
+            executor: signer.pubkey(),
+            game_state: pda::game_state(),
+            roles: pda::roles(),
+            pending_change: key,
+            queued_by: pending.queued_by,
+        },
+        instruction::ExecuteChange {},
+    );
+    ctx.send(&signer, &[ix])
+}
+
+pub fn cancel_change(ctx: &Ctx, cmd: ChangeId) -> Result<()> {
+    let signer = ctx.signer()?;
+    let key = pda::pending_change(cmd.change_id);
+    let pending: PendingChange = ctx.rpc.require_account(&key, "PendingChange")?;
+    let ix = tx::instruction(
+        accounts::CancelChange {
+            canceller: signer.pubkey(),
+            game_state: pda::game_state(),
+            roles: pda::roles(),
+            pending_change: key,
+            queued_by: pending.queued_by,
+        },
+        instruction::CancelChange {},
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct SetPaused {
+    /// Comma-separated: deposits, round-creation, settlement, all, none —
+    /// or the raw bitmask
+    #[arg(value_parser = args::pause_flags)]
+    paused: u8,
+}
+
+pub fn set_paused(ctx: &Ctx, cmd: SetPaused) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::SetPaused {
+            guardian: signer.pubkey(),
+            game_state: pda::game_state(),
+        },
+        instruction::SetPaused { paused: cmd.paused },
+    );
+    ctx.send(&signer, &[ix])
+}
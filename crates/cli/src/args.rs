@@ -0,0 +1,134 @@
+//! Value parsers for command-line arguments.
+
+use std::str::FromStr;
+
+use alons_box::state::*;
+use anchor_lang::prelude::Pubkey;
+
+pub fn pubkey(s: &str) -> Result<Pubkey, String> {
+    Pubkey::from_str(s).map_err(|e| format!("invalid pubkey {}: {}", s, e))
+}
+
+pub fn hash(s: &str) -> Result<[u8; 32], String> {
+    alons_box_client::commit::parse_hash(s).map_err(|e| e.to_string())
+}
+
+/// `<pubkey>:<bps>`.
+fn weighted(s: &str) -> Result<(Pubkey, u16), String> {
+    let (key, bps) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected <pubkey>:<bps>, got {}", s))?;
+    Ok((pubkey(key)?, bps.parse().map_err(|_| format!("invalid BPS in {}", s))?))
+}
+
+/// Revenue split recipient: `<pubkey>:<bps>`.
+pub fn recipient(s: &str) -> Result<SplitRecipient, String> {
+    let (key, bps) = weighted(s)?;
+    Ok(SplitRecipient { key, bps })
+}
+
+/// Settle evidence entry: `<wallet>:<bps>:<question PDA>`.
+#[derive(Clone)]
+pub struct Evidence {
+    pub wallet: Pubkey,
+    pub bps: u16,
+    pub question: Pubkey,
+}
+
+pub fn evidence(s: &str) -> Result<Evidence, String> {
+    let parts: Vec<&str> = s.split(':').collect();
+    let [wallet, bps, question] = parts[..] else {
+        return Err(format!("expected <wallet>:<bps>:<question PDA>, got {}", s));
+    };
+    Ok(Evidence {
+        wallet: pubkey(wallet)?,
+        bps: bps.parse().map_err(|_| format!("invalid BPS in {}", s))?,
+        question: pubkey(question)?,
+    })
+}
+
+/// Comma-separated list of names mapped to bits, or a raw number.
+fn flags(s: &str, names: &[(&str, u8)]) -> Result<u8, String> {
+    if let Ok(n) = s.parse() {
+        return Ok(n);
+    }
+    s.split(',').try_fold(0u8, |acc, name| {
+        names
+            .iter()
+            .find(|(n, _)| *n == name.trim())
+            .map(|(_, bit)| acc | bit)
+            .ok_or_else(|| {
+                let known: Vec<&str> = names.iter().map(|(n, _)| *n).collect();
+                format!("unknown flag {} (expected {})", name, known.join(", "))
+            })
+    })
+}
+
+/// Role capabilities, e.g. `settler,closer` or `all`.
+pub fn capabilities(s: &str) -> Result<u8, String> {
+    flags(
+        s,
+        &[
+            ("none", 0),
+            ("round-creator", ROLE_ROUND_CREATOR),
+            ("settler", ROLE_SETTLER),
+            ("closer", ROLE_CLOSER),
+            ("config-admin", ROLE_CONFIG_ADMIN),
+            ("buyback", ROLE_BUYBACK),
+            ("all", ROLE_ALL),
+        ],
+    )
+}
+
+/// Pause flags, e.g. `deposits,settlement` or `none`.
+pub fn pause_flags(s: &str) -> Result<u8, String> {
+    flags(
+        s,
+        &[
+            ("none", 0),
+            ("deposits", PAUSE_DEPOSITS),
+            ("round-creation", PAUSE_ROUND_CREATION),
+            ("settlement", PAUSE_SETTLEMENT),
+            ("all", PAUSE_ALL),
+        ],
+    )
+}
+
+pub fn overflow_policy(s: &str) -> Result<OverflowPolicy, String> {
+    match s {
+        "jackpot" => Ok(OverflowPolicy::Jackpot),
+        "buyback" => Ok(OverflowPolicy::Buyback),
+        _ => Err(format!("expected jackpot or buyback, got {}", s)),
+    }
+}
+
+pub fn buyback_mode(s: &str) -> Result<BuybackMode, String> {
+    match s {
+        "burn" => Ok(BuybackMode::Burn),
+        "lock" => Ok(BuybackMode::Lock),
+        _ => Err(format!("expected burn or lock, got {}", s)),
+    }
+}
+
+pub fn tie_break(s: &str) -> Result<TieBreakPolicy, String> {
+    match s {
+        "equal-split" => Ok(TieBreakPolicy::EqualSplit),
+        "earliest-guess" => Ok(TieBreakPolicy::EarliestGuess),
+        "largest-deposit" => Ok(TieBreakPolicy::LargestDeposit),
+        _ => Err(format!("expected equal-split, earliest-guess or largest-deposit, got {}", s)),
+    }
+}
+
+/// Soft-close rule: `<window_secs>,<extension_secs>,<max_ends_at>`.
+pub fn soft_close(s: &str) -> Result<SoftClose, String> {
+    let parts: Vec<&str> = s.split(',').collect();
+    let [window, extension, max] = parts[..] else {
+        return Err(format!("expected <window_secs>,<extension_secs>,<max_ends_at>, got {}", s));
+    };
+    let err = |_| format!("invalid soft close {}", s);
+    Ok(SoftClose {
+        window_secs: window.parse().map_err(err)?,
+        extension_secs: extension.parse().map_err(err)?,
+        max_ends_at: max.parse().map_err(err)?,
+    })
+}
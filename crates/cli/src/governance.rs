@@ -0,0 +1,169 @@
+//! Governance: token locking, payout proposals and votes.
+
+use alons_box::state::*;
+use alons_box::{accounts, instruction};
+use alons_box_client::{pda, tx};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::system_program;
+use anyhow::Result;
+use clap::Args;
+use solana_signer::Signer;
+
+use crate::admin::PayoutArgs;
+use crate::args;
+use crate::Ctx;
+
+#[derive(Args)]
+pub struct InitGovernance {
+    /// The governance token mint
+    #[arg(long, value_parser = args::pubkey)]
+    mint: Pubkey,
+    #[arg(long)]
+    quorum_bps: u16,
+    #[arg(long)]
+    approval_bps: u16,
+    /// Seconds a proposal stays open for votes
+    #[arg(long)]
+    voting_period: i64,
+    /// Locked tokens needed to create a proposal
+    #[arg(long)]
+    proposal_threshold: u64,
+}
+
+pub fn init_governance(ctx: &Ctx, cmd: InitGovernance) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::InitGovernance {
+            admin: signer.pubkey(),
+            roles: pda::roles(),
+            governance: pda::governance(),
+            mint: cmd.mint,
+            governance_vault: pda::governance_vault(),
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitGovernance {
+            quorum_bps: cmd.quorum_bps,
+            approval_bps: cmd.approval_bps,
+            voting_period: cmd.voting_period,
+            proposal_threshold: cmd.proposal_threshold,
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct TokenAmount {
+    /// Token base units
+    amount: u64,
+    /// The signer's token account for the governance mint
+    #[arg(long, value_parser = args::pubkey)]
+    owner_tokens: Pubkey,
+}
+
+pub fn lock_tokens(ctx: &Ctx, cmd: TokenAmount) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::LockTokens {
+            owner: signer.pubkey(),
+            governance: pda::governance(),
+            vote_escrow: pda::vote_escrow(&signer.pubkey()),
+            owner_tokens: cmd.owner_tokens,
+            governance_vault: pda::governance_vault(),
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::LockTokens { amount: cmd.amount },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+pub fn unlock_tokens(ctx: &Ctx, cmd: TokenAmount) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::UnlockTokens {
+            owner: signer.pubkey(),
+            governance: pda::governance(),
+            vote_escrow: pda::vote_escrow(&signer.pubkey()),
+            owner_tokens: cmd.owner_tokens,
+            governance_vault: pda::governance_vault(),
+            token_program: anchor_spl::token::ID,
+        },
+        instruction::UnlockTokens { amount: cmd.amount },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct CreateProposal {
+    /// Proposed payout split; omitted shares keep their current value
+    #[command(flatten)]
+    payout: PayoutArgs,
+}
+
+pub fn create_proposal(ctx: &Ctx, cmd: CreateProposal) -> Result<()> {
+    let signer = ctx.signer()?;
+    let gs = ctx.game_state()?;
+    let governance: Governance = ctx.rpc.require_account(&pda::governance(), "Governance")?;
+    let proposal_id = governance.proposal_count + 1;
+    println!("Creating proposal {}", proposal_id);
+
+    let ix = tx::instruction(
+        accounts::CreateProposal {
+            proposer: signer.pubkey(),
+            governance: pda::governance(),
+            vote_escrow: pda::vote_escrow(&signer.pubkey()),
+            proposal: pda::proposal(proposal_id),
+            system_program: system_program::ID,
+        },
+        instruction::CreateProposal {
+            payout: cmd.payout.apply(gs.payout),
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct CastVote {
+    proposal_id: u64,
+    /// Vote against instead of for
+    #[arg(long)]
+    against: bool,
+}
+
+pub fn cast_vote(ctx: &Ctx, cmd: CastVote) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::CastVote {
+            voter: signer.pubkey(),
+            proposal: pda::proposal(cmd.proposal_id),
+            vote_escrow: pda::vote_escrow(&signer.pubkey()),
+            vote_record: pda::vote_record(cmd.proposal_id, &signer.pubkey()),
+            system_program: system_program::ID,
+        },
+        instruction::CastVote { support: !cmd.against },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct ProposalId {
+    proposal_id: u64,
+}
+
+pub fn finalize_proposal(ctx: &Ctx, cmd: ProposalId) -> Result<()> {
+    let signer = ctx.signer()?;
+    let gs = ctx.game_state()?;
+    let ix = tx::instruction(
+        accounts::FinalizeProposal {
+            caller: signer.pubkey(),
+            governance: pda::governance(),
+            game_state: pda::game_state(),
+            proposal: pda::proposal(cmd.proposal_id),
+            pending_change: pda::pending_change(gs.change_count + 1),
+            system_program: system_program::ID,
+        },
+        instruction::FinalizeProposal {},
+    );
+    ctx.send(&signer, &[ix])
+}
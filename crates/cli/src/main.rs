@@ -0,0 +1,250 @@
+//! `alons-box-cli` — operator CLI for the Alon's Box program.
+//!
+//! One subcommand per program instruction, plus `show` for inspecting
+//! accounts and `commit` for generating round commits. Every transaction is
+//! simulated first and only sent if the simulation succeeds; `--dry-run`
+//! stops after the simulation.
+
+mod admin;
+mod args;
+mod governance;
+mod round;
+mod show;
+mod treasury;
+
+use std::path::PathBuf;
+
+use alons_box::state::GameState;
+use alons_box_client::commit::{commit_hash, to_hex};
+use alons_box_client::{pda, rpc, tx, Rpc};
+use anchor_lang::solana_program::instruction::Instruction;
+use anyhow::{anyhow, bail, Result};
+use clap::{Parser, Subcommand};
+use solana_keypair::{read_keypair_file, Keypair};
+
+#[derive(Parser)]
+#[command(name = "alons-box-cli", version, about = "Operate the Alon's Box program")]
+struct Cli {
+    /// RPC URL, or one of localnet, devnet, mainnet-beta
+    #[arg(long, short = 'u', global = true, env = "ALONS_BOX_RPC_URL", default_value = "localnet")]
+    url: String,
+
+    /// Signer keypair file [default: ~/.config/solana/id.json]
+    #[arg(long, short = 'k', global = true, env = "ALONS_BOX_KEYPAIR")]
+    keypair: Option<PathBuf>,
+
+    /// Simulate the transaction and print the result without sending it
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Inspect program accounts
+    #[command(subcommand)]
+    Show(show::Show),
+    /// Print the commit hash create_round expects for an answer and salt
+    Commit {
+        #[arg(long)]
+        answer: String,
+        #[arg(long)]
+        salt: String,
+    },
+
+    // ── Setup and config ──
+    /// Create GameState, Vault, JackpotPool and Roles
+    Initialize(admin::Initialize),
+    /// Set a member's role capabilities
+    SetRole(admin::SetRole),
+    /// Queue a config change behind the timelock
+    #[command(subcommand)]
+    QueueChange(admin::QueueChange),
+    /// Apply a queued change once its delay has passed
+    ExecuteChange(admin::ChangeId),
+    /// Cancel a queued change
+    CancelChange(admin::ChangeId),
+    /// Set the pause flags (guardian)
+    SetPaused(admin::SetPaused),
+
+    // ── Governance ──
+    /// Create the governance config and token vault
+    InitGovernance(governance::InitGovernance),
+    /// Lock governance tokens for voting power
+    LockTokens(governance::TokenAmount),
+    /// Unlock governance tokens
+    UnlockTokens(governance::TokenAmount),
+    /// Propose a new payout split
+    CreateProposal(governance::CreateProposal),
+    /// Vote on a proposal
+    CastVote(governance::CastVote),
+    /// Close voting and queue a passed proposal
+    FinalizeProposal(governance::ProposalId),
+
+    // ── Treasury, buyback, seasons and referrals ──
+    /// Configure the buyback
+    InitBuyback(treasury::InitBuyback),
+    /// Swap the buyback vault balance for tokens
+    ExecuteBuyback(treasury::ExecuteBuyback),
+    /// Set the treasury revenue split recipients
+    SetRevenueSplit(treasury::SetRevenueSplit),
+    /// Pay the treasury pool out per the revenue split
+    DistributeTreasury,
+    /// Start vesting treasury cuts
+    InitTreasuryStream(treasury::InitTreasuryStream),
+    /// Push vested treasury funds to the treasury
+    WithdrawVested,
+    /// Create a season over a range of future rounds
+    CreateSeason(treasury::CreateSeason),
+    /// Pay a finished season's leaderboard
+    FinalizeSeason(treasury::SeasonId),
+    /// Register the signer as a referrer
+    RegisterReferrer,
+    /// Credit a settled deposit's referral share
+    CreditReferral(round::DepositRef),
+    /// Withdraw the signer's referral earnings
+    ClaimReferral,
+
+    // ── Rounds ──
+    /// Open a round with a commit hash
+    CreateRound(round::CreateRound),
+    /// Deposit into a round as the signer
+    Deposit(round::Deposit),
+    /// Set the signer's deposit limits
+    SetPlayerLimits(round::SetPlayerLimits),
+    /// Record a question asked in a round
+    RecordQuestion(round::RecordQuestion),
+    /// Record a player's correct guess and its slot
+    RecordGuess(round::RecordGuess),
+    /// Reveal the answer and pay the winners
+    Settle(round::Settle),
+    /// Reveal the answer and roll the round over with no winner
+    Expire(round::Expire),
+    /// Expire a round 24h past its end without the reveal
+    EmergencyExpire(round::RoundId),
+    /// Fail a round that missed its minimum pool
+    FailRound(round::RoundId),
+    /// Claim the signer's refund from a failed round
+    ClaimRefund(round::RoundId),
+    /// Close a finished deposit and return its rent
+    CloseDeposit(round::DepositRef),
+    /// Close a finished round's question and return its rent
+    CloseQuestion(round::QuestionRef),
+    /// Close a finished round's guess and return its rent
+    CloseGuess(round::DepositRef),
+    /// Close a finished round and archive it
+    CloseRound(round::RoundId),
+}
+
+/// Shared state for a command: the RPC connection, the signer and whether
+/// to stop after simulating.
+pub struct Ctx {
+    pub rpc: Rpc,
+    keypair: PathBuf,
+    dry_run: bool,
+}
+
+impl Ctx {
+    pub fn signer(&self) -> Result<Keypair> {
+        read_keypair_file(&self.keypair)
+            .map_err(|e| anyhow!("cannot read keypair {}: {}", self.keypair.display(), e))
+    }
+
+    pub fn game_state(&self) -> Result<GameState> {
+        self.rpc.require_account(&pda::game_state(), "GameState")
+    }
+
+    /// Simulate `instructions` signed by `signer`, then send them unless
+    /// this is a dry run.
+    pub fn send(&self, signer: &Keypair, instructions: &[Instruction]) -> Result<()> {
+        let submitted = tx::submit(&self.rpc, signer, instructions, self.dry_run)?;
+        let simulation = &submitted.simulation;
+        let units = simulation
+            .units_consumed
+            .map(|u| format!(" ({} compute units)", u))
+            .unwrap_or_default();
+
+        if let Some(err) = &simulation.err {
+            for log in &simulation.logs {
+                eprintln!("  {}", log);
+            }
+            bail!("simulation failed{}: {}", units, err);
+        }
+        println!("Simulation ok{}", units);
+        match submitted.signature {
+            Some(signature) => println!("Signature: {}", signature),
+            None => {
+                for log in &simulation.logs {
+                    println!("  {}", log);
+                }
+                println!("Dry run: not sent");
+            }
+        }
+        Ok(())
+    }
+}
+
+fn default_keypair() -> PathBuf {
+    let home = std::env::var_os("HOME").unwrap_or_default();
+    PathBuf::from(home).join(".config/solana/id.json")
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let ctx = Ctx {
+        rpc: Rpc::new(rpc::cluster_url(&cli.url)),
+        keypair: cli.keypair.unwrap_or_else(default_keypair),
+        dry_run: cli.dry_run,
+    };
+
+    match cli.command {
+        Command::Show(cmd) => show::run(&ctx, cmd),
+        Command::Commit { answer, salt } => {
+            println!("{}", to_hex(&commit_hash(&answer, &salt)));
+            Ok(())
+        }
+
+        Command::Initialize(cmd) => admin::initialize(&ctx, cmd),
+        Command::SetRole(cmd) => admin::set_role(&ctx, cmd),
+        Command::QueueChange(cmd) => admin::queue_change(&ctx, cmd),
+        Command::ExecuteChange(cmd) => admin::execute_change(&ctx, cmd),
+        Command::CancelChange(cmd) => admin::cancel_change(&ctx, cmd),
+        Command::SetPaused(cmd) => admin::set_paused(&ctx, cmd),
+
+        Command::InitGovernance(cmd) => governance::init_governance(&ctx, cmd),
+        Command::LockTokens(cmd) => governance::lock_tokens(&ctx, cmd),
+        Command::UnlockTokens(cmd) => governance::unlock_tokens(&ctx, cmd),
+        Command::CreateProposal(cmd) => governance::create_proposal(&ctx, cmd),
+        Command::CastVote(cmd) => governance::cast_vote(&ctx, cmd),
+        Command::FinalizeProposal(cmd) => governance::finalize_proposal(&ctx, cmd),
+
+        Command::InitBuyback(cmd) => treasury::init_buyback(&ctx, cmd),
+        Command::ExecuteBuyback(cmd) => treasury::execute_buyback(&ctx, cmd),
+        Command::SetRevenueSplit(cmd) => treasury::set_revenue_split(&ctx, cmd),
+        Command::DistributeTreasury => treasury::distribute_treasury(&ctx),
+        Command::InitTreasuryStream(cmd) => treasury::init_treasury_stream(&ctx, cmd),
+        Command::WithdrawVested => treasury::withdraw_vested(&ctx),
+        Command::CreateSeason(cmd) => treasury::create_season(&ctx, cmd),
+        Command::FinalizeSeason(cmd) => treasury::finalize_season(&ctx, cmd),
+        Command::RegisterReferrer => treasury::register_referrer(&ctx),
+        Command::CreditReferral(cmd) => treasury::credit_referral(&ctx, cmd),
+        Command::ClaimReferral => treasury::claim_referral(&ctx),
+
+        Command::CreateRound(cmd) => round::create_round(&ctx, cmd),
+        Command::Deposit(cmd) => round::deposit(&ctx, cmd),
+        Command::SetPlayerLimits(cmd) => round::set_player_limits(&ctx, cmd),
+        Command::RecordQuestion(cmd) => round::record_question(&ctx, cmd),
+        Command::RecordGuess(cmd) => round::record_guess(&ctx, cmd),
+        Command::Settle(cmd) => round::settle(&ctx, cmd),
+        Command::Expire(cmd) => round::expire(&ctx, cmd),
+        Command::EmergencyExpire(cmd) => round::emergency_expire(&ctx, cmd),
+        Command::FailRound(cmd) => round::fail_round(&ctx, cmd),
+        Command::ClaimRefund(cmd) => round::claim_refund(&ctx, cmd),
+        Command::CloseDeposit(cmd) => round::close_deposit(&ctx, cmd),
+        Command::CloseQuestion(cmd) => round::close_question(&ctx, cmd),
+        Command::CloseGuess(cmd) => round::close_guess(&ctx, cmd),
+        Command::CloseRound(cmd) => round::close_round(&ctx, cmd),
+    }
+}
@@ -0,0 +1,587 @@
+//! Round lifecycle: create, deposit, questions, guesses, settle/expire,
+//! refunds and rent recovery.
+
+use alons_box::state::*;
+use alons_box::{accounts, instruction};
+use alons_box_client::commit::{answer_hash, commit_hash, to_hex};
+use alons_box_client::payout::{preview_expire, preview_settle, CappedRollover, Stakes};
+use alons_box_client::{pda, sol, tx};
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::system_program;
+use anyhow::{bail, Result};
+use clap::Args;
+use solana_signer::Signer;
+
+use crate::args::{self, Evidence};
+use crate::show::status_name;
+use crate::Ctx;
+
+#[derive(Args)]
+pub struct RoundId {
+    round_id: u64,
+}
+
+#[derive(Args)]
+pub struct DepositRef {
+    pub round_id: u64,
+    #[arg(value_parser = args::pubkey)]
+    pub player: Pubkey,
+}
+
+#[derive(Args)]
+pub struct QuestionRef {
+    /// Question PDA
+    #[arg(value_parser = args::pubkey)]
+    pub question: Pubkey,
+}
+
+/// Fetch a round and require it to be Active.
+fn active_round(ctx: &Ctx, round_id: u64) -> Result<Round> {
+    let round: Round = ctx.rpc.require_account(&pda::round(round_id), "Round")?;
+    if round.status != RoundStatus::Active {
+        bail!("round {} is {}, not Active", round_id, status_name(round.status));
+    }
+    Ok(round)
+}
+
+/// Check `answer:salt` against the round's commit before spending a transaction.
+fn check_reveal(round: &Round, answer: &str, salt: &str) -> Result<()> {
+    if commit_hash(answer, salt) != round.commit_hash {
+        bail!(
+            "answer and salt do not match round {} commit {}",
+            round.round_id,
+            to_hex(&round.commit_hash)
+        );
+    }
+    Ok(())
+}
+
+fn print_rollover(rollover: &CappedRollover) {
+    println!("  rollover out       {:>20} SOL", sol(rollover.rollover_out));
+    if rollover.to_jackpot > 0 {
+        println!("  over cap → jackpot {:>20} SOL", sol(rollover.to_jackpot));
+    }
+    if rollover.to_buyback > 0 {
+        println!("  over cap → buyback {:>20} SOL", sol(rollover.to_buyback));
+    }
+}
+
+#[derive(Args)]
+pub struct CreateRound {
+    /// Defaults to the next round id
+    #[arg(long)]
+    round_id: Option<u64>,
+    /// Commit hash as 64 hex characters
+    #[arg(long, value_parser = args::hash, conflicts_with_all = ["answer", "salt"], required_unless_present = "answer")]
+    commit_hash: Option<[u8; 32]>,
+    /// Compute the commit from this answer and --salt
+    #[arg(long, requires = "salt")]
+    answer: Option<String>,
+    #[arg(long, requires = "answer")]
+    salt: Option<String>,
+    /// Unix time the round ends
+    #[arg(long, conflicts_with = "duration", required_unless_present = "duration")]
+    ends_at: Option<i64>,
+    /// Seconds from now the round ends
+    #[arg(long)]
+    duration: Option<i64>,
+    /// `<window_secs>,<extension_secs>,<max_ends_at>`
+    #[arg(long, value_parser = args::soft_close)]
+    soft_close: Option<SoftClose>,
+    /// Lamports below which anyone may fail the round
+    #[arg(long)]
+    min_pool: Option<u64>,
+    /// Pull the jackpot pool into this round
+    #[arg(long)]
+    jackpot: bool,
+    #[arg(long, default_value_t = 0)]
+    min_deposit: u64,
+    #[arg(long, default_value_t = 0)]
+    max_deposit: u64,
+    #[arg(long, default_value_t = 0)]
+    max_per_player: u64,
+    #[arg(long, default_value_t = 0)]
+    max_pool: u64,
+}
+
+pub fn create_round(ctx: &Ctx, cmd: CreateRound) -> Result<()> {
+    let signer = ctx.signer()?;
+    let gs = ctx.game_state()?;
+    let round_id = cmd.round_id.unwrap_or(gs.current_round_id + 1);
+    let commit = match (cmd.commit_hash, &cmd.answer, &cmd.salt) {
+        (Some(hash), _, _) => hash,
+        (None, Some(answer), Some(salt)) => commit_hash(answer, salt),
+        _ => bail!("pass --commit-hash or --answer and --salt"),
+    };
+    let ends_at = match (cmd.ends_at, cmd.duration) {
+        (Some(ends_at), _) => ends_at,
+        (None, Some(duration)) => ctx.rpc.cluster_time()? + duration,
+        _ => bail!("pass --ends-at or --duration"),
+    };
+    let deposit_policy = DepositPolicy {
+        min_deposit: cmd.min_deposit,
+        max_deposit: cmd.max_deposit,
+        max_per_player: cmd.max_per_player,
+        max_pool: cmd.max_pool,
+    };
+    println!("Round {}: commit {}, ends at {}", round_id, to_hex(&commit), ends_at);
+
+    let ix = tx::instruction(
+        accounts::CreateRound {
+            authority: signer.pubkey(),
+            game_state: pda::game_state(),
+            roles: pda::roles(),
+            round: pda::round(round_id),
+            vault: pda::vault(),
+            jackpot_pool: pda::jackpot_pool(),
+            season: pda::round_season(gs.season_for(round_id)),
+            system_program: system_program::ID,
+        },
+        instruction::CreateRound {
+            round_id,
+            commit_hash: commit,
+            ends_at,
+            soft_close: cmd.soft_close,
+            min_pool: cmd.min_pool,
+            jackpot: cmd.jackpot,
+            deposit_policy: (deposit_policy != DepositPolicy::default()).then_some(deposit_policy),
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct Deposit {
+    round_id: u64,
+    /// Lamports
+    amount: u64,
+    /// Referrer to bind on the signer's first referred deposit
+    #[arg(long, value_parser = args::pubkey)]
+    referrer: Option<Pubkey>,
+}
+
+pub fn deposit(ctx: &Ctx, cmd: Deposit) -> Result<()> {
+    let signer = ctx.signer()?;
+    let player = signer.pubkey();
+    let ix = tx::instruction(
+        accounts::DepositCtx {
+            player,
+            game_state: pda::game_state(),
+            round: pda::round(cmd.round_id),
+            deposit: pda::deposit(cmd.round_id, &player),
+            vault: pda::vault(),
+            player_limits: pda::player_limits(&player),
+            player_referral: pda::player_referral(&player),
+            player_profile: pda::player_profile(&player),
+            referrer: cmd.referrer.as_ref().map(pda::referrer),
+            system_program: system_program::ID,
+        },
+        instruction::Deposit {
+            amount: cmd.amount,
+            referrer: cmd.referrer,
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct SetPlayerLimits {
+    /// Lamports per day, 0 = no cap
+    #[arg(long, default_value_t = 0)]
+    daily_cap: u64,
+    /// Lamports per week, 0 = no cap
+    #[arg(long, default_value_t = 0)]
+    weekly_cap: u64,
+    /// Lamports per round, 0 = no cap
+    #[arg(long, default_value_t = 0)]
+    round_cap: u64,
+    /// Unix time until which deposits are refused, 0 = none
+    #[arg(long, default_value_t = 0)]
+    excluded_until: i64,
+}
+
+pub fn set_player_limits(ctx: &Ctx, cmd: SetPlayerLimits) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::SetPlayerLimits {
+            player: signer.pubkey(),
+            player_limits: pda::player_limits(&signer.pubkey()),
+            system_program: system_program::ID,
+        },
+        instruction::SetPlayerLimits {
+            daily_cap: cmd.daily_cap,
+            weekly_cap: cmd.weekly_cap,
+            round_cap: cmd.round_cap,
+            excluded_until: cmd.excluded_until,
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct RecordQuestion {
+    round_id: u64,
+    /// Player who asked the question
+    #[arg(long, value_parser = args::pubkey)]
+    asker: Pubkey,
+    #[arg(long, value_parser = args::hash)]
+    question_hash: [u8; 32],
+    #[arg(long, value_parser = args::hash)]
+    answer_hash: [u8; 32],
+    #[arg(long)]
+    public: bool,
+    /// The question was answered YES (makes the asker evidence-eligible)
+    #[arg(long)]
+    yes: bool,
+}
+
+pub fn record_question(ctx: &Ctx, cmd: RecordQuestion) -> Result<()> {
+    let signer = ctx.signer()?;
+    let question = pda::question(cmd.round_id, &cmd.asker, &cmd.question_hash);
+    println!("Question PDA {}", question);
+    let ix = tx::instruction(
+        accounts::RecordQuestion {
+            authority: signer.pubkey(),
+            game_state: pda::game_state(),
+            roles: pda::roles(),
+            round: pda::round(cmd.round_id),
+            asker: cmd.asker,
+            question,
+            system_program: system_program::ID,
+        },
+        instruction::RecordQuestion {
+            question_hash: cmd.question_hash,
+            is_public: cmd.public,
+            answer_hash: cmd.answer_hash,
+            is_yes: cmd.yes,
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct RecordGuess {
+    round_id: u64,
+    /// Player who guessed
+    #[arg(long, value_parser = args::pubkey)]
+    player: Pubkey,
+    /// The guess; only its SHA-256 goes on chain
+    #[arg(long)]
+    guess: String,
+}
+
+pub fn record_guess(ctx: &Ctx, cmd: RecordGuess) -> Result<()> {
+    let signer = ctx.signer()?;
+    let guess = pda::guess(cmd.round_id, &cmd.player);
+    println!("Guess PDA {}", guess);
+    let ix = tx::instruction(
+        accounts::RecordGuess {
+            authority: signer.pubkey(),
+            game_state: pda::game_state(),
+            roles: pda::roles(),
+            round: pda::round(cmd.round_id),
+            player: cmd.player,
+            guess,
+            system_program: system_program::ID,
+        },
+        instruction::RecordGuess {
+            guess_hash: answer_hash(&cmd.guess),
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct Settle {
+    round_id: u64,
+    #[arg(long)]
+    answer: String,
+    #[arg(long)]
+    salt: String,
+    /// Winner wallet, repeated per winner
+    #[arg(long = "winner", value_parser = args::pubkey, required = true)]
+    winners: Vec<Pubkey>,
+    /// `<wallet>:<bps>:<question PDA>`, repeated per evidence wallet
+    #[arg(long = "evidence", value_parser = args::evidence)]
+    evidence: Vec<Evidence>,
+    /// equal-split, earliest-guess to pay the winners whose Guess landed
+    /// first, or largest-deposit to split pro rata to the winners' deposits
+    #[arg(long, value_parser = args::tie_break, default_value = "equal-split")]
+    tie_break: TieBreakPolicy,
+    /// Also update the winners' and evidence wallets' PlayerProfiles
+    #[arg(long)]
+    profiles: bool,
+}
+
+pub fn settle(ctx: &Ctx, cmd: Settle) -> Result<()> {
+    let signer = ctx.signer()?;
+    let gs = ctx.game_state()?;
+    let round = active_round(ctx, cmd.round_id)?;
+    check_reveal(&round, &cmd.answer, &cmd.salt)?;
+
+    // LargestDeposit splits by the winners' Deposits and EarliestGuess by
+    // their Guesses, which settle checks
+    let per_winner_keys: Vec<Pubkey> = match cmd.tie_break {
+        TieBreakPolicy::EqualSplit => Vec::new(),
+        TieBreakPolicy::LargestDeposit => cmd.winners.iter().map(|w| pda::deposit(cmd.round_id, w)).collect(),
+        TieBreakPolicy::EarliestGuess => cmd.winners.iter().map(|w| pda::guess(cmd.round_id, w)).collect(),
+    };
+    let per_winner = per_winner_keys
+        .iter()
+        .map(|key| match cmd.tie_break {
+            TieBreakPolicy::EarliestGuess => Ok(ctx.rpc.require_account::<Guess>(key, "Guess")?.slot),
+            _ => Ok(ctx.rpc.require_account::<alons_box::state::Deposit>(key, "Deposit")?.amount),
+        })
+        .collect::<Result<Vec<u64>>>()?;
+    let evidence_weights: Vec<u16> = cmd.evidence.iter().map(|e| e.bps).collect();
+    // The round records its season at creation, and settle requires it
+    let season_key = pda::round_season(round.season_id);
+    let season = match &season_key {
+        Some(key) => Some(ctx.rpc.require_account::<Season>(key, "Season")?),
+        None => None,
+    };
+    let preview = preview_settle(
+        &gs,
+        &Stakes::from(&round),
+        cmd.winners.len(),
+        cmd.tie_break,
+        &per_winner,
+        &evidence_weights,
+        season.as_ref().map(|s| s.season_bps),
+    )?;
+
+    println!("Settle round {} — pool {} SOL", round.round_id, sol(preview.pool));
+    for (wallet, amount) in cmd.winners.iter().zip(&preview.winner_amounts) {
+        println!("  winner {}  {:>20} SOL", wallet, sol(*amount));
+    }
+    for (evidence, amount) in cmd.evidence.iter().zip(&preview.evidence_amounts) {
+        println!("  evidence {}  {:>18} SOL", evidence.wallet, sol(*amount));
+    }
+    println!(
+        "  treasury           {:>20} SOL (referrers {} SOL)",
+        sol(preview.treasury_amount),
+        sol(preview.referral_amount)
+    );
+    if season.is_some() {
+        println!("  season prize pool  {:>20} SOL", sol(preview.season_amount));
+    }
+    print_rollover(&preview.rollover);
+
+    let mut ix = tx::instruction(
+        accounts::Settle {
+            authority: signer.pubkey(),
+            game_state: pda::game_state(),
+            roles: pda::roles(),
+            round: pda::round(cmd.round_id),
+            vault: pda::vault(),
+            settlement: pda::settlement(cmd.round_id),
+            treasury: gs.treasury,
+            treasury_stream: pda::treasury_stream(),
+            buyback_wallet: gs.buyback_wallet,
+            jackpot_pool: pda::jackpot_pool(),
+            season: season_key,
+            system_program: system_program::ID,
+        },
+        instruction::Settle {
+            answer: cmd.answer,
+            salt: cmd.salt,
+            winner_count: cmd.winners.len() as u8,
+            evidence_weights,
+            tie_break: cmd.tie_break,
+        },
+    );
+    ix.accounts
+        .extend(cmd.winners.iter().map(|wallet| AccountMeta::new(*wallet, false)));
+    ix.accounts
+        .extend(per_winner_keys.iter().map(|key| AccountMeta::new_readonly(*key, false)));
+    for evidence in &cmd.evidence {
+        ix.accounts.push(AccountMeta::new(evidence.wallet, false));
+        ix.accounts.push(AccountMeta::new_readonly(evidence.question, false));
+    }
+    if cmd.profiles {
+        let wallets = cmd
+            .winners
+            .iter()
+            .chain(cmd.evidence.iter().map(|e| &e.wallet));
+        ix.accounts
+            .extend(wallets.map(|w| AccountMeta::new(pda::player_profile(w), false)));
+    }
+    ctx.send(&signer, &[ix])
+}
+
+fn print_expire_preview(ctx: &Ctx, round: &Round) -> Result<GameState> {
+    let gs = ctx.game_state()?;
+    let preview = preview_expire(&gs, &Stakes::from(round))?;
+    println!("Expire round {} — deposits {} SOL", round.round_id, sol(preview.total_deposits));
+    println!("  buyback            {:>20} SOL", sol(preview.buyback_amount));
+    println!(
+        "  treasury           {:>20} SOL (referrers {} SOL)",
+        sol(preview.treasury_amount),
+        sol(preview.referral_amount)
+    );
+    println!("  rollover in        {:>20} SOL", sol(preview.rollover_in));
+    print_rollover(&preview.rollover);
+    Ok(gs)
+}
+
+#[derive(Args)]
+pub struct Expire {
+    round_id: u64,
+    #[arg(long)]
+    answer: String,
+    #[arg(long)]
+    salt: String,
+}
+
+pub fn expire(ctx: &Ctx, cmd: Expire) -> Result<()> {
+    let signer = ctx.signer()?;
+    let round = active_round(ctx, cmd.round_id)?;
+    check_reveal(&round, &cmd.answer, &cmd.salt)?;
+    let gs = print_expire_preview(ctx, &round)?;
+
+    let ix = tx::instruction(
+        accounts::Expire {
+            authority: signer.pubkey(),
+            game_state: pda::game_state(),
+            roles: pda::roles(),
+            round: pda::round(cmd.round_id),
+            vault: pda::vault(),
+            settlement: pda::settlement(cmd.round_id),
+            treasury: gs.treasury,
+            treasury_stream: pda::treasury_stream(),
+            buyback_wallet: gs.buyback_wallet,
+            jackpot_pool: pda::jackpot_pool(),
+            season: pda::round_season(round.season_id),
+            system_program: system_program::ID,
+        },
+        instruction::Expire {
+            answer: cmd.answer,
+            salt: cmd.salt,
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+pub fn emergency_expire(ctx: &Ctx, cmd: RoundId) -> Result<()> {
+    let signer = ctx.signer()?;
+    let round = active_round(ctx, cmd.round_id)?;
+    let gs = print_expire_preview(ctx, &round)?;
+
+    let ix = tx::instruction(
+        accounts::EmergencyExpire {
+            caller: signer.pubkey(),
+            game_state: pda::game_state(),
+            round: pda::round(cmd.round_id),
+            vault: pda::vault(),
+            settlement: pda::settlement(cmd.round_id),
+            treasury: gs.treasury,
+            treasury_stream: pda::treasury_stream(),
+            buyback_wallet: gs.buyback_wallet,
+            jackpot_pool: pda::jackpot_pool(),
+            season: pda::round_season(round.season_id),
+            system_program: system_program::ID,
+        },
+        instruction::EmergencyExpire {},
+    );
+    ctx.send(&signer, &[ix])
+}
+
+pub fn fail_round(ctx: &Ctx, cmd: RoundId) -> Result<()> {
+    let signer = ctx.signer()?;
+    let round = active_round(ctx, cmd.round_id)?;
+    let ix = tx::instruction(
+        accounts::FailRound {
+            caller: signer.pubkey(),
+            game_state: pda::game_state(),
+            round: pda::round(cmd.round_id),
+            settlement: pda::settlement(cmd.round_id),
+            season: pda::round_season(round.season_id),
+            system_program: system_program::ID,
+        },
+        instruction::FailRound {},
+    );
+    ctx.send(&signer, &[ix])
+}
+
+pub fn claim_refund(ctx: &Ctx, cmd: RoundId) -> Result<()> {
+    let signer = ctx.signer()?;
+    let player = signer.pubkey();
+    let ix = tx::instruction(
+        accounts::ClaimRefund {
+            player,
+            round: pda::round(cmd.round_id),
+            deposit: pda::deposit(cmd.round_id, &player),
+            vault: pda::vault(),
+            player_profile: pda::player_profile(&player),
+        },
+        instruction::ClaimRefund {},
+    );
+    ctx.send(&signer, &[ix])
+}
+
+pub fn close_deposit(ctx: &Ctx, cmd: DepositRef) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::CloseDeposit {
+            authority: signer.pubkey(),
+            game_state: pda::game_state(),
+            roles: pda::roles(),
+            round: pda::round(cmd.round_id),
+            deposit: pda::deposit(cmd.round_id, &cmd.player),
+        },
+        instruction::CloseDeposit {},
+    );
+    ctx.send(&signer, &[ix])
+}
+
+pub fn close_question(ctx: &Ctx, cmd: QuestionRef) -> Result<()> {
+    let signer = ctx.signer()?;
+    let question: Question = ctx.rpc.require_account(&cmd.question, "Question")?;
+    let ix = tx::instruction(
+        accounts::CloseQuestion {
+            authority: signer.pubkey(),
+            game_state: pda::game_state(),
+            roles: pda::roles(),
+            round: pda::round(question.round_id),
+            question: cmd.question,
+        },
+        instruction::CloseQuestion {},
+    );
+    ctx.send(&signer, &[ix])
+}
+
+pub fn close_guess(ctx: &Ctx, cmd: DepositRef) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::CloseGuess {
+            authority: signer.pubkey(),
+            game_state: pda::game_state(),
+            roles: pda::roles(),
+            round: pda::round(cmd.round_id),
+            guess: pda::guess(cmd.round_id, &cmd.player),
+        },
+        instruction::CloseGuess {},
+    );
+    ctx.send(&signer, &[ix])
+}
+
+pub fn close_round(ctx: &Ctx, cmd: RoundId) -> Result<()> {
+    let signer = ctx.signer()?;
+    let settlement: Settlement = ctx
+        .rpc
+        .require_account(&pda::settlement(cmd.round_id), "Settlement")?;
+    let ix = tx::instruction(
+        accounts::CloseRound {
+            authority: signer.pubkey(),
+            game_state: pda::game_state(),
+            roles: pda::roles(),
+            round: pda::round(cmd.round_id),
+            settlement: pda::settlement(cmd.round_id),
+            rent_payer: settlement.rent_payer,
+            archive: pda::round_archive(),
+            system_program: system_program::ID,
+        },
+        instruction::CloseRound {},
+    );
+    ctx.send(&signer, &[ix])
+}
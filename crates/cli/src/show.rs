@@ -0,0 +1,169 @@
+//! `show` — read-only views of GameState, Round and Deposit accounts.
+
+use alons_box::state::*;
+use alons_box_client::commit::to_hex;
+use alons_box_client::{pda, sol};
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use clap::Subcommand;
+
+use crate::args;
+use crate::Ctx;
+
+#[derive(Subcommand)]
+pub enum Show {
+    /// Global config, payout split and balances
+    GameState,
+    /// One round
+    Round { round_id: u64 },
+    /// One player's deposit in a round
+    Deposit {
+        round_id: u64,
+        #[arg(value_parser = args::pubkey)]
+        player: Pubkey,
+    },
+    /// Every deposit in a round that is still open on-chain
+    Deposits { round_id: u64 },
+}
+
+pub fn run(ctx: &Ctx, cmd: Show) -> Result<()> {
+    match cmd {
+        Show::GameState => {
+            let gs = ctx.game_state()?;
+            let vault = ctx.rpc.get_account(&pda::vault())?.map(|a| a.lamports).unwrap_or(0);
+            print_game_state(&gs, vault);
+        }
+        Show::Round { round_id } => {
+            let round: Round = ctx.rpc.require_account(&pda::round(round_id), "Round")?;
+            print_round(&round);
+        }
+        Show::Deposit { round_id, player } => {
+            let deposit: Deposit = ctx
+                .rpc
+                .require_account(&pda::deposit(round_id, &player), "Deposit")?;
+            print_deposit(&deposit);
+        }
+        Show::Deposits { round_id } => {
+            let mut deposits = ctx
+                .rpc
+                .program_accounts::<Deposit>(&[(8, &round_id.to_le_bytes())])?;
+            deposits.sort_by_key(|(_, d)| std::cmp::Reverse(d.amount));
+            let total: u64 = deposits.iter().map(|(_, d)| d.amount).sum();
+            for (_, deposit) in &deposits {
+                println!("{}  {:>20}", deposit.user, sol(deposit.amount));
+            }
+            println!("{} deposits, {} SOL", deposits.len(), sol(total));
+        }
+    }
+    Ok(())
+}
+
+pub fn status_name(status: RoundStatus) -> &'static str {
+    match status {
+        RoundStatus::Active => "Active",
+        RoundStatus::Settled => "Settled",
+        RoundStatus::Expired => "Expired",
+        RoundStatus::Refunding => "Refunding",
+    }
+}
+
+fn policy_name(policy: OverflowPolicy) -> &'static str {
+    match policy {
+        OverflowPolicy::Jackpot => "jackpot",
+        OverflowPolicy::Buyback => "buyback",
+    }
+}
+
+fn print_game_state(gs: &GameState, vault_lamports: u64) {
+    let payout = gs.payout;
+    println!("GameState            {}", pda::game_state());
+    println!("  authority          {}", gs.authority);
+    println!("  treasury           {}", gs.treasury);
+    println!("  buyback_wallet     {}", gs.buyback_wallet);
+    println!("  guardian           {}", gs.guardian);
+    println!("  current_round_id   {}", gs.current_round_id);
+    println!("  paused             {:#05b}", gs.paused);
+    println!("  rollover_balance   {} SOL", sol(gs.rollover_balance));
+    println!("  rollover_cap       {} SOL ({})", sol(gs.rollover_cap), policy_name(gs.overflow_policy));
+    println!("  timelock_delay     {}s", gs.timelock_delay);
+    println!("  change_count       {}", gs.change_count);
+    println!(
+        "  payout             winner {} / evidence {} / treasury {} bps; expire buyback {} / treasury {} bps",
+        payout.winner_bps,
+        payout.evidence_bps,
+        payout.treasury_bps,
+        payout.expire_buyback_bps,
+        payout.expire_treasury_bps,
+    );
+    println!("  referral_bps       {}", gs.referral_bps);
+    println!("  referral_liability {} SOL", sol(gs.referral_liability));
+    println!("  swap_program       {}", gs.swap_program);
+    if gs.season_id != 0 {
+        println!(
+            "  season             {} (rounds {}..={}, {} bps)",
+            gs.season_id, gs.season_start_round_id, gs.season_end_round_id, gs.season_bps
+        );
+    }
+    println!("Vault                {} SOL", sol(vault_lamports));
+}
+
+fn print_round(round: &Round) {
+    println!("Round {}             {}", round.round_id, pda::round(round.round_id));
+    println!("  status             {}", status_name(round.status));
+    println!("  commit_hash        {}", to_hex(&round.commit_hash));
+    println!("  authority          {}", round.authority);
+    println!("  ends_at            {} (originally {})", round.ends_at, round.original_ends_at);
+    println!("  total_deposits     {} SOL", sol(round.total_deposits));
+    println!("  rollover_in        {} SOL (jackpot {} SOL)", sol(round.rollover_in), sol(round.jackpot_in));
+    println!("  min_pool           {} SOL", sol(round.min_pool));
+    if round.season_id != 0 {
+        println!("  season             {}", round.season_id);
+    }
+    if round.soft_close != SoftClose::default() {
+        let sc = round.soft_close;
+        println!(
+            "  soft_close         window {}s, +{}s, until {}",
+            sc.window_secs, sc.extension_secs, sc.max_ends_at
+        );
+    }
+    if round.deposit_policy != DepositPolicy::default() {
+        let dp = round.deposit_policy;
+        println!(
+            "  deposit_policy     min {} / max {} / per player {} / pool {} SOL",
+            sol(dp.min_deposit),
+            sol(dp.max_deposit),
+            sol(dp.max_per_player),
+            sol(dp.max_pool)
+        );
+    }
+    if round.status == RoundStatus::Refunding {
+        println!("  refunded           {} SOL", sol(round.refunded));
+    }
+    if round.referred_deposits > 0 {
+        println!(
+            "  referrals          {} SOL referred, {} / {} SOL credited",
+            sol(round.referred_deposits),
+            sol(round.referral_credited),
+            sol(round.referral_reserve)
+        );
+    }
+    if !round.revealed_answer.is_empty() {
+        println!("  revealed           {}:{}", round.revealed_answer, round.revealed_salt);
+    }
+}
+
+fn print_deposit(deposit: &Deposit) {
+    println!(
+        "Deposit              {}",
+        pda::deposit(deposit.round_id, &deposit.user)
+    );
+    println!("  round_id           {}", deposit.round_id);
+    println!("  user               {}", deposit.user);
+    println!("  amount             {} SOL", sol(deposit.amount));
+    if deposit.referrer != Pubkey::default() {
+        println!(
+            "  referrer           {} (credited: {})",
+            deposit.referrer, deposit.referral_credited
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! Treasury side: buyback, revenue split, vesting stream, seasons and
+//! referrals.
+
+use alons_box::state::*;
+use alons_box::{accounts, instruction};
+use alons_box_client::{pda, sol, tx};
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::system_program;
+use anyhow::{bail, Result};
+use clap::Args;
+use solana_signer::Signer;
+
+use crate::args;
+use crate::round::DepositRef;
+use crate::Ctx;
+
+#[derive(Args)]
+pub struct InitBuyback {
+    /// What happens to bought tokens: burn or lock
+    #[arg(long, value_parser = args::buyback_mode)]
+    mode: BuybackMode,
+    #[arg(long)]
+    max_slippage_bps: u16,
+    /// Lamports spent per execute_buyback, 0 = whole balance
+    #[arg(long, default_value_t = 0)]
+    max_sol_per_buyback: u64,
+    #[arg(long, value_parser = args::pubkey)]
+    token_mint: Pubkey,
+    #[arg(long, value_parser = args::pubkey)]
+    swap_program: Pubkey,
+    #[arg(long, value_parser = args::pubkey)]
+    pool: Pubkey,
+    #[arg(long, value_parser = args::pubkey)]
+    pool_token_vault: Pubkey,
+}
+
+pub fn init_buyback(ctx: &Ctx, cmd: InitBuyback) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::InitBuyback {
+            admin: signer.pubkey(),
+            roles: pda::roles(),
+            game_state: pda::game_state(),
+            buyback: pda::buyback(),
+            buyback_vault: pda::buyback_vault(),
+            buyback_tokens: pda::buyback_tokens(),
+            token_mint: cmd.token_mint,
+            swap_program: cmd.swap_program,
+            pool: cmd.pool,
+            pool_token_vault: cmd.pool_token_vault,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitBuyback {
+            mode: cmd.mode,
+            max_slippage_bps: cmd.max_slippage_bps,
+            max_sol_per_buyback: cmd.max_sol_per_buyback,
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct ExecuteBuyback {
+    /// Fewest tokens to accept, quoted off-chain (e.g. from a TWAP)
+    #[arg(long)]
+    min_tokens_out: u64,
+}
+
+pub fn execute_buyback(ctx: &Ctx, cmd: ExecuteBuyback) -> Result<()> {
+    let signer = ctx.signer()?;
+    let buyback: Buyback = ctx.rpc.require_account(&pda::buyback(), "Buyback")?;
+    let ix = tx::instruction(
+        accounts::ExecuteBuyback {
+            executor: signer.pubkey(),
+            buyback: pda::buyback(),
+            buyback_vault: pda::buyback_vault(),
+            buyback_tokens: pda::buyback_tokens(),
+            token_mint: buyback.token_mint,
+            swap_program: buyback.swap_program,
+            pool: buyback.pool,
+            pool_token_vault: buyback.pool_token_vault,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ExecuteBuyback {
+            min_tokens_out: cmd.min_tokens_out,
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct SetRevenueSplit {
+    /// `<pubkey>:<bps>`, repeated once per recipient
+    #[arg(long = "recipient", value_parser = args::recipient, required = true)]
+    recipients: Vec<SplitRecipient>,
+}
+
+pub fn set_revenue_split(ctx: &Ctx, cmd: SetRevenueSplit) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::SetRevenueSplit {
+            admin: signer.pubkey(),
+            game_state: pda::game_state(),
+            roles: pda::roles(),
+            revenue_split: pda::revenue_split(),
+            treasury_pool: pda::treasury_pool(),
+            system_program: system_program::ID,
+        },
+        instruction::SetRevenueSplit {
+            recipients: cmd.recipients,
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+pub fn distribute_treasury(ctx: &Ctx) -> Result<()> {
+    let signer = ctx.signer()?;
+    let mut split: RevenueSplit = ctx
+        .rpc
+        .require_account(&pda::revenue_split(), "RevenueSplit")?;
+    // The handler promotes a matured pending list before paying
+    split.apply_pending(ctx.rpc.cluster_time()?);
+
+    let mut ix = tx::instruction(
+        accounts::DistributeTreasury {
+            executor: signer.pubkey(),
+            revenue_split: pda::revenue_split(),
+            treasury_pool: pda::treasury_pool(),
+        },
+        instruction::DistributeTreasury {},
+    );
+    ix.accounts
+        .extend(split.recipients.iter().map(|r| AccountMeta::new(r.key, false)));
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct InitTreasuryStream {
+    /// Seconds over which each treasury cut vests
+    vesting_period: i64,
+}
+
+pub fn init_treasury_stream(ctx: &Ctx, cmd: InitTreasuryStream) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::InitTreasuryStream {
+            admin: signer.pubkey(),
+            roles: pda::roles(),
+            treasury_stream: pda::treasury_stream(),
+            system_program: system_program::ID,
+        },
+        instruction::InitTreasuryStream {
+            vesting_period: cmd.vesting_period,
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+pub fn withdraw_vested(ctx: &Ctx) -> Result<()> {
+    let signer = ctx.signer()?;
+    let gs = ctx.game_state()?;
+    let ix = tx::instruction(
+        accounts::WithdrawVested {
+            caller: signer.pubkey(),
+            game_state: pda::game_state(),
+            treasury_stream: pda::treasury_stream(),
+            vault: pda::vault(),
+            treasury: gs.treasury,
+        },
+        instruction::WithdrawVested {},
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct CreateSeason {
+    season_id: u64,
+    #[arg(long)]
+    start_round_id: u64,
+    #[arg(long)]
+    end_round_id: u64,
+    /// Share of each settled pool added to the prize pool
+    #[arg(long)]
+    season_bps: u16,
+    /// Share of the prize pool per rank, best first, e.g. 5000,3000,2000
+    #[arg(long, value_delimiter = ',', required = true)]
+    prize_curve: Vec<u16>,
+}
+
+pub fn create_season(ctx: &Ctx, cmd: CreateSeason) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::CreateSeason {
+            admin: signer.pubkey(),
+            game_state: pda::game_state(),
+            roles: pda::roles(),
+            season: pda::season(cmd.season_id),
+            system_program: system_program::ID,
+        },
+        instruction::CreateSeason {
+            season_id: cmd.season_id,
+            start_round_id: cmd.start_round_id,
+            end_round_id: cmd.end_round_id,
+            season_bps: cmd.season_bps,
+            prize_curve: cmd.prize_curve,
+        },
+    );
+    ctx.send(&signer, &[ix])
+}
+
+#[derive(Args)]
+pub struct SeasonId {
+    season_id: u64,
+}
+
+pub fn finalize_season(ctx: &Ctx, cmd: SeasonId) -> Result<()> {
+    let signer = ctx.signer()?;
+    let key = pda::season(cmd.season_id);
+    let season: Season = ctx.rpc.require_account(&key, "Season")?;
+    if season.finalized {
+        bail!("season {} is already finalized", cmd.season_id);
+    }
+    if season.open_rounds > 0 {
+        bail!("season {} still has {} open round(s)", cmd.season_id, season.open_rounds);
+    }
+
+    let prizes = season.prizes()?;
+    for (entry, prize) in season.leaderboard.iter().zip(&prizes) {
+        println!("  {}  {:>20} SOL", entry.player, sol(*prize));
+    }
+    println!(
+        "  to jackpot pool                                {:>20} SOL",
+        sol(season.prize_pool - prizes.iter().sum::<u64>())
+    );
+
+    let mut ix = tx::instruction(
+        accounts::FinalizeSeason {
+            caller: signer.pubkey(),
+            game_state: pda::game_state(),
+            season: key,
+            jackpot_pool: pda::jackpot_pool(),
+        },
+        instruction::FinalizeSeason {},
+    );
+    ix.accounts.extend(
+        season
+            .leaderboard
+            .iter()
+            .take(prizes.len())
+            .map(|e| AccountMeta::new(e.player, false)),
+    );
+    ctx.send(&signer, &[ix])
+}
+
+pub fn register_referrer(ctx: &Ctx) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::RegisterReferrer {
+            owner: signer.pubkey(),
+            referrer: pda::referrer(&signer.pubkey()),
+            system_program: system_program::ID,
+        },
+        instruction::RegisterReferrer {},
+    );
+    ctx.send(&signer, &[ix])
+}
+
+pub fn credit_referral(ctx: &Ctx, cmd: DepositRef) -> Result<()> {
+    let signer = ctx.signer()?;
+    let key = pda::deposit(cmd.round_id, &cmd.player);
+    let deposit: Deposit = ctx.rpc.require_account(&key, "Deposit")?;
+    if deposit.referrer == Pubkey::default() {
+        bail!("deposit {} has no referrer", key);
+    }
+    let ix = tx::instruction(
+        accounts::CreditReferral {
+            caller: signer.pubkey(),
+            round: pda::round(cmd.round_id),
+            deposit: key,
+            referrer: pda::referrer(&deposit.referrer),
+        },
+        instruction::CreditReferral {},
+    );
+    ctx.send(&signer, &[ix])
+}
+
+pub fn claim_referral(ctx: &Ctx) -> Result<()> {
+    let signer = ctx.signer()?;
+    let ix = tx::instruction(
+        accounts::ClaimReferral {
+            owner: signer.pubkey(),
+            game_state: pda::game_state(),
+            referrer: pda::referrer(&signer.pubkey()),
+            vault: pda::vault(),
+        },
+        instruction::ClaimReferral {},
+    );
+    ctx.send(&signer, &[ix])
+}
@@ -0,0 +1,70 @@
+//! Commit-reveal hashing, byte-for-byte what `settle` and `expire` check.
+
+use anchor_lang::solana_program::hash::hash;
+
+/// `SHA-256(answer:salt)`, the `commit_hash` passed to `create_round`.
+pub fn commit_hash(answer: &str, salt: &str) -> [u8; 32] {
+    hash(format!("{}:{}", answer, salt).as_bytes()).to_bytes()
+}
+
+/// `SHA-256(answer)`, the `answer_hash` stored in the Settlement PDA.
+pub fn answer_hash(answer: &str) -> [u8; 32] {
+    hash(answer.as_bytes()).to_bytes()
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Parse a 32-byte hash written as 64 hex characters.
+pub fn parse_hash(s: &str) -> anyhow::Result<[u8; 32]> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    anyhow::ensure!(s.len() == 64, "expected 64 hex characters, got {}", s.len());
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16)?;
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commit_hash_is_sha256_of_answer_colon_salt() {
+        assert_eq!(
+            to_hex(&commit_hash("swordfish", "pepper")),
+            "39837f6c4d251e5b69091e5a3907abbe812859735189231026f3e7e765c04f41"
+        );
+        assert_ne!(commit_hash("swordfish", "pepper"), commit_hash("swordfish:", "pepper"));
+    }
+
+    #[test]
+    fn answer_hash_is_sha256_of_the_answer() {
+        assert_eq!(
+            to_hex(&answer_hash("swordfish")),
+            "b9f195c5cc7ef6afadbfbc42892ad47d3b24c6bc94bb510c4564a90a14e8b799"
+        );
+        assert_eq!(
+            to_hex(&answer_hash("")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn parse_hash_round_trips_hex_with_or_without_prefix() {
+        let hash = commit_hash("a", "b");
+        let hex = to_hex(&hash);
+        assert_eq!(parse_hash(&hex).unwrap(), hash);
+        assert_eq!(parse_hash(&format!("0x{}", hex)).unwrap(), hash);
+        assert_eq!(parse_hash(&hex.to_uppercase()).unwrap(), hash);
+    }
+
+    #[test]
+    fn parse_hash_rejects_bad_length_and_digits() {
+        assert!(parse_hash("abcd").is_err());
+        assert!(parse_hash(&"0".repeat(66)).is_err());
+        assert!(parse_hash(&"zz".repeat(32)).is_err());
+    }
+}
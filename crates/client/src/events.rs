@@ -0,0 +1,202 @@
+//! Decode program events from transaction logs.
+//!
+//! `emit!` writes each event as a `Program data:` log line holding the
+//! event's discriminator and Borsh body. Lines are attributed to the program
+//! on top of the invoke stack, so data logged by other programs in the same
+//! transaction is ignored.
+
+use alons_box::events::*;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+/// Round lifecycle events. Anything else the program emits decodes to `None`.
+pub enum Event {
+    RoundCreated(RoundCreated),
+    JackpotReleased(JackpotReleased),
+    DepositMade(DepositMade),
+    RoundExtended(RoundExtended),
+    RoundSettled(RoundSettled),
+    RoundSettledV2(RoundSettledV2),
+    RoundExpired(RoundExpired),
+    EmergencyExpired(EmergencyExpired),
+    RoundFailed(RoundFailed),
+    RefundClaimed(RefundClaimed),
+    DepositClosed(DepositClosed),
+    RoundClosed(RoundClosed),
+}
+
+fn body<T: AnchorDeserialize + Discriminator>(data: &[u8]) -> Option<T> {
+    let rest = data.strip_prefix(T::DISCRIMINATOR)?;
+    T::deserialize(&mut &rest[..]).ok()
+}
+
+impl Event {
+    /// Decode one event payload (discriminator + body).
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let disc = data.get(..8)?;
+        macro_rules! try_events {
+            ($($name:ident),*) => {
+                $(if disc == $name::DISCRIMINATOR {
+                    return body::<$name>(data).map(Event::$name);
+                })*
+            };
+        }
+        try_events!(
+            RoundCreated,
+            JackpotReleased,
+            DepositMade,
+            RoundExtended,
+            RoundSettled,
+            RoundSettledV2,
+            RoundExpired,
+            EmergencyExpired,
+            RoundFailed,
+            RefundClaimed,
+            DepositClosed,
+            RoundClosed
+        );
+        None
+    }
+}
+
+/// Raw `Program data:` payloads logged by the alons_box program, in order.
+pub fn program_data(logs: &[String]) -> Vec<Vec<u8>> {
+    let program = alons_box::ID.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut out = Vec::new();
+
+    for line in logs {
+        if let Some(data) = line.strip_prefix("Program data: ") {
+            if stack.last() == Some(&program.as_str()) {
+                if let Ok(bytes) = BASE64.decode(data) {
+                    out.push(bytes);
+                }
+            }
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(id), Some("invoke")) => stack.push(id),
+                (Some(_), Some("success" | "failed:")) => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    out
+}
+
+/// Every event of type `T` in a transaction's logs, including those outside
+/// [`Event`], in emit order.
+pub fn find<T: AnchorDeserialize + Discriminator>(logs: &[String]) -> Vec<T> {
+    program_data(logs)
+        .iter()
+        .filter_map(|data| body::<T>(data))
+        .collect()
+}
+
+/// Every round lifecycle event in a transaction's logs, in emit order.
+pub fn parse_logs(logs: &[String]) -> Vec<Event> {
+    program_data(logs)
+        .iter()
+        .filter_map(|data| Event::decode(data))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::AnchorSerialize;
+
+    const OTHER: &str = "6DfCWrwNa6bkca2tZRGtzZ3AdVZQ9ktXVoeSuPRjn5D2";
+
+    fn data_line<T: AnchorSerialize + Discriminator>(event: &T) -> String {
+        let mut data = T::DISCRIMINATOR.to_vec();
+        event.serialize(&mut data).unwrap();
+        format!("Program data: {}", BASE64.encode(data))
+    }
+
+    fn created(round_id: u64) -> RoundCreated {
+        RoundCreated { round_id, ends_at: 1_700_000_000, rollover_in: 5 }
+    }
+
+    #[test]
+    fn top_level_events_decode_in_emit_order() {
+        let program = alons_box::ID.to_string();
+        let logs = vec![
+            format!("Program {} invoke [1]", program),
+            "Program log: Instruction: CreateRound".to_string(),
+            data_line(&created(7)),
+            data_line(&DepositMade { round_id: 7, player: Pubkey::new_unique(), amount: 10, total_deposits: 10 }),
+            format!("Program {} consumed 5000 of 200000 compute units", program),
+            format!("Program {} success", program),
+        ];
+
+        let events = parse_logs(&logs);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], Event::RoundCreated(e) if e.round_id == 7));
+        assert!(matches!(&events[1], Event::DepositMade(e) if e.amount == 10));
+    }
+
+    #[test]
+    fn data_from_an_inner_cpi_to_another_program_is_skipped() {
+        let program = alons_box::ID.to_string();
+        let logs = vec![
+            format!("Program {} invoke [1]", program),
+            format!("Program {} invoke [2]", OTHER),
+            // Same bytes, but logged by the swap program
+            data_line(&created(1)),
+            format!("Program {} success", OTHER),
+            data_line(&created(2)),
+            format!("Program {} invoke [2]", OTHER),
+            format!("Program {} failed: custom program error: 0x1", OTHER),
+            data_line(&created(3)),
+            format!("Program {} success", program),
+        ];
+
+        let ids: Vec<u64> = find::<RoundCreated>(&logs).iter().map(|e| e.round_id).collect();
+        assert_eq!(ids, vec![2, 3]);
+    }
+
+    #[test]
+    fn events_emitted_under_an_outer_program_are_kept() {
+        let program = alons_box::ID.to_string();
+        let logs = vec![
+            format!("Program {} invoke [1]", OTHER),
+            data_line(&created(1)),
+            format!("Program {} invoke [2]", program),
+            data_line(&created(2)),
+            format!("Program {} success", program),
+            data_line(&created(3)),
+            format!("Program {} success", OTHER),
+        ];
+
+        let ids: Vec<u64> = find::<RoundCreated>(&logs).iter().map(|e| e.round_id).collect();
+        assert_eq!(ids, vec![2]);
+    }
+
+    #[test]
+    fn events_outside_the_lifecycle_set_only_decode_through_find() {
+        let program = alons_box::ID.to_string();
+        let distributed = TreasuryDistributed {
+            recipients: vec![Pubkey::new_unique()],
+            amounts: vec![90],
+            carried: 10,
+            executor: Pubkey::new_unique(),
+        };
+        let logs = vec![
+            format!("Program {} invoke [1]", program),
+            data_line(&distributed),
+            "Program data: not base64!".to_string(),
+            format!("Program {} success", program),
+        ];
+
+        assert!(parse_logs(&logs).is_empty());
+        let found = find::<TreasuryDistributed>(&logs);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].carried, 10);
+        assert!(find::<RoundCreated>(&logs).is_empty());
+    }
+}
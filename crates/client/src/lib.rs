@@ -0,0 +1,26 @@
+//! Off-chain client for the Alon's Box program.
+//!
+//! Shared by the operator tools in this workspace. Instructions and account
+//! types come straight from the `alons-box` crate, so anything built here
+//! matches what the program deserializes.
+
+pub mod commit;
+pub mod events;
+pub mod payout;
+pub mod pda;
+pub mod rpc;
+pub mod tx;
+
+pub use rpc::Rpc;
+
+/// Lamports per SOL, for display.
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Format lamports as SOL with nine decimals.
+pub fn sol(lamports: u64) -> String {
+    format!(
+        "{}.{:09}",
+        lamports / LAMPORTS_PER_SOL,
+        lamports % LAMPORTS_PER_SOL
+    )
+}
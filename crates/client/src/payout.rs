@@ -0,0 +1,306 @@
+//! Payout previews for `settle` and `expire`.
+//!
+//! Uses the program's own `bps_of`, `bps_shares` and `winner_shares`, and
+//! follows the handlers step by step, so a preview matches what the
+//! instruction would pay from the same GameState and Round.
+
+use alons_box::state::{GameState, OverflowPolicy, Round, TieBreakPolicy};
+use alons_box::utils;
+use anyhow::{anyhow, Result};
+
+fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    utils::bps_of(amount, bps).map_err(|e| anyhow!("{}", e))
+}
+
+fn bps_shares(amount: u64, weights_bps: &[u16]) -> Result<Vec<u64>> {
+    utils::bps_shares(amount, weights_bps).map_err(|e| anyhow!("{}", e))
+}
+
+fn winner_shares(amount: u64, winner_count: usize, tie_break: TieBreakPolicy, per_winner: &[u64]) -> Result<Vec<u64>> {
+    utils::winner_shares(amount, winner_count, tie_break, per_winner).map_err(|e| anyhow!("{}", e))
+}
+
+fn sub(a: u64, b: u64) -> Result<u64> {
+    a.checked_sub(b).ok_or_else(|| anyhow!("math overflow"))
+}
+
+/// Where rollover above `game_state.rollover_cap` would go.
+pub struct CappedRollover {
+    pub rollover_out: u64,
+    pub to_jackpot: u64,
+    pub to_buyback: u64,
+}
+
+/// Mirror of `route_rollover_overflow`.
+pub fn cap_rollover(game_state: &GameState, rollover: u64) -> CappedRollover {
+    let cap = game_state.rollover_cap;
+    if cap == 0 || rollover <= cap {
+        return CappedRollover { rollover_out: rollover, to_jackpot: 0, to_buyback: 0 };
+    }
+    let overflow = rollover - cap;
+    match game_state.overflow_policy {
+        OverflowPolicy::Jackpot => CappedRollover { rollover_out: cap, to_jackpot: overflow, to_buyback: 0 },
+        OverflowPolicy::Buyback => CappedRollover { rollover_out: cap, to_jackpot: 0, to_buyback: overflow },
+    }
+}
+
+/// The Round figures a payout is computed from.
+pub struct Stakes {
+    pub total_deposits: u64,
+    pub rollover_in: u64,
+    pub referred_deposits: u64,
+}
+
+impl From<&Round> for Stakes {
+    fn from(round: &Round) -> Self {
+        Self {
+            total_deposits: round.total_deposits,
+            rollover_in: round.rollover_in,
+            referred_deposits: round.referred_deposits,
+        }
+    }
+}
+
+/// Referral slice withheld from a treasury cut charged at `treasury_bps`.
+pub fn referral_reserve(game_state: &GameState, stakes: &Stakes, treasury_bps: u16) -> Result<u64> {
+    bps_of(bps_of(stakes.referred_deposits, treasury_bps)?, game_state.referral_bps)
+}
+
+pub struct SettlePreview {
+    pub pool: u64,
+    pub winner_amounts: Vec<u64>,
+    pub evidence_amounts: Vec<u64>,
+    /// Full treasury cut, including the referral slice
+    pub treasury_amount: u64,
+    /// Part of the treasury cut kept in the vault for referrers
+    pub referral_amount: u64,
+    pub season_amount: u64,
+    pub rollover: CappedRollover,
+}
+
+/// What `settle` would pay these winners and evidence weights.
+/// `per_winner` holds each winner's Deposit amount under `LargestDeposit`
+/// or Guess slot under `EarliestGuess`, and is ignored otherwise.
+/// `season_bps` is the passed Season's share, if any.
+pub fn preview_settle(
+    game_state: &GameState,
+    stakes: &Stakes,
+    winner_count: usize,
+    tie_break: TieBreakPolicy,
+    per_winner: &[u64],
+    evidence_weights: &[u16],
+    season_bps: Option<u16>,
+) -> Result<SettlePreview> {
+    let payout = game_state.payout;
+    let pool = stakes
+        .total_deposits
+        .checked_add(stakes.rollover_in)
+        .ok_or_else(|| anyhow!("math overflow"))?;
+
+    let winner_amounts = winner_shares(bps_of(pool, payout.winner_bps)?, winner_count, tie_break, per_winner)?;
+    let evidence_amounts = bps_shares(bps_of(pool, payout.evidence_bps)?, evidence_weights)?;
+    let treasury_amount = bps_of(pool, payout.treasury_bps)?;
+    let referral_amount = referral_reserve(game_state, stakes, payout.treasury_bps)?;
+    let season_amount = match season_bps {
+        Some(bps) => bps_of(pool, bps)?,
+        None => 0,
+    };
+
+    let rollover = sub(pool, winner_amounts.iter().sum())?;
+    let rollover = sub(rollover, evidence_amounts.iter().sum())?;
+    let rollover = sub(rollover, treasury_amount)?;
+    let rollover = sub(rollover, season_amount)?;
+
+    Ok(SettlePreview {
+        pool,
+        winner_amounts,
+        evidence_amounts,
+        treasury_amount,
+        referral_amount,
+        season_amount,
+        rollover: cap_rollover(game_state, rollover),
+    })
+}
+
+pub struct ExpirePreview {
+    pub total_deposits: u64,
+    pub rollover_in: u64,
+    pub buyback_amount: u64,
+    /// Full treasury cut, including the referral slice
+    pub treasury_amount: u64,
+    pub referral_amount: u64,
+    pub rollover: CappedRollover,
+}
+
+/// What `expire` and `emergency_expire` would pay.
+pub fn preview_expire(game_state: &GameState, stakes: &Stakes) -> Result<ExpirePreview> {
+    let payout = game_state.payout;
+    let buyback_amount = bps_of(stakes.total_deposits, payout.expire_buyback_bps)?;
+    let treasury_amount = bps_of(stakes.total_deposits, payout.expire_treasury_bps)?;
+    let referral_amount = referral_reserve(game_state, stakes, payout.expire_treasury_bps)?;
+    let rollover_added = sub(sub(stakes.total_deposits, buyback_amount)?, treasury_amount)?;
+    let rollover = stakes
+        .rollover_in
+        .checked_add(rollover_added)
+        .ok_or_else(|| anyhow!("math overflow"))?;
+
+    Ok(ExpirePreview {
+        total_deposits: stakes.total_deposits,
+        rollover_in: stakes.rollover_in,
+        buyback_amount,
+        treasury_amount,
+        referral_amount,
+        rollover: cap_rollover(game_state, rollover),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alons_box::state::PayoutConfig;
+    use anchor_lang::prelude::Pubkey;
+
+    const SOL: u64 = 1_000_000_000;
+
+    fn game_state(rollover_cap: u64, overflow_policy: OverflowPolicy, referral_bps: u16) -> GameState {
+        GameState {
+            authority: Pubkey::default(),
+            treasury: Pubkey::default(),
+            buyback_wallet: Pubkey::default(),
+            current_round_id: 0,
+            bump: 0,
+            rollover_balance: 0,
+            rollover_cap,
+            overflow_policy,
+            guardian: Pubkey::default(),
+            paused: 0,
+            timelock_delay: 0,
+            change_count: 0,
+            payout: PayoutConfig::default(),
+            referral_bps,
+            referral_liability: 0,
+            season_id: 0,
+            season_start_round_id: 0,
+            season_end_round_id: 0,
+            season_bps: 0,
+            swap_program: Pubkey::default(),
+        }
+    }
+
+    fn stakes(total_deposits: u64, rollover_in: u64, referred_deposits: u64) -> Stakes {
+        Stakes { total_deposits, rollover_in, referred_deposits }
+    }
+
+    #[test]
+    fn settle_splits_the_pool_and_rolls_over_the_rest() {
+        let gs = game_state(0, OverflowPolicy::Jackpot, 0);
+        let p = preview_settle(
+            &gs,
+            &stakes(SOL, SOL / 2, 0),
+            3,
+            TieBreakPolicy::EqualSplit,
+            &[],
+            &[6000, 3000],
+            Some(500),
+        )
+        .unwrap();
+
+        assert_eq!(p.pool, 1_500_000_000);
+        assert_eq!(p.winner_amounts, vec![250_000_000; 3]);
+        // 10% of the evidence pool is unweighted and rolls over
+        assert_eq!(p.evidence_amounts, vec![270_000_000, 135_000_000]);
+        assert_eq!(p.treasury_amount, 75_000_000);
+        assert_eq!(p.referral_amount, 0);
+        assert_eq!(p.season_amount, 75_000_000);
+        assert_eq!(p.rollover.rollover_out, 195_000_000);
+        assert_eq!(p.rollover.to_jackpot, 0);
+    }
+
+    #[test]
+    fn settle_gives_the_equal_split_remainder_to_the_first_winner() {
+        let gs = game_state(0, OverflowPolicy::Jackpot, 0);
+        let p = preview_settle(&gs, &stakes(1001, 0, 0), 3, TieBreakPolicy::EqualSplit, &[], &[], None).unwrap();
+
+        // 1001 * 50% = 500 rounds down; 500 / 3 = 166 rem 2
+        assert_eq!(p.winner_amounts, vec![168, 166, 166]);
+        assert_eq!(p.treasury_amount, 50);
+        assert_eq!(p.season_amount, 0);
+        assert_eq!(p.rollover.rollover_out, 1001 - 500 - 50);
+    }
+
+    #[test]
+    fn settle_largest_deposit_is_pro_rata_and_dust_rolls_over() {
+        let gs = game_state(0, OverflowPolicy::Jackpot, 0);
+        let p = preview_settle(&gs, &stakes(1000, 0, 0), 2, TieBreakPolicy::LargestDeposit, &[1, 2], &[], None)
+            .unwrap();
+
+        assert_eq!(p.winner_amounts, vec![166, 333]);
+        assert_eq!(p.rollover.rollover_out, 1000 - 499 - 50);
+        let missing_deposit =
+            preview_settle(&gs, &stakes(1000, 0, 0), 2, TieBreakPolicy::LargestDeposit, &[1], &[], None);
+        assert!(missing_deposit.is_err());
+    }
+
+    #[test]
+    fn settle_earliest_guess_splits_between_the_earliest_slot() {
+        let gs = game_state(0, OverflowPolicy::Jackpot, 0);
+        let p = preview_settle(&gs, &stakes(1002, 0, 0), 3, TieBreakPolicy::EarliestGuess, &[9, 7, 7], &[], None)
+            .unwrap();
+
+        assert_eq!(p.winner_amounts, vec![0, 251, 250]);
+        assert_eq!(p.rollover.rollover_out, 1002 - 501 - 50);
+        let missing_guess = preview_settle(&gs, &stakes(1002, 0, 0), 2, TieBreakPolicy::EarliestGuess, &[7], &[], None);
+        assert!(missing_guess.is_err());
+    }
+
+    #[test]
+    fn settle_caps_the_rollover_into_the_jackpot() {
+        let gs = game_state(100_000_000, OverflowPolicy::Jackpot, 0);
+        let p = preview_settle(
+            &gs,
+            &stakes(SOL, SOL / 2, 0),
+            3,
+            TieBreakPolicy::EqualSplit,
+            &[],
+            &[6000, 3000],
+            Some(500),
+        )
+        .unwrap();
+
+        assert_eq!(p.rollover.rollover_out, 100_000_000);
+        assert_eq!(p.rollover.to_jackpot, 95_000_000);
+        assert_eq!(p.rollover.to_buyback, 0);
+    }
+
+    #[test]
+    fn settle_rejects_evidence_weights_over_10000() {
+        let gs = game_state(0, OverflowPolicy::Jackpot, 0);
+        let over = preview_settle(&gs, &stakes(SOL, 0, 0), 1, TieBreakPolicy::EqualSplit, &[], &[6000, 4001], None);
+        assert!(over.is_err());
+    }
+
+    #[test]
+    fn expire_withholds_referrals_and_caps_into_the_buyback() {
+        let gs = game_state(500_000_000, OverflowPolicy::Buyback, 2000);
+        let p = preview_expire(&gs, &stakes(SOL, 200_000_000, 400_000_000)).unwrap();
+
+        assert_eq!(p.buyback_amount, 475_000_000);
+        assert_eq!(p.treasury_amount, 50_000_000);
+        // 20% of the 5% treasury cut on 0.4 SOL of referred deposits
+        assert_eq!(p.referral_amount, 4_000_000);
+        // 0.2 SOL in + 0.475 SOL of deposits left after the cuts, capped at 0.5 SOL
+        assert_eq!(p.rollover.rollover_out, 500_000_000);
+        assert_eq!(p.rollover.to_buyback, 175_000_000);
+        assert_eq!(p.rollover.to_jackpot, 0);
+    }
+
+    #[test]
+    fn expire_with_no_deposits_keeps_the_rollover() {
+        let gs = game_state(0, OverflowPolicy::Jackpot, 0);
+        let p = preview_expire(&gs, &stakes(0, 123, 0)).unwrap();
+
+        assert_eq!(p.buyback_amount, 0);
+        assert_eq!(p.treasury_amount, 0);
+        assert_eq!(p.rollover.rollover_out, 123);
+    }
+}
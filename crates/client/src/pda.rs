@@ -0,0 +1,186 @@
+//! Program-derived addresses, one function per seed layout in `state.rs`.
+
+use anchor_lang::prelude::Pubkey;
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &alons_box::ID).0
+}
+
+pub fn game_state() -> Pubkey {
+    find(&[b"game_state"])
+}
+
+pub fn roles() -> Pubkey {
+    find(&[b"roles"])
+}
+
+pub fn vault() -> Pubkey {
+    find(&[b"vault"])
+}
+
+pub fn jackpot_pool() -> Pubkey {
+    find(&[b"jackpot_pool"])
+}
+
+pub fn round_archive() -> Pubkey {
+    find(&[b"round_archive"])
+}
+
+pub fn round(round_id: u64) -> Pubkey {
+    find(&[b"round", &round_id.to_le_bytes()])
+}
+
+pub fn deposit(round_id: u64, player: &Pubkey) -> Pubkey {
+    find(&[b"deposit", &round_id.to_le_bytes(), player.as_ref()])
+}
+
+pub fn settlement(round_id: u64) -> Pubkey {
+    find(&[b"settlement", &round_id.to_le_bytes()])
+}
+
+pub fn question(round_id: u64, asker: &Pubkey, question_hash: &[u8; 32]) -> Pubkey {
+    find(&[b"question", &round_id.to_le_bytes(), asker.as_ref(), question_hash])
+}
+
+pub fn guess(round_id: u64, player: &Pubkey) -> Pubkey {
+    find(&[b"guess", &round_id.to_le_bytes(), player.as_ref()])
+}
+
+pub fn pending_change(change_id: u64) -> Pubkey {
+    find(&[b"pending_change", &change_id.to_le_bytes()])
+}
+
+pub fn governance() -> Pubkey {
+    find(&[b"governance"])
+}
+
+pub fn governance_vault() -> Pubkey {
+    find(&[b"governance_vault"])
+}
+
+pub fn vote_escrow(owner: &Pubkey) -> Pubkey {
+    find(&[b"vote_escrow", owner.as_ref()])
+}
+
+pub fn proposal(proposal_id: u64) -> Pubkey {
+    find(&[b"proposal", &proposal_id.to_le_bytes()])
+}
+
+pub fn vote_record(proposal_id: u64, voter: &Pubkey) -> Pubkey {
+    find(&[b"vote", &proposal_id.to_le_bytes(), voter.as_ref()])
+}
+
+pub fn buyback() -> Pubkey {
+    find(&[b"buyback"])
+}
+
+pub fn buyback_vault() -> Pubkey {
+    find(&[b"buyback_vault"])
+}
+
+pub fn buyback_tokens() -> Pubkey {
+    find(&[b"buyback_tokens"])
+}
+
+pub fn revenue_split() -> Pubkey {
+    find(&[b"revenue_split"])
+}
+
+pub fn treasury_pool() -> Pubkey {
+    find(&[b"treasury_pool"])
+}
+
+pub fn treasury_stream() -> Pubkey {
+    find(&[b"treasury_stream"])
+}
+
+pub fn season(season_id: u64) -> Pubkey {
+    find(&[b"season", &season_id.to_le_bytes()])
+}
+
+/// The Season a round is counted in, passed to the instructions that open
+/// and finish it (`season_id` 0 = none)
+pub fn round_season(season_id: u64) -> Option<Pubkey> {
+    (season_id != 0).then(|| season(season_id))
+}
+
+pub fn referrer(owner: &Pubkey) -> Pubkey {
+    find(&[b"referrer", owner.as_ref()])
+}
+
+pub fn player_referral(player: &Pubkey) -> Pubkey {
+    find(&[b"referral", player.as_ref()])
+}
+
+pub fn player_profile(player: &Pubkey) -> Pubkey {
+    find(&[b"player_profile", player.as_ref()])
+}
+
+pub fn player_limits(player: &Pubkey) -> Pubkey {
+    find(&[b"player_limits", player.as_ref()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_little_endian_seeds() {
+        let le = Pubkey::find_program_address(&[b"round", &[1, 0, 0, 0, 0, 0, 0, 0]], &alons_box::ID).0;
+        let be = Pubkey::find_program_address(&[b"round", &[0, 0, 0, 0, 0, 0, 0, 1]], &alons_box::ID).0;
+        assert_eq!(round(1), le);
+        assert_ne!(round(1), be);
+        assert_ne!(round(1), round(256));
+        assert_ne!(settlement(1), round(1));
+    }
+
+    #[test]
+    fn singletons_are_distinct_and_off_curve() {
+        let keys = [
+            game_state(),
+            roles(),
+            vault(),
+            jackpot_pool(),
+            round_archive(),
+            governance(),
+            governance_vault(),
+            buyback(),
+            buyback_vault(),
+            buyback_tokens(),
+            revenue_split(),
+            treasury_pool(),
+            treasury_stream(),
+        ];
+        for (i, key) in keys.iter().enumerate() {
+            assert!(!key.is_on_curve());
+            assert!(keys[..i].iter().all(|k| k != key));
+        }
+    }
+
+    #[test]
+    fn per_player_accounts_depend_on_player_and_round() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_ne!(deposit(1, &a), deposit(1, &b));
+        assert_ne!(deposit(1, &a), deposit(2, &a));
+        assert_ne!(vote_record(1, &a), vote_record(2, &a));
+        assert_ne!(guess(1, &a), deposit(1, &a));
+        assert_ne!(guess(1, &a), guess(2, &a));
+        // Same owner, different seed prefixes
+        let per_player = [
+            vote_escrow(&a),
+            referrer(&a),
+            player_referral(&a),
+            player_profile(&a),
+            player_limits(&a),
+        ];
+        for (i, key) in per_player.iter().enumerate() {
+            assert!(per_player[..i].iter().all(|k| k != key));
+        }
+    }
+
+    #[test]
+    fn question_depends_on_the_hash() {
+        let asker = Pubkey::new_unique();
+        assert_ne!(question(1, &asker, &[0; 32]), question(1, &asker, &[1; 32]));
+    }
+}
@@ -0,0 +1,434 @@
+//! Minimal Solana JSON-RPC client covering the calls the tools make.
+
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::Hash;
+use anchor_lang::AccountDeserialize;
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{json, Value};
+use solana_transaction::Transaction;
+
+/// Resolve a cluster alias (localnet, devnet, mainnet-beta, or their first
+/// letter) to its RPC URL; anything else is taken as a URL.
+pub fn cluster_url(url: &str) -> &str {
+    match url {
+        "l" | "localnet" | "localhost" => "http://127.0.0.1:8899",
+        "d" | "devnet" => "https://api.devnet.solana.com",
+        "m" | "mainnet-beta" => "https://api.mainnet-beta.solana.com",
+        url => url,
+    }
+}
+
+/// Commitment level for reads that let the caller choose.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Commitment {
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        }
+    }
+}
+
+impl FromStr for Commitment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "confirmed" => Ok(Commitment::Confirmed),
+            "finalized" => Ok(Commitment::Finalized),
+            _ => Err(format!("expected confirmed or finalized, got {}", s)),
+        }
+    }
+}
+
+/// One entry of `getSignaturesForAddress`.
+pub struct SignatureInfo {
+    pub signature: String,
+    pub slot: u64,
+    /// The transaction failed; its logs carry no committed events
+    pub failed: bool,
+    pub block_time: Option<i64>,
+}
+
+/// The parts of `getTransaction` the tools read.
+pub struct ConfirmedTransaction {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub failed: bool,
+    pub fee: u64,
+    /// Static keys, then lookup-table keys (writable, then readonly); the
+    /// balances and instruction accounts follow this order
+    pub account_keys: Vec<Pubkey>,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+    /// Top-level instructions only
+    pub instructions: Vec<TransactionInstruction>,
+    pub logs: Vec<String>,
+}
+
+pub struct TransactionInstruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<Pubkey>,
+    pub data: Vec<u8>,
+}
+
+impl ConfirmedTransaction {
+    /// Lamports `key` gained (negative if lost) in this transaction, `None`
+    /// if the transaction did not load it.
+    pub fn balance_change(&self, key: &Pubkey) -> Option<i64> {
+        let i = self.account_keys.iter().position(|k| k == key)?;
+        Some(*self.post_balances.get(i)? as i64 - *self.pre_balances.get(i)? as i64)
+    }
+}
+
+/// Raw account as returned by `getAccountInfo`.
+pub struct RpcAccount {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+/// Outcome of `simulateTransaction`.
+pub struct Simulation {
+    /// Transaction error as reported by the node, `None` on success
+    pub err: Option<Value>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+}
+
+pub struct Rpc {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl Rpc {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Send one JSON-RPC request and return its `result`.
+    pub fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(body)
+            .with_context(|| format!("{} request to {} failed", method, self.url))?
+            .into_json()?;
+        if let Some(err) = response.get("error") {
+            bail!("{} failed: {}", method, err);
+        }
+        response
+            .get("result")
+            .cloned()
+            .ok_or_else(|| anyhow!("{} returned no result", method))
+    }
+
+    pub fn get_account(&self, key: &Pubkey) -> Result<Option<RpcAccount>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([key.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        match &result["value"] {
+            Value::Null => Ok(None),
+            value => parse_account(value).map(Some),
+        }
+    }
+
+    /// Fetch and deserialize an Anchor account, `None` if it does not exist.
+    pub fn account<T: AccountDeserialize>(&self, key: &Pubkey) -> Result<Option<T>> {
+        let Some(account) = self.get_account(key)? else {
+            return Ok(None);
+        };
+        if account.owner != alons_box::ID || account.data.is_empty() {
+            return Ok(None);
+        }
+        let decoded = T::try_deserialize(&mut &account.data[..])
+            .map_err(|e| anyhow!("cannot decode {}: {}", key, e))?;
+        Ok(Some(decoded))
+    }
+
+    /// Like [`Rpc::account`], but a missing account is an error.
+    pub fn require_account<T: AccountDeserialize>(&self, key: &Pubkey, what: &str) -> Result<T> {
+        self.account(key)?
+            .ok_or_else(|| anyhow!("{} {} does not exist", what, key))
+    }
+
+    /// Program accounts of type `T`, selected by its discriminator plus any
+    /// extra `(offset, bytes)` memcmp filters. Accounts that do not decode as
+    /// `T` (e.g. left over from an older layout) are logged and skipped.
+    pub fn program_accounts<T: AccountDeserialize + anchor_lang::Discriminator>(
+        &self,
+        filters: &[(usize, &[u8])],
+    ) -> Result<Vec<(Pubkey, T)>> {
+        let mut memcmp = vec![json!({ "memcmp": { "offset": 0, "bytes": BASE64.encode(T::DISCRIMINATOR), "encoding": "base64" } })];
+        for (offset, bytes) in filters {
+            memcmp.push(json!({ "memcmp": { "offset": offset, "bytes": BASE64.encode(bytes), "encoding": "base64" } }));
+        }
+        let result = self.call(
+            "getProgramAccounts",
+            json!([alons_box::ID.to_string(), {
+                "encoding": "base64",
+                "commitment": "confirmed",
+                "filters": memcmp,
+            }]),
+        )?;
+        let entries = result.as_array().ok_or_else(|| anyhow!("getProgramAccounts: expected an array"))?;
+        let mut accounts = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let key = parse_pubkey(&entry["pubkey"])?;
+            let account = parse_account(&entry["account"])?;
+            match T::try_deserialize(&mut &account.data[..]) {
+                Ok(decoded) => accounts.push((key, decoded)),
+                Err(e) => eprintln!("skipping {}: cannot decode: {}", key, e),
+            }
+        }
+        Ok(accounts)
+    }
+
+    pub fn latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+        let hash = result["value"]["blockhash"]
+            .as_str()
+            .ok_or_else(|| anyhow!("getLatestBlockhash: no blockhash"))?;
+        Ok(Hash::from_str(hash)?)
+    }
+
+    pub fn slot(&self, commitment: Commitment) -> Result<u64> {
+        self.call("getSlot", json!([{ "commitment": commitment.as_str() }]))?
+            .as_u64()
+            .ok_or_else(|| anyhow!("getSlot: expected a number"))
+    }
+
+    /// Program signatures newest first, strictly older than `before` and
+    /// strictly newer than `until`.
+    pub fn program_signatures(
+        &self,
+        before: Option<&str>,
+        until: Option<&str>,
+        limit: usize,
+        commitment: Commitment,
+    ) -> Result<Vec<SignatureInfo>> {
+        self.signatures_for(&alons_box::ID, before, until, limit, commitment)
+    }
+
+    /// Signatures of transactions that loaded `address`, newest first, with
+    /// the same bounds as [`Rpc::program_signatures`].
+    pub fn signatures_for(
+        &self,
+        address: &Pubkey,
+        before: Option<&str>,
+        until: Option<&str>,
+        limit: usize,
+        commitment: Commitment,
+    ) -> Result<Vec<SignatureInfo>> {
+        let mut config = json!({ "limit": limit, "commitment": commitment.as_str() });
+        if let Some(before) = before {
+            config["before"] = json!(before);
+        }
+        if let Some(until) = until {
+            config["until"] = json!(until);
+        }
+        let result = self.call("getSignaturesForAddress", json!([address.to_string(), config]))?;
+        let entries = result
+            .as_array()
+            .ok_or_else(|| anyhow!("getSignaturesForAddress: expected an array"))?;
+        entries
+            .iter()
+            .map(|entry| {
+                Ok(SignatureInfo {
+                    signature: entry["signature"]
+                        .as_str()
+                        .ok_or_else(|| anyhow!("getSignaturesForAddress: no signature"))?
+                        .to_string(),
+                    slot: entry["slot"].as_u64().unwrap_or_default(),
+                    failed: !entry["err"].is_null(),
+                    block_time: entry["blockTime"].as_i64(),
+                })
+            })
+            .collect()
+    }
+
+    /// A landed transaction, `None` if the node does not have it at `commitment`.
+    pub fn transaction(&self, signature: &str, commitment: Commitment) -> Result<Option<ConfirmedTransaction>> {
+        let result = self.call(
+            "getTransaction",
+            json!([signature, {
+                "encoding": "json",
+                "commitment": commitment.as_str(),
+                "maxSupportedTransactionVersion": 0,
+            }]),
+        )?;
+        if result.is_null() {
+            return Ok(None);
+        }
+        let meta = &result["meta"];
+        let message = &result["transaction"]["message"];
+
+        let mut account_keys = Vec::new();
+        for key in [
+            &message["accountKeys"],
+            &meta["loadedAddresses"]["writable"],
+            &meta["loadedAddresses"]["readonly"],
+        ] {
+            for key in key.as_array().map(Vec::as_slice).unwrap_or_default() {
+                account_keys.push(parse_pubkey(key)?);
+            }
+        }
+
+        let mut instructions = Vec::new();
+        for ix in message["instructions"].as_array().map(Vec::as_slice).unwrap_or_default() {
+            let key = |index: &Value| {
+                index
+                    .as_u64()
+                    .and_then(|i| account_keys.get(i as usize).copied())
+                    .ok_or_else(|| anyhow!("getTransaction: bad account index {}", index))
+            };
+            let data = ix["data"].as_str().ok_or_else(|| anyhow!("getTransaction: no instruction data"))?;
+            instructions.push(TransactionInstruction {
+                program_id: key(&ix["programIdIndex"])?,
+                accounts: ix["accounts"]
+                    .as_array()
+                    .map(Vec::as_slice)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(key)
+                    .collect::<Result<_>>()?,
+                data: bs58::decode(data).into_vec()?,
+            });
+        }
+
+        let balances = |field: &str| -> Vec<u64> {
+            meta[field]
+                .as_array()
+                .map(|b| b.iter().filter_map(Value::as_u64).collect())
+                .unwrap_or_default()
+        };
+        Ok(Some(ConfirmedTransaction {
+            slot: result["slot"].as_u64().unwrap_or_default(),
+            block_time: result["blockTime"].as_i64(),
+            failed: !meta["err"].is_null(),
+            fee: meta["fee"].as_u64().unwrap_or_default(),
+            pre_balances: balances("preBalances"),
+            post_balances: balances("postBalances"),
+            account_keys,
+            instructions,
+            logs: meta["logMessages"]
+                .as_array()
+                .map(|logs| logs.iter().filter_map(|l| l.as_str().map(String::from)).collect())
+                .unwrap_or_default(),
+        }))
+    }
+
+    /// Unix time of the latest confirmed slot — the cluster's
+    /// `Clock::unix_timestamp`, within a slot or two.
+    pub fn cluster_time(&self) -> Result<i64> {
+        let slot = self.slot(Commitment::Confirmed)?;
+        self.call("getBlockTime", json!([slot]))?
+            .as_i64()
+            .ok_or_else(|| anyhow!("getBlockTime: no time for slot {}", slot))
+    }
+
+    /// Simulate a signed transaction against the latest bank.
+    pub fn simulate(&self, tx: &Transaction) -> Result<Simulation> {
+        let result = self.call(
+            "simulateTransaction",
+            json!([encode_tx(tx)?, {
+                "encoding": "base64",
+                "commitment": "confirmed",
+                "sigVerify": false,
+                "replaceRecentBlockhash": true,
+            }]),
+        )?;
+        let value = &result["value"];
+        Ok(Simulation {
+            err: match &value["err"] {
+                Value::Null => None,
+                err => Some(err.clone()),
+            },
+            logs: value["logs"]
+                .as_array()
+                .map(|logs| logs.iter().filter_map(|l| l.as_str().map(String::from)).collect())
+                .unwrap_or_default(),
+            units_consumed: value["unitsConsumed"].as_u64(),
+        })
+    }
+
+    /// Lamports the cluster would charge to land `tx`.
+    pub fn fee_for_message(&self, tx: &Transaction) -> Result<u64> {
+        let result = self.call(
+            "getFeeForMessage",
+            json!([BASE64.encode(tx.message_data()), { "commitment": "confirmed" }]),
+        )?;
+        result["value"]
+            .as_u64()
+            .ok_or_else(|| anyhow!("getFeeForMessage: blockhash expired before the fee was quoted"))
+    }
+
+    /// Send a signed transaction and wait until it is confirmed.
+    pub fn send_and_confirm(&self, tx: &Transaction) -> Result<String> {
+        let signature = self.call(
+            "sendTransaction",
+            json!([encode_tx(tx)?, { "encoding": "base64", "preflightCommitment": "confirmed" }]),
+        )?;
+        let signature = signature
+            .as_str()
+            .ok_or_else(|| anyhow!("sendTransaction: expected a signature"))?
+            .to_string();
+
+        let deadline = Instant::now() + Duration::from_secs(60);
+        while Instant::now() < deadline {
+            let result = self.call("getSignatureStatuses", json!([[signature]]))?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    bail!("transaction {} failed: {}", signature, status["err"]);
+                }
+                if matches!(status["confirmationStatus"].as_str(), Some("confirmed" | "finalized")) {
+                    return Ok(signature);
+                }
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
+        bail!("transaction {} not confirmed within 60s", signature)
+    }
+}
+
+fn encode_tx(tx: &Transaction) -> Result<String> {
+    Ok(BASE64.encode(bincode::serialize(tx)?))
+}
+
+fn parse_pubkey(value: &Value) -> Result<Pubkey> {
+    let s = value.as_str().ok_or_else(|| anyhow!("expected a pubkey string"))?;
+    Ok(Pubkey::from_str(s)?)
+}
+
+fn parse_account(value: &Value) -> Result<RpcAccount> {
+    let data = value["data"][0]
+        .as_str()
+        .ok_or_else(|| anyhow!("account data is not base64"))?;
+    Ok(RpcAccount {
+        lamports: value["lamports"].as_u64().unwrap_or_default(),
+        owner: parse_pubkey(&value["owner"])?,
+        data: BASE64.decode(data)?,
+    })
+}
@@ -0,0 +1,50 @@
+//! Build, simulate and send program transactions.
+
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anyhow::Result;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::rpc::{Rpc, Simulation};
+
+/// An `alons_box` instruction from its generated accounts and args structs.
+pub fn instruction(accounts: impl ToAccountMetas, args: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: alons_box::ID,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+pub struct Submitted {
+    pub simulation: Simulation,
+    /// Set once the transaction has been sent and confirmed
+    pub signature: Option<String>,
+}
+
+/// Sign `instructions` with `signer` as fee payer against the latest blockhash.
+pub fn sign(rpc: &Rpc, signer: &Keypair, instructions: &[Instruction]) -> Result<Transaction> {
+    let blockhash = rpc.latest_blockhash()?;
+    Ok(Transaction::new_signed_with_payer(
+        instructions,
+        Some(&signer.pubkey()),
+        &[signer],
+        blockhash,
+    ))
+}
+
+/// Sign `instructions` with `signer` as fee payer and simulate them. Unless
+/// `dry_run`, a transaction that simulated cleanly is then sent; one that
+/// failed simulation is never sent.
+pub fn submit(rpc: &Rpc, signer: &Keypair, instructions: &[Instruction], dry_run: bool) -> Result<Submitted> {
+    let tx = sign(rpc, signer, instructions)?;
+    let simulation = rpc.simulate(&tx)?;
+    let signature = if dry_run || simulation.err.is_some() {
+        None
+    } else {
+        Some(rpc.send_and_confirm(&tx)?)
+    };
+    Ok(Submitted { simulation, signature })
+}
@@ -0,0 +1,265 @@
+//! SQLite storage: schema, cursors, event rows and orphan pruning.
+
+use std::path::Path;
+
+use alons_box::state::TieBreakPolicy;
+use alons_box_client::events::Event;
+use alons_box_client::rpc::Commitment;
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+
+pub fn open(path: &Path) -> Result<Connection> {
+    let conn = Connection::open(path)?;
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    init(conn)
+}
+
+#[cfg(test)]
+pub fn open_in_memory() -> Result<Connection> {
+    init(Connection::open_in_memory()?)
+}
+
+fn init(conn: Connection) -> Result<Connection> {
+    conn.pragma_update(None, "foreign_keys", "ON")?;
+    conn.execute_batch(include_str!("schema.sql"))?;
+    Ok(conn)
+}
+
+/// Last signature processed at `commitment`, and its slot.
+pub struct Cursor {
+    pub signature: String,
+    pub slot: u64,
+}
+
+pub fn cursor(conn: &Connection, commitment: Commitment) -> Result<Option<Cursor>> {
+    Ok(conn
+        .query_row(
+            "SELECT signature, slot FROM cursors WHERE commitment = ?1",
+            [commitment.as_str()],
+            |row| {
+                Ok(Cursor {
+                    signature: row.get(0)?,
+                    slot: row.get::<_, i64>(1)? as u64,
+                })
+            },
+        )
+        .optional()?)
+}
+
+pub fn set_cursor(tx: &Transaction, commitment: Commitment, signature: &str, slot: u64) -> Result<()> {
+    tx.execute(
+        "INSERT INTO cursors (commitment, signature, slot) VALUES (?1, ?2, ?3)
+         ON CONFLICT (commitment) DO UPDATE SET signature = ?2, slot = ?3",
+        params![commitment.as_str(), signature, slot as i64],
+    )?;
+    Ok(())
+}
+
+pub fn clear_cursor(conn: &Connection, commitment: Commitment) -> Result<()> {
+    conn.execute("DELETE FROM cursors WHERE commitment = ?1", [commitment.as_str()])?;
+    Ok(())
+}
+
+/// `Some(finalized)` if the transaction is already indexed.
+pub fn transaction_state(tx: &Transaction, signature: &str) -> Result<Option<bool>> {
+    Ok(tx
+        .query_row(
+            "SELECT finalized FROM transactions WHERE signature = ?1",
+            [signature],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+pub fn mark_finalized(tx: &Transaction, signature: &str) -> Result<()> {
+    tx.execute("UPDATE transactions SET finalized = 1 WHERE signature = ?1", [signature])?;
+    Ok(())
+}
+
+/// Drop transactions indexed at `confirmed` whose slot is now finalized but
+/// that the finalized pass never saw. Their events go with them.
+pub fn prune_orphans(conn: &Connection, finalized_slot: u64) -> Result<usize> {
+    Ok(conn.execute(
+        "DELETE FROM transactions WHERE finalized = 0 AND slot <= ?1",
+        [finalized_slot as i64],
+    )?)
+}
+
+fn tie_break_name(policy: TieBreakPolicy) -> &'static str {
+    match policy {
+        TieBreakPolicy::EqualSplit => "equal_split",
+        TieBreakPolicy::EarliestGuess => "earliest_guess",
+        TieBreakPolicy::LargestDeposit => "largest_deposit",
+    }
+}
+
+/// Store a transaction and the round events it emitted.
+pub fn insert_transaction(
+    tx: &Transaction,
+    signature: &str,
+    slot: u64,
+    block_time: Option<i64>,
+    finalized: bool,
+    events: &[Event],
+) -> Result<()> {
+    tx.execute(
+        "INSERT INTO transactions (signature, slot, block_time, finalized) VALUES (?1, ?2, ?3, ?4)",
+        params![signature, slot as i64, block_time, finalized],
+    )?;
+    for (i, event) in events.iter().enumerate() {
+        // settle emits the legacy RoundSettled alongside RoundSettledV2; the
+        // V2 event carries everything, so the round is stored once
+        if let Event::RoundSettled(e) = event {
+            let has_v2 = events
+                .iter()
+                .any(|other| matches!(other, Event::RoundSettledV2(v2) if v2.round_id == e.round_id));
+            if has_v2 {
+                continue;
+            }
+        }
+        insert_event(tx, signature, i as i64, event)?;
+    }
+    Ok(())
+}
+
+fn insert_event(tx: &Transaction, sig: &str, i: i64, event: &Event) -> Result<()> {
+    match event {
+        Event::RoundCreated(e) => tx.execute(
+            "INSERT INTO round_created VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![sig, i, e.round_id as i64, e.ends_at, e.rollover_in as i64],
+        )?,
+        Event::JackpotReleased(e) => tx.execute(
+            "INSERT INTO jackpot_released VALUES (?1, ?2, ?3, ?4)",
+            params![sig, i, e.round_id as i64, e.amount as i64],
+        )?,
+        Event::DepositMade(e) => tx.execute(
+            "INSERT INTO deposit_made VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                sig,
+                i,
+                e.round_id as i64,
+                e.player.to_string(),
+                e.amount as i64,
+                e.total_deposits as i64
+            ],
+        )?,
+        Event::RoundExtended(e) => tx.execute(
+            "INSERT INTO round_extended VALUES (?1, ?2, ?3, ?4)",
+            params![sig, i, e.round_id as i64, e.ends_at],
+        )?,
+        Event::RoundSettled(e) => {
+            tx.execute(
+                "INSERT INTO round_payouts VALUES (?1, ?2, 0, ?3, ?4, ?5)",
+                params![sig, i, e.round_id as i64, e.winner.to_string(), e.winner_amount as i64],
+            )?;
+            tx.execute(
+                "INSERT INTO round_settled VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, NULL)",
+                params![
+                    sig,
+                    i,
+                    e.round_id as i64,
+                    e.pool as i64,
+                    e.winner_amount as i64,
+                    e.evidence_total as i64,
+                    e.treasury_amount as i64,
+                    e.rollover_out as i64
+                ],
+            )?
+        }
+        Event::RoundSettledV2(e) => {
+            for (rank, (winner, amount)) in e.winners.iter().zip(&e.winner_amounts).enumerate() {
+                tx.execute(
+                    "INSERT INTO round_payouts VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![sig, i, rank as i64, e.round_id as i64, winner.to_string(), *amount as i64],
+                )?;
+            }
+            tx.execute(
+                "INSERT INTO round_settled VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    sig,
+                    i,
+                    e.round_id as i64,
+                    e.pool as i64,
+                    e.winner_total as i64,
+                    e.evidence_total as i64,
+                    e.treasury_amount as i64,
+                    e.rollover_out as i64,
+                    tie_break_name(e.tie_break)
+                ],
+            )?
+        }
+        Event::RoundExpired(e) => tx.execute(
+            "INSERT INTO round_expired VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 0, NULL)",
+            params![
+                sig,
+                i,
+                e.round_id as i64,
+                e.pool as i64,
+                e.buyback_amount as i64,
+                e.treasury_amount as i64,
+                e.rollover_out as i64
+            ],
+        )?,
+        Event::EmergencyExpired(e) => tx.execute(
+            "INSERT INTO round_expired VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 1, ?8)",
+            params![
+                sig,
+                i,
+                e.round_id as i64,
+                e.pool as i64,
+                e.buyback_amount as i64,
+                e.treasury_amount as i64,
+                e.rollover_out as i64,
+                e.caller.to_string()
+            ],
+        )?,
+        Event::RoundFailed(e) => tx.execute(
+            "INSERT INTO round_failed VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![sig, i, e.round_id as i64, e.total_deposits as i64, e.min_pool as i64],
+        )?,
+        Event::RefundClaimed(e) => tx.execute(
+            "INSERT INTO refund_claimed VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![sig, i, e.round_id as i64, e.player.to_string(), e.amount as i64],
+        )?,
+        Event::DepositClosed(e) => tx.execute(
+            "INSERT INTO deposit_closed VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![sig, i, e.round_id as i64, e.player.to_string(), e.rent_recovered as i64],
+        )?,
+        Event::RoundClosed(e) => tx.execute(
+            "INSERT INTO round_closed VALUES (?1, ?2, ?3, ?4)",
+            params![sig, i, e.round_id as i64, e.rent_recovered as i64],
+        )?,
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alons_box::events::RoundCreated;
+
+    fn count(conn: &Connection, table: &str) -> i64 {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn pruning_an_orphan_drops_its_events() {
+        let mut conn = open_in_memory().unwrap();
+        let created = |round_id| Event::RoundCreated(RoundCreated { round_id, ends_at: 0, rollover_in: 0 });
+        let tx = conn.transaction().unwrap();
+        insert_transaction(&tx, "kept", 4, None, true, &[created(1)]).unwrap();
+        insert_transaction(&tx, "orphan", 5, None, false, &[created(2)]).unwrap();
+        insert_transaction(&tx, "pending", 7, None, false, &[created(3)]).unwrap();
+        tx.commit().unwrap();
+
+        // Slot 7 is not finalized yet, so its row may still finalize
+        assert_eq!(prune_orphans(&conn, 6).unwrap(), 1);
+        assert_eq!(count(&conn, "transactions"), 2);
+        assert_eq!(count(&conn, "round_created"), 2);
+        let orphaned: i64 = conn
+            .query_row("SELECT COUNT(*) FROM round_created WHERE round_id = 2", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(orphaned, 0);
+    }
+}
@@ -0,0 +1,72 @@
+//! `alons-box-indexer` — follows the program's transactions and stores its
+//! round events in SQLite.
+//!
+//! Progress is kept as per-commitment cursors in the database itself, so a
+//! restarted indexer resumes where it stopped. By default only finalized
+//! transactions are indexed; `--commitment confirmed` also indexes confirmed
+//! ones as provisional rows, which are dropped if their fork is.
+
+mod db;
+mod sync;
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use alons_box_client::rpc::{self, Commitment};
+use alons_box_client::Rpc;
+use anyhow::Result;
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(name = "alons-box-indexer", version, about = "Index Alon's Box round events into SQLite")]
+struct Cli {
+    /// RPC URL, or one of localnet, devnet, mainnet-beta
+    #[arg(long, short = 'u', env = "ALONS_BOX_RPC_URL", default_value = "localnet")]
+    url: String,
+
+    /// SQLite database file
+    #[arg(long, env = "ALONS_BOX_INDEX_DB", default_value = "alons-box.sqlite")]
+    db: PathBuf,
+
+    /// Lowest commitment to index: finalized, or confirmed for provisional
+    /// rows ahead of finalization
+    #[arg(long, default_value = "finalized")]
+    commitment: Commitment,
+
+    /// Seconds between passes
+    #[arg(long, default_value_t = 2)]
+    poll_interval: u64,
+
+    /// Run a single pass and exit
+    #[arg(long)]
+    once: bool,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut indexer = sync::Indexer {
+        rpc: Rpc::new(rpc::cluster_url(&cli.url)),
+        conn: db::open(&cli.db)?,
+        confirmed: cli.commitment == Commitment::Confirmed,
+    };
+
+    loop {
+        match indexer.pass() {
+            Ok(stats) => {
+                if stats.finalized + stats.confirmed + stats.orphaned > 0 {
+                    eprintln!(
+                        "indexed {} finalized, {} confirmed; dropped {} orphaned",
+                        stats.finalized, stats.confirmed, stats.orphaned
+                    );
+                }
+            }
+            // A failed pass leaves the cursors at the last committed batch
+            Err(e) if !cli.once => eprintln!("pass failed: {:#}", e),
+            Err(e) => return Err(e),
+        }
+        if cli.once {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_secs(cli.poll_interval));
+    }
+}
@@ -0,0 +1,172 @@
+-- Alon's Box event index.
+--
+-- Every event row belongs to a transaction. Rows indexed at `confirmed` have
+-- transactions.finalized = 0 until the finalized pass sees them; if their
+-- slot is finalized without them, the fork they were on was dropped and
+-- deleting the transaction cascades to its events.
+
+CREATE TABLE IF NOT EXISTS cursors (
+    commitment TEXT PRIMARY KEY,
+    signature  TEXT NOT NULL,
+    slot       INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS transactions (
+    signature  TEXT PRIMARY KEY,
+    slot       INTEGER NOT NULL,
+    block_time INTEGER,
+    finalized  INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS round_created (
+    signature   TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    event_index INTEGER NOT NULL,
+    round_id    INTEGER NOT NULL,
+    ends_at     INTEGER NOT NULL,
+    rollover_in INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS jackpot_released (
+    signature   TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    event_index INTEGER NOT NULL,
+    round_id    INTEGER NOT NULL,
+    amount      INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS deposit_made (
+    signature      TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    event_index    INTEGER NOT NULL,
+    round_id       INTEGER NOT NULL,
+    player         TEXT NOT NULL,
+    amount         INTEGER NOT NULL,
+    total_deposits INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS deposit_made_round ON deposit_made(round_id);
+CREATE INDEX IF NOT EXISTS deposit_made_player ON deposit_made(player);
+
+CREATE TABLE IF NOT EXISTS round_extended (
+    signature   TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    event_index INTEGER NOT NULL,
+    round_id    INTEGER NOT NULL,
+    ends_at     INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+-- RoundSettled (legacy, one winner) and RoundSettledV2; tie_break is NULL
+-- for legacy rows
+CREATE TABLE IF NOT EXISTS round_settled (
+    signature       TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    event_index     INTEGER NOT NULL,
+    round_id        INTEGER NOT NULL,
+    pool            INTEGER NOT NULL,
+    winner_total    INTEGER NOT NULL,
+    evidence_total  INTEGER NOT NULL,
+    treasury_amount INTEGER NOT NULL,
+    rollover_out    INTEGER NOT NULL,
+    tie_break       TEXT,
+    PRIMARY KEY (signature, event_index)
+);
+
+-- One row per winner of a settled round, rank 0 first
+CREATE TABLE IF NOT EXISTS round_payouts (
+    signature   TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    event_index INTEGER NOT NULL,
+    rank        INTEGER NOT NULL,
+    round_id    INTEGER NOT NULL,
+    player      TEXT NOT NULL,
+    amount      INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index, rank)
+);
+CREATE INDEX IF NOT EXISTS round_payouts_player ON round_payouts(player);
+
+-- RoundExpired and EmergencyExpired; caller is set for emergency rows
+CREATE TABLE IF NOT EXISTS round_expired (
+    signature       TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    event_index     INTEGER NOT NULL,
+    round_id        INTEGER NOT NULL,
+    pool            INTEGER NOT NULL,
+    buyback_amount  INTEGER NOT NULL,
+    treasury_amount INTEGER NOT NULL,
+    rollover_out    INTEGER NOT NULL,
+    emergency       INTEGER NOT NULL,
+    caller          TEXT,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS round_failed (
+    signature      TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    event_index    INTEGER NOT NULL,
+    round_id       INTEGER NOT NULL,
+    total_deposits INTEGER NOT NULL,
+    min_pool       INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS refund_claimed (
+    signature   TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    event_index INTEGER NOT NULL,
+    round_id    INTEGER NOT NULL,
+    player      TEXT NOT NULL,
+    amount      INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS deposit_closed (
+    signature      TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    event_index    INTEGER NOT NULL,
+    round_id       INTEGER NOT NULL,
+    player         TEXT NOT NULL,
+    rent_recovered INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS round_closed (
+    signature      TEXT NOT NULL REFERENCES transactions(signature) ON DELETE CASCADE,
+    event_index    INTEGER NOT NULL,
+    round_id       INTEGER NOT NULL,
+    rent_recovered INTEGER NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+
+-- ── Views for the frontend ──
+
+CREATE VIEW IF NOT EXISTS rounds AS
+SELECT
+    c.round_id,
+    t.block_time AS created_at,
+    COALESCE((SELECT MAX(x.ends_at) FROM round_extended x WHERE x.round_id = c.round_id), c.ends_at) AS ends_at,
+    c.rollover_in,
+    COALESCE((SELECT SUM(d.amount) FROM deposit_made d WHERE d.round_id = c.round_id), 0) AS total_deposits,
+    (SELECT COUNT(DISTINCT d.player) FROM deposit_made d WHERE d.round_id = c.round_id) AS players,
+    CASE
+        WHEN s.round_id IS NOT NULL THEN 'settled'
+        WHEN e.emergency = 1 THEN 'emergency_expired'
+        WHEN e.round_id IS NOT NULL THEN 'expired'
+        WHEN f.round_id IS NOT NULL THEN 'refunding'
+        ELSE 'active'
+    END AS outcome,
+    COALESCE(s.pool, e.pool) AS pool,
+    COALESCE(s.rollover_out, e.rollover_out) AS rollover_out,
+    EXISTS (SELECT 1 FROM round_closed rc WHERE rc.round_id = c.round_id) AS closed,
+    t.finalized
+FROM round_created c
+JOIN transactions t ON t.signature = c.signature
+LEFT JOIN round_settled s ON s.round_id = c.round_id
+LEFT JOIN round_expired e ON e.round_id = c.round_id
+LEFT JOIN round_failed f ON f.round_id = c.round_id;
+
+CREATE VIEW IF NOT EXISTS player_rounds AS
+SELECT
+    d.round_id,
+    d.player,
+    COUNT(*) AS deposits,
+    SUM(d.amount) AS deposited,
+    COALESCE((SELECT SUM(p.amount) FROM round_payouts p
+              WHERE p.round_id = d.round_id AND p.player = d.player), 0) AS won,
+    COALESCE((SELECT SUM(r.amount) FROM refund_claimed r
+              WHERE r.round_id = d.round_id AND r.player = d.player), 0) AS refunded
+FROM deposit_made d
+GROUP BY d.round_id, d.player;
@@ -0,0 +1,345 @@
+//! One indexing pass: follow program signatures past each cursor, decode
+//! their events and reconcile rows indexed ahead of finalization.
+
+use alons_box_client::events::parse_logs;
+use alons_box_client::rpc::{Commitment, ConfirmedTransaction, SignatureInfo};
+use alons_box_client::Rpc;
+use anyhow::{anyhow, Result};
+use rusqlite::Connection;
+
+use crate::db;
+
+/// Largest page `getSignaturesForAddress` returns.
+const PAGE: usize = 1000;
+/// Transactions written per SQLite transaction; the cursor moves with them.
+const BATCH: usize = 100;
+
+#[derive(Default)]
+pub struct PassStats {
+    pub finalized: usize,
+    pub confirmed: usize,
+    pub orphaned: usize,
+}
+
+/// The cluster reads a pass makes; [`Rpc`] outside tests.
+pub trait Source {
+    fn slot(&self, commitment: Commitment) -> Result<u64>;
+    fn program_signatures(
+        &self,
+        before: Option<&str>,
+        until: Option<&str>,
+        limit: usize,
+        commitment: Commitment,
+    ) -> Result<Vec<SignatureInfo>>;
+    fn transaction(&self, signature: &str, commitment: Commitment) -> Result<Option<ConfirmedTransaction>>;
+}
+
+impl Source for Rpc {
+    fn slot(&self, commitment: Commitment) -> Result<u64> {
+        Rpc::slot(self, commitment)
+    }
+
+    fn program_signatures(
+        &self,
+        before: Option<&str>,
+        until: Option<&str>,
+        limit: usize,
+        commitment: Commitment,
+    ) -> Result<Vec<SignatureInfo>> {
+        Rpc::program_signatures(self, before, until, limit, commitment)
+    }
+
+    fn transaction(&self, signature: &str, commitment: Commitment) -> Result<Option<ConfirmedTransaction>> {
+        Rpc::transaction(self, signature, commitment)
+    }
+}
+
+pub struct Indexer<S = Rpc> {
+    pub rpc: S,
+    pub conn: Connection,
+    /// Also index confirmed transactions ahead of finalization
+    pub confirmed: bool,
+}
+
+impl<S: Source> Indexer<S> {
+    pub fn pass(&mut self) -> Result<PassStats> {
+        // Read before the finalized pass, so every finalized transaction at
+        // or below this slot has been seen by the time orphans are pruned
+        let finalized_slot = self.rpc.slot(Commitment::Finalized)?;
+        let mut stats = PassStats {
+            finalized: self.follow(Commitment::Finalized)?,
+            ..PassStats::default()
+        };
+
+        if self.confirmed {
+            stats.orphaned = db::prune_orphans(&self.conn, finalized_slot)?;
+            if stats.orphaned > 0 {
+                // The confirmed cursor may point into the dropped fork
+                db::clear_cursor(&self.conn, Commitment::Confirmed)?;
+            }
+            stats.confirmed = self.follow(Commitment::Confirmed)?;
+        }
+        Ok(stats)
+    }
+
+    /// Where to resume at `commitment`. The confirmed pass never starts
+    /// behind the finalized one.
+    fn start(&self, commitment: Commitment) -> Result<Option<db::Cursor>> {
+        let finalized = db::cursor(&self.conn, Commitment::Finalized)?;
+        if commitment == Commitment::Finalized {
+            return Ok(finalized);
+        }
+        Ok(match (db::cursor(&self.conn, Commitment::Confirmed)?, finalized) {
+            (Some(confirmed), Some(finalized)) if confirmed.slot < finalized.slot => Some(finalized),
+            (Some(confirmed), _) => Some(confirmed),
+            (None, finalized) => finalized,
+        })
+    }
+
+    /// Index every program signature newer than the cursor at `commitment`,
+    /// oldest first. Returns how many transactions were added.
+    fn follow(&mut self, commitment: Commitment) -> Result<usize> {
+        let until = self.start(commitment)?.map(|c| c.signature);
+
+        let mut pending: Vec<SignatureInfo> = Vec::new();
+        loop {
+            let before = pending.last().map(|s| s.signature.as_str());
+            let page = self
+                .rpc
+                .program_signatures(before, until.as_deref(), PAGE, commitment)?;
+            let done = page.len() < PAGE;
+            pending.extend(page);
+            if done {
+                break;
+            }
+        }
+        pending.reverse();
+
+        let finalized = commitment == Commitment::Finalized;
+        let mut added = 0;
+        for batch in pending.chunks(BATCH) {
+            let tx = self.conn.transaction()?;
+            for sig in batch {
+                if !sig.failed {
+                    match db::transaction_state(&tx, &sig.signature)? {
+                        Some(true) => {}
+                        Some(false) if finalized => db::mark_finalized(&tx, &sig.signature)?,
+                        Some(false) => {}
+                        None => {
+                            let landed = self
+                                .rpc
+                                .transaction(&sig.signature, commitment)?
+                                .ok_or_else(|| anyhow!("transaction {} not found", sig.signature))?;
+                            if !landed.failed {
+                                db::insert_transaction(
+                                    &tx,
+                                    &sig.signature,
+                                    landed.slot,
+                                    landed.block_time,
+                                    finalized,
+                                    &parse_logs(&landed.logs),
+                                )?;
+                                added += 1;
+                            }
+                        }
+                    }
+                }
+                db::set_cursor(&tx, commitment, &sig.signature, sig.slot)?;
+            }
+            tx.commit()?;
+        }
+        Ok(added)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// A cluster whose transactions are listed oldest first.
+    #[derive(Default)]
+    struct Chain {
+        finalized_slot: u64,
+        /// (signature, slot, finalized)
+        txs: Vec<(&'static str, u64, bool)>,
+        /// `until` of every program_signatures call
+        untils: RefCell<Vec<Option<String>>>,
+    }
+
+    impl Chain {
+        fn push(&mut self, signature: &'static str, slot: u64, finalized: bool) {
+            self.txs.push((signature, slot, finalized));
+        }
+
+        fn finalize_through(&mut self, slot: u64) {
+            self.finalized_slot = slot;
+            for tx in self.txs.iter_mut().filter(|tx| tx.1 <= slot) {
+                tx.2 = true;
+            }
+        }
+
+        fn visible(&self, commitment: Commitment) -> impl DoubleEndedIterator<Item = &(&'static str, u64, bool)> {
+            self.txs
+                .iter()
+                .filter(move |tx| commitment == Commitment::Confirmed || tx.2)
+        }
+    }
+
+    impl Source for Chain {
+        fn slot(&self, commitment: Commitment) -> Result<u64> {
+            Ok(match commitment {
+                Commitment::Finalized => self.finalized_slot,
+                Commitment::Confirmed => self.txs.iter().map(|tx| tx.1).max().unwrap_or(0),
+            })
+        }
+
+        fn program_signatures(
+            &self,
+            before: Option<&str>,
+            until: Option<&str>,
+            limit: usize,
+            commitment: Commitment,
+        ) -> Result<Vec<SignatureInfo>> {
+            self.untils.borrow_mut().push(until.map(str::to_string));
+            let newest_first: Vec<_> = self.visible(commitment).rev().collect();
+            let start = match before {
+                Some(sig) => newest_first.iter().position(|tx| tx.0 == sig).map_or(0, |i| i + 1),
+                None => 0,
+            };
+            Ok(newest_first[start..]
+                .iter()
+                .take_while(|tx| Some(tx.0) != until)
+                .take(limit)
+                .map(|tx| SignatureInfo {
+                    signature: tx.0.to_string(),
+                    slot: tx.1,
+                    failed: false,
+                    block_time: None,
+                })
+                .collect())
+        }
+
+        fn transaction(&self, signature: &str, commitment: Commitment) -> Result<Option<ConfirmedTransaction>> {
+            Ok(self.visible(commitment).find(|tx| tx.0 == signature).map(|tx| ConfirmedTransaction {
+                slot: tx.1,
+                block_time: None,
+                failed: false,
+                fee: 5000,
+                account_keys: Vec::new(),
+                pre_balances: Vec::new(),
+                post_balances: Vec::new(),
+                instructions: Vec::new(),
+                logs: Vec::new(),
+            }))
+        }
+    }
+
+    fn indexer(chain: Chain, confirmed: bool) -> Indexer<Chain> {
+        Indexer {
+            rpc: chain,
+            conn: db::open_in_memory().unwrap(),
+            confirmed,
+        }
+    }
+
+    fn indexed(indexer: &Indexer<Chain>) -> Vec<(String, bool)> {
+        let mut stmt = indexer
+            .conn
+            .prepare("SELECT signature, finalized FROM transactions ORDER BY slot")
+            .unwrap();
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap()
+    }
+
+    fn cursor(indexer: &Indexer<Chain>, commitment: Commitment) -> Option<String> {
+        db::cursor(&indexer.conn, commitment).unwrap().map(|c| c.signature)
+    }
+
+    #[test]
+    fn a_pass_resumes_after_the_cursor() {
+        let mut chain = Chain::default();
+        chain.push("a", 1, true);
+        chain.push("b", 2, true);
+        chain.finalized_slot = 2;
+        let mut ix = indexer(chain, false);
+
+        assert_eq!(ix.pass().unwrap().finalized, 2);
+        assert_eq!(cursor(&ix, Commitment::Finalized).as_deref(), Some("b"));
+
+        ix.rpc.push("c", 3, true);
+        ix.rpc.finalized_slot = 3;
+        ix.rpc.untils.borrow_mut().clear();
+        assert_eq!(ix.pass().unwrap().finalized, 1);
+        assert_eq!(*ix.rpc.untils.borrow(), vec![Some("b".to_string())]);
+        assert_eq!(cursor(&ix, Commitment::Finalized).as_deref(), Some("c"));
+        assert_eq!(indexed(&ix).len(), 3);
+
+        // Nothing new: the cursor holds
+        assert_eq!(ix.pass().unwrap().finalized, 0);
+        assert_eq!(cursor(&ix, Commitment::Finalized).as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn confirmed_rows_are_marked_once_finalized() {
+        let mut chain = Chain::default();
+        chain.push("a", 1, true);
+        chain.push("b", 2, false);
+        chain.finalized_slot = 1;
+        let mut ix = indexer(chain, true);
+
+        let stats = ix.pass().unwrap();
+        assert_eq!((stats.finalized, stats.confirmed), (1, 1));
+        assert_eq!(indexed(&ix), vec![("a".into(), true), ("b".into(), false)]);
+
+        ix.rpc.finalize_through(2);
+        let stats = ix.pass().unwrap();
+        // Already stored: marked, not added again
+        assert_eq!((stats.finalized, stats.confirmed, stats.orphaned), (0, 0, 0));
+        assert_eq!(indexed(&ix), vec![("a".into(), true), ("b".into(), true)]);
+    }
+
+    #[test]
+    fn a_confirmed_transaction_that_never_finalizes_is_pruned() {
+        let mut chain = Chain::default();
+        chain.push("a", 1, true);
+        chain.push("fork", 2, false);
+        chain.finalized_slot = 1;
+        let mut ix = indexer(chain, true);
+
+        ix.pass().unwrap();
+        assert_eq!(cursor(&ix, Commitment::Confirmed).as_deref(), Some("fork"));
+
+        // The fork is dropped and slot 3 finalizes past it
+        ix.rpc.txs.retain(|tx| tx.0 != "fork");
+        ix.rpc.push("c", 3, true);
+        ix.rpc.finalized_slot = 3;
+        let stats = ix.pass().unwrap();
+        assert_eq!(stats.orphaned, 1);
+        assert_eq!(stats.finalized, 1);
+        assert_eq!(indexed(&ix), vec![("a".into(), true), ("c".into(), true)]);
+        // The confirmed pass restarted from the finalized cursor
+        assert_eq!(cursor(&ix, Commitment::Confirmed), None);
+    }
+
+    #[test]
+    fn the_confirmed_pass_never_starts_behind_finalized() {
+        let mut ix = indexer(Chain::default(), true);
+        let tx = ix.conn.transaction().unwrap();
+        db::set_cursor(&tx, Commitment::Confirmed, "old", 1).unwrap();
+        db::set_cursor(&tx, Commitment::Finalized, "new", 5).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(ix.start(Commitment::Confirmed).unwrap().unwrap().signature, "new");
+
+        let tx = ix.conn.transaction().unwrap();
+        db::set_cursor(&tx, Commitment::Confirmed, "ahead", 9).unwrap();
+        tx.commit().unwrap();
+        assert_eq!(ix.start(Commitment::Confirmed).unwrap().unwrap().signature, "ahead");
+        assert_eq!(ix.start(Commitment::Finalized).unwrap().unwrap().signature, "new");
+
+        db::clear_cursor(&ix.conn, Commitment::Confirmed).unwrap();
+        assert_eq!(ix.start(Commitment::Confirmed).unwrap().unwrap().signature, "new");
+    }
+}
@@ -0,0 +1,71 @@
+//! Rolling cap on the lamports the keeper spends on transaction fees.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+pub struct FeeBudget {
+    limit: u64,
+    window: Duration,
+    /// Fees charged inside the window, oldest first
+    spent: VecDeque<(Instant, u64)>,
+}
+
+impl FeeBudget {
+    pub fn new(limit: u64, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            spent: VecDeque::new(),
+        }
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Lamports left to spend in the current window.
+    pub fn remaining(&mut self) -> u64 {
+        let now = Instant::now();
+        while let Some(&(at, _)) = self.spent.front() {
+            if now.duration_since(at) < self.window {
+                break;
+            }
+            self.spent.pop_front();
+        }
+        let spent: u64 = self.spent.iter().map(|&(_, fee)| fee).sum();
+        self.limit.saturating_sub(spent)
+    }
+
+    pub fn charge(&mut self, fee: u64) {
+        self.spent.push_back((Instant::now(), fee));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charges_count_against_the_limit_until_exhausted() {
+        let mut budget = FeeBudget::new(10_000, Duration::from_secs(3600));
+        assert_eq!(budget.remaining(), 10_000);
+        budget.charge(6_000);
+        assert_eq!(budget.remaining(), 4_000);
+        budget.charge(4_000);
+        assert_eq!(budget.remaining(), 0);
+        // A fee quoted above the estimate cannot push it below zero
+        budget.charge(5_000);
+        assert_eq!(budget.remaining(), 0);
+        assert_eq!(budget.limit(), 10_000);
+    }
+
+    #[test]
+    fn charges_older_than_the_window_are_released() {
+        let mut budget = FeeBudget::new(10_000, Duration::from_millis(50));
+        budget.charge(10_000);
+        assert_eq!(budget.remaining(), 0);
+        std::thread::sleep(Duration::from_millis(60));
+        budget.charge(2_500);
+        assert_eq!(budget.remaining(), 7_500);
+    }
+}
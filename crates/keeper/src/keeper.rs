@@ -0,0 +1,423 @@
+//! One keeper pass: fail ended rounds under their min_pool, emergency-expire
+//! rounds stuck past their grace period, then recover the rent of finished
+//! rounds, their deposits, questions and guesses.
+
+use alons_box::state::*;
+use alons_box::{accounts, instruction};
+use alons_box_client::{pda, tx, Rpc};
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::system_program;
+use anyhow::Result;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+use crate::budget::FeeBudget;
+
+/// Deposits closed per transaction. Each one adds its Deposit key, and its
+/// Referrer key when a referral is credited first; eight stays well inside
+/// the 1232-byte packet limit.
+const CLOSE_BATCH: usize = 8;
+
+/// Offset of `Deposit.round_id`, `Question.round_id` and `Guess.round_id`,
+/// right after the discriminator.
+const ROUND_ID_OFFSET: usize = 8;
+
+/// Offset of `Round.status`: discriminator, round_id, commit_hash, authority
+/// and ends_at come first.
+const ROUND_STATUS_OFFSET: usize = 8 + 8 + 32 + 32 + 8;
+
+#[derive(Default)]
+pub struct PassStats {
+    pub sent: usize,
+    pub failed: usize,
+    pub deferred: usize,
+}
+
+enum Sent {
+    Ok,
+    /// Simulation failed; nothing was sent
+    Failed,
+    /// The fee budget cannot cover it; the rest of the pass is deferred
+    OverBudget,
+}
+
+/// What a pass does with an `Active` round
+#[derive(Debug, PartialEq, Eq)]
+enum Expiry {
+    /// Before ends_at
+    Live,
+    /// Ended; emergency_expire is allowed in this many seconds
+    Pending(i64),
+    /// Ended under its min_pool: settle and expire reject it, fail_round refunds it
+    Fail,
+    /// Past the emergency grace period
+    Emergency,
+}
+
+fn expiry(round: &Round, now: i64) -> Expiry {
+    if now < round.ends_at {
+        return Expiry::Live;
+    }
+    if !round.min_pool_met() {
+        return Expiry::Fail;
+    }
+    let deadline = round.ends_at.saturating_add(EMERGENCY_GRACE_PERIOD);
+    if now <= deadline {
+        Expiry::Pending(deadline - now + 1)
+    } else {
+        Expiry::Emergency
+    }
+}
+
+pub struct Keeper {
+    pub rpc: Rpc,
+    pub signer: Keypair,
+    pub budget: FeeBudget,
+    pub dry_run: bool,
+}
+
+impl Keeper {
+    pub fn pass(&mut self) -> Result<PassStats> {
+        let gs: GameState = self.rpc.require_account(&pda::game_state(), "GameState")?;
+        let roles: Roles = self.rpc.require_account(&pda::roles(), "Roles")?;
+        let now = self.rpc.cluster_time()?;
+
+        let active = self.rounds(&[RoundStatus::Active])?;
+        let mut stats = PassStats::default();
+        for round in &active {
+            let (ix, what) = match expiry(round, now) {
+                Expiry::Live => continue,
+                Expiry::Pending(wait) => {
+                    if self.dry_run {
+                        println!(
+                            "round {}: ended, emergency_expire allowed in {}s",
+                            round.round_id, wait
+                        );
+                    }
+                    continue;
+                }
+                Expiry::Fail => (self.fail_round(round), "fail_round"),
+                Expiry::Emergency => (self.emergency_expire(&gs, round), "emergency_expire"),
+            };
+            let what = format!("round {}: {}", round.round_id, what);
+            if let Sent::OverBudget = self.send(&what, &[ix], &mut stats)? {
+                return Ok(stats);
+            }
+        }
+
+        let finished = self.rounds(&[RoundStatus::Settled, RoundStatus::Expired, RoundStatus::Refunding])?;
+        if finished.is_empty() {
+            return Ok(stats);
+        }
+        if !roles.has(&self.signer.pubkey(), ROLE_CLOSER) {
+            eprintln!(
+                "{} lacks the closer role; {} finished round(s) left open",
+                self.signer.pubkey(),
+                finished.len()
+            );
+            return Ok(stats);
+        }
+        for round in &finished {
+            if !self.clean_up(round, &mut stats)? {
+                break;
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Rounds in any of `statuses`, oldest first, fetched with a memcmp
+    /// filter on the status byte per status.
+    fn rounds(&self, statuses: &[RoundStatus]) -> Result<Vec<Round>> {
+        let mut rounds = Vec::new();
+        for &status in statuses {
+            rounds.extend(
+                self.rpc
+                    .program_accounts::<Round>(&[(ROUND_STATUS_OFFSET, &[status as u8])])?
+                    .into_iter()
+                    .map(|(_, round)| round),
+            );
+        }
+        rounds.sort_by_key(|round| round.round_id);
+        Ok(rounds)
+    }
+
+    /// Close a finished round's questions, guesses and deposits, crediting
+    /// outstanding referrals first, then the round itself. Returns false
+    /// once the budget runs out.
+    fn clean_up(&mut self, round: &Round, stats: &mut PassStats) -> Result<bool> {
+        let round_id = round.round_id;
+        let mut all_closed = true;
+
+        // close_question and close_guess also work once the Round is closed,
+        // so a failed batch does not hold back close_round
+        let questions = self
+            .rpc
+            .program_accounts::<Question>(&[(ROUND_ID_OFFSET, &round_id.to_le_bytes())])?;
+        for batch in questions.chunks(CLOSE_BATCH) {
+            let ixs: Vec<Instruction> = batch.iter().map(|(key, q)| self.close_question(key, q)).collect();
+            let what = format!("round {}: close {} question(s)", round_id, batch.len());
+            if let Sent::OverBudget = self.send(&what, &ixs, stats)? {
+                return Ok(false);
+            }
+        }
+        let guesses = self
+            .rpc
+            .program_accounts::<Guess>(&[(ROUND_ID_OFFSET, &round_id.to_le_bytes())])?;
+        for batch in guesses.chunks(CLOSE_BATCH) {
+            let ixs: Vec<Instruction> = batch.iter().map(|(key, g)| self.close_guess(key, g)).collect();
+            let what = format!("round {}: close {} guess(es)", round_id, batch.len());
+            if let Sent::OverBudget = self.send(&what, &ixs, stats)? {
+                return Ok(false);
+            }
+        }
+
+        if round.status == RoundStatus::Refunding {
+            // claim_refund closes each deposit; the round waits for the last one
+            if round.refunded < round.total_deposits {
+                return Ok(true);
+            }
+        } else {
+            let deposits = self
+                .rpc
+                .program_accounts::<Deposit>(&[(ROUND_ID_OFFSET, &round_id.to_le_bytes())])?;
+            let credit = !round.referrals_credited();
+
+            for batch in deposits.chunks(CLOSE_BATCH) {
+                let mut ixs = Vec::new();
+                for (_, deposit) in batch {
+                    if credit && deposit.referrer != Pubkey::default() && !deposit.referral_credited {
+                        ixs.push(self.credit_referral(deposit));
+                    }
+                    ixs.push(self.close_deposit(deposit));
+                }
+                let what = format!("round {}: close {} deposit(s)", round_id, batch.len());
+                match self.send(&what, &ixs, stats)? {
+                    Sent::Ok => {}
+                    Sent::Failed => all_closed = false,
+                    Sent::OverBudget => return Ok(false),
+                }
+            }
+        }
+
+        // A Round closed ahead of its deposits would strand them: close_deposit
+        // needs the Round account
+        if !all_closed {
+            return Ok(true);
+        }
+        let what = format!("round {}: close_round", round_id);
+        let ix = self.close_round(round_id)?;
+        Ok(!matches!(self.send(&what, &[ix], stats)?, Sent::OverBudget))
+    }
+
+    /// Quote, budget-check and simulate one transaction, then send it unless
+    /// this is a dry run. A dry run still charges the budget, so it shows
+    /// what a live pass would defer.
+    fn send(&mut self, what: &str, ixs: &[Instruction], stats: &mut PassStats) -> Result<Sent> {
+        let tx = tx::sign(&self.rpc, &self.signer, ixs)?;
+        let fee = self.rpc.fee_for_message(&tx)?;
+        let remaining = self.budget.remaining();
+        if fee > remaining {
+            println!(
+                "{}: deferred, fee {} lamports exceeds the {} left of the {} budget",
+                what,
+                fee,
+                remaining,
+                self.budget.limit()
+            );
+            stats.deferred += 1;
+            return Ok(Sent::OverBudget);
+        }
+
+        let simulation = self.rpc.simulate(&tx)?;
+        if let Some(err) = &simulation.err {
+            eprintln!("{}: simulation failed: {}", what, err);
+            for log in &simulation.logs {
+                eprintln!("  {}", log);
+            }
+            stats.failed += 1;
+            return Ok(Sent::Failed);
+        }
+
+        // Charged before sending: a transaction that lands but times out
+        // waiting for confirmation has still paid its fee
+        self.budget.charge(fee);
+        if self.dry_run {
+            println!("{}: simulated ok, fee {} lamports (dry run)", what, fee);
+        } else {
+            println!("{}: {}", what, self.rpc.send_and_confirm(&tx)?);
+        }
+        stats.sent += 1;
+        Ok(Sent::Ok)
+    }
+
+    fn emergency_expire(&self, gs: &GameState, round: &Round) -> Instruction {
+        let round_id = round.round_id;
+        tx::instruction(
+            accounts::EmergencyExpire {
+                caller: self.signer.pubkey(),
+                game_state: pda::game_state(),
+                round: pda::round(round_id),
+                vault: pda::vault(),
+                settlement: pda::settlement(round_id),
+                treasury: gs.treasury,
+                treasury_stream: pda::treasury_stream(),
+                buyback_wallet: gs.buyback_wallet,
+                jackpot_pool: pda::jackpot_pool(),
+                season: pda::round_season(round.season_id),
+                system_program: system_program::ID,
+            },
+            instruction::EmergencyExpire {},
+        )
+    }
+
+    fn fail_round(&self, round: &Round) -> Instruction {
+        let round_id = round.round_id;
+        tx::instruction(
+            accounts::FailRound {
+                caller: self.signer.pubkey(),
+                game_state: pda::game_state(),
+                round: pda::round(round_id),
+                settlement: pda::settlement(round_id),
+                season: pda::round_season(round.season_id),
+                system_program: system_program::ID,
+            },
+            instruction::FailRound {},
+        )
+    }
+
+    fn credit_referral(&self, deposit: &Deposit) -> Instruction {
+        tx::instruction(
+            accounts::CreditReferral {
+                caller: self.signer.pubkey(),
+                round: pda::round(deposit.round_id),
+                deposit: pda::deposit(deposit.round_id, &deposit.user),
+                referrer: pda::referrer(&deposit.referrer),
+            },
+            instruction::CreditReferral {},
+        )
+    }
+
+    fn close_deposit(&self, deposit: &Deposit) -> Instruction {
+        tx::instruction(
+            accounts::CloseDeposit {
+                authority: self.signer.pubkey(),
+                game_state: pda::game_state(),
+                roles: pda::roles(),
+                round: pda::round(deposit.round_id),
+                deposit: pda::deposit(deposit.round_id, &deposit.user),
+            },
+            instruction::CloseDeposit {},
+        )
+    }
+
+    fn close_question(&self, key: &Pubkey, question: &Question) -> Instruction {
+        tx::instruction(
+            accounts::CloseQuestion {
+                authority: self.signer.pubkey(),
+                game_state: pda::game_state(),
+                roles: pda::roles(),
+                round: pda::round(question.round_id),
+                question: *key,
+            },
+            instruction::CloseQuestion {},
+        )
+    }
+
+    fn close_guess(&self, key: &Pubkey, guess: &Guess) -> Instruction {
+        tx::instruction(
+            accounts::CloseGuess {
+                authority: self.signer.pubkey(),
+                game_state: pda::game_state(),
+                roles: pda::roles(),
+                round: pda::round(guess.round_id),
+                guess: *key,
+            },
+            instruction::CloseGuess {},
+        )
+    }
+
+    fn close_round(&self, round_id: u64) -> Result<Instruction> {
+        let settlement: Settlement = self.rpc.require_account(&pda::settlement(round_id), "Settlement")?;
+        Ok(tx::instruction(
+            accounts::CloseRound {
+                authority: self.signer.pubkey(),
+                game_state: pda::game_state(),
+                roles: pda::roles(),
+                round: pda::round(round_id),
+                settlement: pda::settlement(round_id),
+                rent_payer: settlement.rent_payer,
+                archive: pda::round_archive(),
+                system_program: system_program::ID,
+            },
+            instruction::CloseRound {},
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENDS_AT: i64 = 1_700_000_000;
+
+    fn round(total_deposits: u64, min_pool: u64) -> Round {
+        Round {
+            round_id: 1,
+            commit_hash: [0; 32],
+            authority: Pubkey::default(),
+            ends_at: ENDS_AT,
+            status: RoundStatus::Active,
+            total_deposits,
+            rollover_in: 0,
+            revealed_answer: String::new(),
+            revealed_salt: String::new(),
+            bump: 0,
+            original_ends_at: ENDS_AT,
+            soft_close: SoftClose::default(),
+            min_pool,
+            refunded: 0,
+            jackpot_in: 0,
+            deposit_policy: DepositPolicy::default(),
+            referred_deposits: 0,
+            referral_reserve: 0,
+            referred_credited: 0,
+            referral_credited: 0,
+            season_id: 0,
+        }
+    }
+
+    #[test]
+    fn live_rounds_are_skipped_until_ends_at() {
+        assert_eq!(expiry(&round(0, 0), ENDS_AT - 1), Expiry::Live);
+        // Under min_pool does not matter while deposits are still open
+        assert_eq!(expiry(&round(0, 100), ENDS_AT - 1), Expiry::Live);
+    }
+
+    #[test]
+    fn ended_rounds_wait_out_the_grace_period() {
+        let deadline = ENDS_AT + EMERGENCY_GRACE_PERIOD;
+        assert_eq!(expiry(&round(5, 0), ENDS_AT), Expiry::Pending(EMERGENCY_GRACE_PERIOD + 1));
+        assert_eq!(expiry(&round(5, 0), deadline), Expiry::Pending(1));
+        assert_eq!(expiry(&round(5, 0), deadline + 1), Expiry::Emergency);
+        // A round that met its min_pool follows the same schedule
+        assert_eq!(expiry(&round(100, 100), deadline + 1), Expiry::Emergency);
+    }
+
+    #[test]
+    fn ended_rounds_under_min_pool_are_failed_at_once() {
+        assert_eq!(expiry(&round(99, 100), ENDS_AT), Expiry::Fail);
+        assert_eq!(expiry(&round(0, 100), ENDS_AT + EMERGENCY_GRACE_PERIOD + 1), Expiry::Fail);
+    }
+
+    #[test]
+    fn status_filter_matches_the_serialized_round() {
+        use anchor_lang::AccountSerialize;
+
+        let mut settled = round(0, 0);
+        settled.status = RoundStatus::Settled;
+        let mut data = Vec::new();
+        settled.try_serialize(&mut data).unwrap();
+        assert_eq!(data[ROUND_STATUS_OFFSET], RoundStatus::Settled as u8);
+    }
+}
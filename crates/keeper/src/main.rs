@@ -0,0 +1,92 @@
+//! `alons-box-keeper` — watches every Round and does the permissionless and
+//! closer-role housekeeping nobody else will:
+//!
+//! - `emergency_expire` for rounds still Active past `ends_at` plus the grace
+//!   period
+//! - `close_question`, `close_guess`, `credit_referral`, `close_deposit` and
+//!   `close_round` for finished rounds, returning their rent to the keeper
+//!
+//! Every transaction is simulated first, and fees are capped by a rolling
+//! budget.
+
+mod budget;
+mod keeper;
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use alons_box_client::{rpc, Rpc};
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use solana_keypair::read_keypair_file;
+
+#[derive(Parser)]
+#[command(name = "alons-box-keeper", version, about = "Expire stuck Alon's Box rounds and recover rent")]
+struct Cli {
+    /// RPC URL, or one of localnet, devnet, mainnet-beta
+    #[arg(long, short = 'u', env = "ALONS_BOX_RPC_URL", default_value = "localnet")]
+    url: String,
+
+    /// Keeper keypair; pays fees and receives the recovered rent
+    /// [default: ~/.config/solana/id.json]
+    #[arg(long, short = 'k', env = "ALONS_BOX_KEYPAIR")]
+    keypair: Option<PathBuf>,
+
+    /// Most lamports to spend on fees per budget window
+    #[arg(long, env = "ALONS_BOX_KEEPER_FEE_BUDGET", default_value_t = 10_000_000)]
+    fee_budget: u64,
+
+    /// Length of the fee budget window, in seconds
+    #[arg(long, default_value_t = 86_400)]
+    budget_window: u64,
+
+    /// Seconds between passes
+    #[arg(long, default_value_t = 60)]
+    poll_interval: u64,
+
+    /// Run a single pass and exit
+    #[arg(long)]
+    once: bool,
+
+    /// Simulate every transaction, never send
+    #[arg(long)]
+    dry_run: bool,
+}
+
+fn default_keypair() -> PathBuf {
+    let home = std::env::var_os("HOME").unwrap_or_default();
+    PathBuf::from(home).join(".config/solana/id.json")
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let keypair = cli.keypair.unwrap_or_else(default_keypair);
+    let signer = read_keypair_file(&keypair)
+        .map_err(|e| anyhow!("cannot read keypair {}: {}", keypair.display(), e))?;
+
+    let mut keeper = keeper::Keeper {
+        rpc: Rpc::new(rpc::cluster_url(&cli.url)),
+        signer,
+        budget: budget::FeeBudget::new(cli.fee_budget, Duration::from_secs(cli.budget_window)),
+        dry_run: cli.dry_run,
+    };
+
+    loop {
+        match keeper.pass() {
+            Ok(stats) => {
+                if stats.sent + stats.failed + stats.deferred > 0 {
+                    eprintln!(
+                        "pass: {} sent, {} failed simulation, {} deferred by the fee budget",
+                        stats.sent, stats.failed, stats.deferred
+                    );
+                }
+            }
+            Err(e) if !cli.once => eprintln!("pass failed: {:#}", e),
+            Err(e) => return Err(e),
+        }
+        if cli.once {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_secs(cli.poll_interval));
+    }
+}
@@ -0,0 +1,275 @@
+//! `alons-box-sim` — plays thousands of simulated rounds through the
+//! program's payout math to show where a split, rollover cap and overflow
+//! policy take the rollover, treasury, buyback and player returns over time.
+//! Per-round rows go out as CSV for plotting; a summary goes to stderr.
+
+mod dist;
+mod sim;
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use alons_box::state::{OverflowPolicy, PayoutConfig};
+use alons_box_client::LAMPORTS_PER_SOL;
+use anyhow::{bail, Result};
+use clap::Parser;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use sim::{Config, Outcome, Players, RoundResult, Sim};
+
+#[derive(Parser)]
+#[command(
+    name = "alons-box-sim",
+    version,
+    about = "Simulate Alon's Box economics over many rounds"
+)]
+struct Cli {
+    /// Rounds per run
+    #[arg(long, default_value_t = 5000)]
+    rounds: u64,
+    /// Independent runs, each from an empty game
+    #[arg(long, default_value_t = 1)]
+    runs: u64,
+    /// RNG seed; run N uses seed + N
+    #[arg(long, default_value_t = 1)]
+    seed: u64,
+    /// Write per-round CSV to a file instead of stdout
+    #[arg(long, short = 'o')]
+    output: Option<PathBuf>,
+
+    /// Mean players per round
+    #[arg(long, default_value_t = 20.0)]
+    players: f64,
+    /// Median deposit, in SOL
+    #[arg(long, default_value_t = 0.1)]
+    deposit_median: f64,
+    /// Spread of deposits (sigma of the log-normal)
+    #[arg(long, default_value_t = 1.0)]
+    deposit_sigma: f64,
+    /// Chance each player guesses the answer
+    #[arg(long, default_value_t = 0.02)]
+    win_prob: f64,
+    /// Chance each player asks a question
+    #[arg(long, default_value_t = 0.3)]
+    question_prob: f64,
+    /// Chance a question is answered YES
+    #[arg(long, default_value_t = 0.5)]
+    yes_prob: f64,
+
+    #[arg(long, default_value_t = PayoutConfig::default().winner_bps)]
+    winner_bps: u16,
+    #[arg(long, default_value_t = PayoutConfig::default().evidence_bps)]
+    evidence_bps: u16,
+    #[arg(long, default_value_t = PayoutConfig::default().treasury_bps)]
+    treasury_bps: u16,
+    #[arg(long, default_value_t = PayoutConfig::default().expire_buyback_bps)]
+    expire_buyback_bps: u16,
+    #[arg(long, default_value_t = PayoutConfig::default().expire_treasury_bps)]
+    expire_treasury_bps: u16,
+    /// Rollover cap in SOL; 0 = uncapped
+    #[arg(long, default_value_t = 0.0)]
+    rollover_cap: f64,
+    /// Where rollover above the cap goes: jackpot or buyback
+    #[arg(long, default_value = "jackpot", value_parser = overflow_policy)]
+    overflow_policy: OverflowPolicy,
+    /// Every Nth round pulls the jackpot pool in; 0 = never
+    #[arg(long, default_value_t = 0)]
+    jackpot_every: u64,
+    /// Share of the evidence pool allocated to YES askers, in BPS
+    #[arg(long, default_value_t = 10000)]
+    evidence_alloc_bps: u16,
+    /// Most evidence wallets paid per round
+    #[arg(long, default_value_t = 10)]
+    max_evidence: usize,
+}
+
+fn overflow_policy(s: &str) -> Result<OverflowPolicy, String> {
+    match s {
+        "jackpot" => Ok(OverflowPolicy::Jackpot),
+        "buyback" => Ok(OverflowPolicy::Buyback),
+        _ => Err(format!("expected jackpot or buyback, got {}", s)),
+    }
+}
+
+const HEADER: &str = "run,round,players,deposits,rollover_in,jackpot_in,outcome,winners,evidence,\
+pool,winner_paid,evidence_paid,treasury,buyback,to_jackpot,jackpot_balance,rollover_out,player_net";
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let payout = PayoutConfig {
+        winner_bps: cli.winner_bps,
+        evidence_bps: cli.evidence_bps,
+        treasury_bps: cli.treasury_bps,
+        expire_buyback_bps: cli.expire_buyback_bps,
+        expire_treasury_bps: cli.expire_treasury_bps,
+    };
+    if !payout.is_valid() {
+        bail!("invalid payout config: settle or expire shares exceed 10000 BPS");
+    }
+    if cli.evidence_alloc_bps > 10000 {
+        bail!("--evidence-alloc-bps must be at most 10000");
+    }
+    for (name, p) in [
+        ("--win-prob", cli.win_prob),
+        ("--question-prob", cli.question_prob),
+        ("--yes-prob", cli.yes_prob),
+    ] {
+        if !(0.0..=1.0).contains(&p) {
+            bail!("{} must be between 0 and 1", name);
+        }
+    }
+
+    let players = Players {
+        mean: cli.players,
+        deposit_median: cli.deposit_median,
+        deposit_sigma: cli.deposit_sigma,
+        win_prob: cli.win_prob,
+        question_prob: cli.question_prob,
+        yes_prob: cli.yes_prob,
+    };
+    let config = Config {
+        payout,
+        rollover_cap: (cli.rollover_cap * LAMPORTS_PER_SOL as f64) as u64,
+        overflow_policy: cli.overflow_policy,
+        evidence_alloc_bps: cli.evidence_alloc_bps,
+        max_evidence: cli.max_evidence,
+        jackpot_every: cli.jackpot_every,
+    };
+
+    let mut out: BufWriter<Box<dyn Write>> = BufWriter::new(match &cli.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout().lock()),
+    });
+    writeln!(out, "{}", HEADER)?;
+
+    let mut summary = Summary::default();
+    for run in 0..cli.runs {
+        let mut sim = Sim::new(&players, &config, StdRng::seed_from_u64(cli.seed + run));
+        let mut last = None;
+        for _ in 0..cli.rounds {
+            let r = sim.step()?;
+            write_row(&mut out, run, &r)?;
+            summary.add(&r);
+            last = Some(r);
+        }
+        if let Some(r) = last {
+            summary.finals.push(r.rollover_out);
+            summary.final_jackpots.push(r.jackpot_balance);
+        }
+    }
+    out.flush()?;
+    summary.print(cli.runs, cli.rounds);
+    Ok(())
+}
+
+fn write_row(out: &mut impl Write, run: u64, r: &RoundResult) -> io::Result<()> {
+    writeln!(
+        out,
+        "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+        run,
+        r.round,
+        r.players,
+        r.deposits,
+        r.rollover_in,
+        r.jackpot_in,
+        r.outcome.as_str(),
+        r.winners,
+        r.evidence,
+        r.pool,
+        r.winner_paid,
+        r.evidence_paid,
+        r.treasury,
+        r.buyback,
+        r.to_jackpot,
+        r.jackpot_balance,
+        r.rollover_out,
+        r.player_net(),
+    )
+}
+
+/// Totals across every round of every run.
+#[derive(Default)]
+struct Summary {
+    settled: u64,
+    deposits: u128,
+    returned: u128,
+    treasury: u128,
+    buyback: u128,
+    rollovers: Vec<u64>,
+    finals: Vec<u64>,
+    final_jackpots: Vec<u64>,
+}
+
+impl Summary {
+    fn add(&mut self, r: &RoundResult) {
+        if r.outcome == Outcome::Settled {
+            self.settled += 1;
+        }
+        self.deposits += r.deposits as u128;
+        self.returned += (r.winner_paid + r.evidence_paid) as u128;
+        self.treasury += r.treasury as u128;
+        self.buyback += r.buyback as u128;
+        self.rollovers.push(r.rollover_out);
+    }
+
+    fn print(mut self, runs: u64, rounds: u64) {
+        let total = self.rollovers.len().max(1) as f64;
+        self.rollovers.sort_unstable();
+        let percentile = |p: f64| {
+            let i = ((self.rollovers.len() as f64 - 1.0) * p).round() as usize;
+            self.rollovers.get(i).copied().unwrap_or(0)
+        };
+        let mean = |values: &[u64]| {
+            values.iter().map(|&v| v as f64).sum::<f64>() / values.len().max(1) as f64
+        };
+        let sol_f = |lamports: f64| format!("{:.4}", lamports / LAMPORTS_PER_SOL as f64);
+
+        eprintln!("{} run(s) x {} rounds", runs, rounds);
+        eprintln!(
+            "Settled:      {:.1}% of rounds ({} settled, {} expired)",
+            self.settled as f64 / total * 100.0,
+            self.settled,
+            self.rollovers.len() as u64 - self.settled
+        );
+        eprintln!(
+            "Rollover:     mean {}  p50 {}  p95 {}  max {} SOL",
+            sol_f(mean(&self.rollovers)),
+            sol_f(percentile(0.5) as f64),
+            sol_f(percentile(0.95) as f64),
+            sol_f(self.rollovers.last().copied().unwrap_or(0) as f64),
+        );
+        eprintln!(
+            "Final:        rollover mean {}  max {}  jackpot pool mean {} SOL",
+            sol_f(mean(&self.finals)),
+            sol_f(self.finals.iter().copied().max().unwrap_or(0) as f64),
+            sol_f(mean(&self.final_jackpots)),
+        );
+        eprintln!(
+            "Deposits:     {} SOL ({} per round)",
+            sol_f(self.deposits as f64),
+            sol_f(self.deposits as f64 / total)
+        );
+        eprintln!(
+            "Treasury:     {} SOL ({} per round)",
+            sol_f(self.treasury as f64),
+            sol_f(self.treasury as f64 / total)
+        );
+        eprintln!(
+            "Buyback:      {} SOL ({} per round)",
+            sol_f(self.buyback as f64),
+            sol_f(self.buyback as f64 / total)
+        );
+        let ev = if self.deposits == 0 {
+            0.0
+        } else {
+            (self.returned as f64 - self.deposits as f64) / self.deposits as f64
+        };
+        eprintln!(
+            "Player EV:    {:+.4} per SOL deposited ({} SOL returned)",
+            ev,
+            sol_f(self.returned as f64)
+        );
+    }
+}
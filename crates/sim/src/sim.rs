@@ -0,0 +1,346 @@
+//! Round-by-round model of the game's economy. Players and outcomes are
+//! sampled; every payout goes through `alons_box_client::payout`, the same
+//! math the program and the verifier use.
+
+use alons_box::state::{GameState, OverflowPolicy, PayoutConfig, TieBreakPolicy, MAX_WINNERS};
+use alons_box_client::payout::{self, Stakes};
+use alons_box_client::LAMPORTS_PER_SOL;
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use rand::Rng;
+
+use crate::dist;
+
+/// Player behaviour.
+pub struct Players {
+    /// Mean players per round (Poisson)
+    pub mean: f64,
+    /// Median deposit in SOL (log-normal)
+    pub deposit_median: f64,
+    /// Shape of the deposit distribution
+    pub deposit_sigma: f64,
+    /// Chance a player guesses the answer
+    pub win_prob: f64,
+    /// Chance a player asks a question
+    pub question_prob: f64,
+    /// Chance a question is answered YES
+    pub yes_prob: f64,
+}
+
+/// Game configuration under test.
+pub struct Config {
+    pub payout: PayoutConfig,
+    /// Lamports; 0 = uncapped
+    pub rollover_cap: u64,
+    pub overflow_policy: OverflowPolicy,
+    /// Share of the evidence pool the operator allocates, in BPS
+    pub evidence_alloc_bps: u16,
+    /// Most evidence wallets paid per round
+    pub max_evidence: usize,
+    /// Every Nth round is a jackpot round; 0 = never
+    pub jackpot_every: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Settled,
+    Expired,
+}
+
+impl Outcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Settled => "settled",
+            Outcome::Expired => "expired",
+        }
+    }
+}
+
+/// One simulated round, in lamports.
+pub struct RoundResult {
+    pub round: u64,
+    pub players: u64,
+    pub deposits: u64,
+    pub rollover_in: u64,
+    /// Jackpot pool pulled into this round's rollover
+    pub jackpot_in: u64,
+    pub outcome: Outcome,
+    pub winners: usize,
+    pub evidence: usize,
+    pub pool: u64,
+    pub winner_paid: u64,
+    pub evidence_paid: u64,
+    pub treasury: u64,
+    /// Expire buyback plus rollover overflow sent to the buyback wallet
+    pub buyback: u64,
+    /// Rollover overflow sent to the jackpot pool
+    pub to_jackpot: u64,
+    pub jackpot_balance: u64,
+    pub rollover_out: u64,
+}
+
+impl RoundResult {
+    /// What players got back, less what they put in.
+    pub fn player_net(&self) -> i128 {
+        (self.winner_paid + self.evidence_paid) as i128 - self.deposits as i128
+    }
+}
+
+/// One run: a game starting empty, played for a number of rounds.
+pub struct Sim<'a, R> {
+    players: &'a Players,
+    config: &'a Config,
+    game_state: GameState,
+    jackpot_balance: u64,
+    round: u64,
+    rng: R,
+}
+
+impl<'a, R: Rng> Sim<'a, R> {
+    pub fn new(players: &'a Players, config: &'a Config, rng: R) -> Self {
+        Self {
+            players,
+            config,
+            game_state: game_state(config),
+            jackpot_balance: 0,
+            round: 0,
+            rng,
+        }
+    }
+
+    pub fn step(&mut self) -> Result<RoundResult> {
+        self.round += 1;
+        let jackpot_in = match self.config.jackpot_every {
+            n if n > 0 && self.round.is_multiple_of(n) => std::mem::take(&mut self.jackpot_balance),
+            _ => 0,
+        };
+        self.game_state.rollover_balance += jackpot_in;
+        let rollover_in = self.game_state.rollover_balance;
+
+        let players = dist::poisson(&mut self.rng, self.players.mean);
+        let mut deposits = 0;
+        let mut winners = 0;
+        let mut evidence = 0;
+        for _ in 0..players {
+            let sol = dist::log_normal(
+                &mut self.rng,
+                self.players.deposit_median,
+                self.players.deposit_sigma,
+            );
+            deposits += ((sol * LAMPORTS_PER_SOL as f64) as u64).max(1);
+            if self.rng.gen_bool(self.players.question_prob)
+                && self.rng.gen_bool(self.players.yes_prob)
+            {
+                evidence += 1;
+            }
+            if self.rng.gen_bool(self.players.win_prob) {
+                winners += 1;
+            }
+        }
+        let winners = winners.min(MAX_WINNERS);
+        let evidence = evidence.min(self.config.max_evidence);
+
+        let stakes = Stakes {
+            total_deposits: deposits,
+            rollover_in,
+            referred_deposits: 0,
+        };
+        let mut result = RoundResult {
+            round: self.round,
+            players,
+            deposits,
+            rollover_in,
+            jackpot_in,
+            outcome: Outcome::Expired,
+            winners,
+            evidence: 0,
+            pool: deposits + rollover_in,
+            winner_paid: 0,
+            evidence_paid: 0,
+            treasury: 0,
+            buyback: 0,
+            to_jackpot: 0,
+            jackpot_balance: 0,
+            rollover_out: 0,
+        };
+
+        let rollover = if winners > 0 {
+            let preview = payout::preview_settle(
+                &self.game_state,
+                &stakes,
+                winners,
+                TieBreakPolicy::EqualSplit,
+                &[],
+                &weights(evidence, self.config.evidence_alloc_bps),
+                None,
+            )?;
+            result.outcome = Outcome::Settled;
+            result.evidence = evidence;
+            result.pool = preview.pool;
+            result.winner_paid = preview.winner_amounts.iter().sum();
+            result.evidence_paid = preview.evidence_amounts.iter().sum();
+            result.treasury = preview.treasury_amount;
+            preview.rollover
+        } else {
+            let preview = payout::preview_expire(&self.game_state, &stakes)?;
+            result.treasury = preview.treasury_amount;
+            result.buyback = preview.buyback_amount;
+            preview.rollover
+        };
+
+        result.buyback += rollover.to_buyback;
+        result.to_jackpot = rollover.to_jackpot;
+        result.rollover_out = rollover.rollover_out;
+        self.jackpot_balance += rollover.to_jackpot;
+        self.game_state.rollover_balance = rollover.rollover_out;
+        result.jackpot_balance = self.jackpot_balance;
+        Ok(result)
+    }
+}
+
+/// `total` BPS split evenly over `n` entries, the remainder going to the
+/// first ones.
+fn weights(n: usize, total: u16) -> Vec<u16> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let (base, extra) = (total / n as u16, total as usize % n);
+    (0..n).map(|i| base + u16::from(i < extra)).collect()
+}
+
+/// A fresh `GameState` carrying only what the payout math reads.
+fn game_state(config: &Config) -> GameState {
+    GameState {
+        authority: Pubkey::default(),
+        treasury: Pubkey::default(),
+        buyback_wallet: Pubkey::default(),
+        current_round_id: 0,
+        bump: 0,
+        rollover_balance: 0,
+        rollover_cap: config.rollover_cap,
+        overflow_policy: config.overflow_policy,
+        guardian: Pubkey::default(),
+        paused: 0,
+        timelock_delay: 0,
+        change_count: 0,
+        payout: config.payout,
+        referral_bps: 0,
+        referral_liability: 0,
+        season_id: 0,
+        season_start_round_id: 0,
+        season_end_round_id: 0,
+        season_bps: 0,
+        swap_program: Pubkey::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const ROUNDS: u64 = 2000;
+
+    fn players() -> Players {
+        Players {
+            mean: 20.0,
+            deposit_median: 0.1,
+            deposit_sigma: 1.0,
+            win_prob: 0.02,
+            question_prob: 0.3,
+            yes_prob: 0.5,
+        }
+    }
+
+    fn config(overflow_policy: OverflowPolicy) -> Config {
+        Config {
+            payout: PayoutConfig::default(),
+            rollover_cap: 5 * LAMPORTS_PER_SOL,
+            overflow_policy,
+            // Leaves part of the evidence pool unallocated, to roll over
+            evidence_alloc_bps: 9000,
+            max_evidence: 10,
+            jackpot_every: 25,
+        }
+    }
+
+    /// Totals over a run, checking each round balances as it goes.
+    #[derive(Default)]
+    struct Totals {
+        deposits: u64,
+        paid_out: u64,
+        buyback: u64,
+        to_jackpot: u64,
+        settled: u64,
+        expired: u64,
+        final_rollover: u64,
+        final_jackpot: u64,
+    }
+
+    fn run(config: &Config, seed: u64) -> Totals {
+        let players = players();
+        let mut sim = Sim::new(&players, config, StdRng::seed_from_u64(seed));
+        let mut totals = Totals::default();
+        for _ in 0..ROUNDS {
+            let r = sim.step().unwrap();
+            let out = r.winner_paid + r.evidence_paid + r.treasury + r.buyback + r.to_jackpot + r.rollover_out;
+            assert_eq!(r.deposits + r.rollover_in, out, "round {} leaks lamports", r.round);
+            if config.rollover_cap > 0 {
+                assert!(r.rollover_out <= config.rollover_cap, "round {} rolls over past the cap", r.round);
+            }
+
+            totals.deposits += r.deposits;
+            totals.paid_out += r.winner_paid + r.evidence_paid + r.treasury;
+            totals.buyback += r.buyback;
+            totals.to_jackpot += r.to_jackpot;
+            match r.outcome {
+                Outcome::Settled => totals.settled += 1,
+                Outcome::Expired => totals.expired += 1,
+            }
+            totals.final_rollover = r.rollover_out;
+            totals.final_jackpot = r.jackpot_balance;
+        }
+        totals
+    }
+
+    #[test]
+    fn lamports_are_conserved_with_overflow_to_the_jackpot() {
+        let config = config(OverflowPolicy::Jackpot);
+        let totals = run(&config, 1);
+        assert!(totals.settled > 0 && totals.expired > 0);
+        assert!(totals.to_jackpot > 0, "the rollover cap never bound");
+        assert!(totals.buyback > 0);
+        assert_eq!(
+            totals.deposits,
+            totals.paid_out + totals.buyback + totals.final_rollover + totals.final_jackpot
+        );
+    }
+
+    #[test]
+    fn lamports_are_conserved_with_overflow_to_buyback() {
+        let config = config(OverflowPolicy::Buyback);
+        let totals = run(&config, 2);
+        assert!(totals.settled > 0 && totals.expired > 0);
+        assert_eq!(totals.to_jackpot, 0);
+        assert_eq!(totals.final_jackpot, 0);
+        assert_eq!(totals.deposits, totals.paid_out + totals.buyback + totals.final_rollover);
+    }
+
+    #[test]
+    fn lamports_are_conserved_uncapped() {
+        let config = Config {
+            rollover_cap: 0,
+            ..config(OverflowPolicy::Jackpot)
+        };
+        let totals = run(&config, 3);
+        assert_eq!(totals.to_jackpot, 0);
+        assert_eq!(totals.deposits, totals.paid_out + totals.buyback + totals.final_rollover);
+    }
+
+    #[test]
+    fn weights_spread_the_remainder_over_the_first_entries() {
+        assert_eq!(weights(3, 10000), vec![3334, 3333, 3333]);
+        assert_eq!(weights(0, 10000), Vec::<u16>::new());
+    }
+}
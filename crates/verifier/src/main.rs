@@ -0,0 +1,89 @@
+//! `alons-box-verifier` — checks a finished round end to end from chain data
+//! alone: the commit reveal, the payout split recomputed with the program's
+//! own math, the emitted event, the lamports actually moved and the archive
+//! leaf. The result is a JSON report, optionally signed by the verifier.
+
+mod report;
+mod verify;
+
+use std::path::PathBuf;
+
+use alons_box_client::rpc::{self, Commitment};
+use alons_box_client::Rpc;
+use anyhow::{anyhow, bail, Result};
+use clap::{Parser, Subcommand};
+use solana_keypair::read_keypair_file;
+
+#[derive(Parser)]
+#[command(name = "alons-box-verifier", version, about = "Verify finished Alon's Box rounds")]
+struct Cli {
+    /// RPC URL, or one of localnet, devnet, mainnet-beta
+    #[arg(long, short = 'u', global = true, env = "ALONS_BOX_RPC_URL", default_value = "localnet")]
+    url: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Verify a settled or expired round and write its JSON report
+    Round {
+        round_id: u64,
+        /// Read only transactions at this commitment: finalized or confirmed
+        #[arg(long, default_value = "finalized")]
+        commitment: Commitment,
+        /// Sign the report with this keypair
+        #[arg(long)]
+        sign: Option<PathBuf>,
+        /// Write the report to a file instead of stdout
+        #[arg(long, short = 'o')]
+        output: Option<PathBuf>,
+    },
+    /// Check the signature on a saved report
+    CheckReport { file: PathBuf },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Round {
+            round_id,
+            commitment,
+            sign,
+            output,
+        } => {
+            let signer = match sign {
+                Some(path) => Some(
+                    read_keypair_file(&path)
+                        .map_err(|e| anyhow!("cannot read keypair {}: {}", path.display(), e))?,
+                ),
+                None => None,
+            };
+            let rpc = Rpc::new(rpc::cluster_url(&cli.url));
+            let report = verify::verify(&rpc, round_id, commitment)?;
+            let passed = report["verdict"] == "pass";
+
+            let json = serde_json::to_string_pretty(&report::envelope(report, signer.as_ref())?)?;
+            match output {
+                Some(path) => std::fs::write(&path, json + "\n")?,
+                None => println!("{}", json),
+            }
+            if !passed {
+                bail!("round {} failed verification", round_id);
+            }
+        }
+        Command::CheckReport { file } => {
+            let envelope: serde_json::Value = serde_json::from_slice(&std::fs::read(&file)?)?;
+            let (signer, valid) = report::check_signature(&envelope)?;
+            if !valid {
+                bail!("invalid signature: the report was not signed by {} or was altered", signer);
+            }
+            println!(
+                "Valid signature by {} — round {}, verdict {}",
+                signer, envelope["report"]["round_id"], envelope["report"]["verdict"]
+            );
+        }
+    }
+    Ok(())
+}
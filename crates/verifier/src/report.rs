@@ -0,0 +1,143 @@
+//! Check results and the signed JSON envelope around a report.
+//!
+//! The signed message is the report object serialized compactly by
+//! `serde_json`, whose maps keep keys sorted, so re-serializing a parsed
+//! report reproduces the exact bytes that were signed.
+
+use std::str::FromStr;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use solana_keypair::Keypair;
+use solana_signature::Signature;
+use solana_signer::Signer;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    /// Nothing on chain to check against
+    Skip,
+}
+
+impl Status {
+    pub fn of(ok: bool) -> Self {
+        if ok {
+            Status::Pass
+        } else {
+            Status::Fail
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Fail => "fail",
+            Status::Skip => "skip",
+        }
+    }
+}
+
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+    /// Per-item breakdown (payout lines, balance changes)
+    pub rows: Vec<Value>,
+}
+
+impl Check {
+    pub fn new(name: &'static str, status: Status, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut check = json!({
+            "name": self.name,
+            "status": self.status.as_str(),
+            "detail": self.detail,
+        });
+        if !self.rows.is_empty() {
+            check["rows"] = Value::Array(self.rows.clone());
+        }
+        check
+    }
+}
+
+/// Wrap `report` for output, signed by `signer` if given.
+pub fn envelope(report: Value, signer: Option<&Keypair>) -> Result<Value> {
+    let Some(signer) = signer else {
+        return Ok(json!({ "report": report }));
+    };
+    let signature = signer.sign_message(serde_json::to_string(&report)?.as_bytes());
+    Ok(json!({
+        "report": report,
+        "signer": signer.pubkey().to_string(),
+        "signature": signature.to_string(),
+    }))
+}
+
+/// Signer of an enveloped report and whether its signature is valid.
+pub fn check_signature(envelope: &Value) -> Result<(Pubkey, bool)> {
+    let field = |name: &str| {
+        envelope[name]
+            .as_str()
+            .ok_or_else(|| anyhow!("report is not signed: no {} field", name))
+    };
+    let signer = Pubkey::from_str(field("signer")?)?;
+    let signature = Signature::from_str(field("signature")?)?;
+    let message = serde_json::to_string(&envelope["report"])?;
+    Ok((signer, signature.verify(signer.as_ref(), message.as_bytes())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Value {
+        json!({ "round_id": 7, "verdict": "pass", "checks": [{ "name": "commit", "status": "pass" }] })
+    }
+
+    #[test]
+    fn signed_report_verifies_against_its_signer() {
+        let signer = Keypair::new();
+        let envelope = envelope(report(), Some(&signer)).unwrap();
+        assert_eq!(check_signature(&envelope).unwrap(), (signer.pubkey(), true));
+    }
+
+    #[test]
+    fn signature_survives_a_print_and_parse() {
+        let signer = Keypair::new();
+        let printed = serde_json::to_string_pretty(&envelope(report(), Some(&signer)).unwrap()).unwrap();
+        let parsed: Value = serde_json::from_str(&printed).unwrap();
+        assert!(check_signature(&parsed).unwrap().1);
+    }
+
+    #[test]
+    fn tampered_report_fails_the_signature() {
+        let signer = Keypair::new();
+        let mut envelope = envelope(report(), Some(&signer)).unwrap();
+        envelope["report"]["verdict"] = json!("fail");
+        assert_eq!(check_signature(&envelope).unwrap(), (signer.pubkey(), false));
+    }
+
+    #[test]
+    fn swapped_signer_fails_the_signature() {
+        let mut envelope = envelope(report(), Some(&Keypair::new())).unwrap();
+        envelope["signer"] = json!(Keypair::new().pubkey().to_string());
+        assert!(!check_signature(&envelope).unwrap().1);
+    }
+
+    #[test]
+    fn unsigned_report_is_an_error() {
+        let envelope = envelope(report(), None).unwrap();
+        assert!(envelope.get("signature").is_none());
+        assert!(check_signature(&envelope).is_err());
+    }
+}
@@ -0,0 +1,921 @@
+//! Rebuild a finished round from chain data and check each step of it.
+//!
+//! The Settlement PDA is created by the instruction that finished the round
+//! and closed by `close_round`, so its signature history leads to both: the
+//! settle/expire transaction (its arguments, events and balance changes)
+//! and, once the round is closed, the `RoundArchived` event that committed
+//! the record to the archive.
+
+use std::collections::BTreeMap;
+
+use alons_box::archive::leaf_hash;
+use alons_box::events::{EmergencyExpired, RoundArchived, RoundExpired, RoundSettled, RoundSettledV2};
+use alons_box::state::*;
+use alons_box::{accounts, instruction, utils};
+use alons_box_client::commit::{answer_hash, commit_hash, to_hex};
+use alons_box_client::events;
+use alons_box_client::payout::{preview_expire, preview_settle, ExpirePreview, SettlePreview, Stakes};
+use alons_box_client::pda;
+use alons_box_client::rpc::{Commitment, ConfirmedTransaction, SignatureInfo, TransactionInstruction};
+use alons_box_client::Rpc;
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::{AnchorDeserialize, Discriminator, ToAccountMetas};
+use anyhow::{anyhow, bail, Result};
+use serde_json::{json, Value};
+
+use crate::report::{Check, Status};
+
+/// The instruction that finished the round, with its arguments.
+enum Finish {
+    Settle(instruction::Settle),
+    Expire(instruction::Expire),
+    EmergencyExpire,
+}
+
+/// Accounts of the finishing instruction that the checks read.
+struct FinishAccounts {
+    round: Pubkey,
+    treasury: Pubkey,
+    buyback_wallet: Pubkey,
+    season: Option<Pubkey>,
+    /// Accounts past the named ones; for settle, the winners, their Deposits
+    /// or Guesses under LargestDeposit or EarliestGuess, then (wallet,
+    /// Question) pairs, then any profiles
+    remaining: Vec<Pubkey>,
+}
+
+// Placeholder keys for the named accounts, so their positions can be read off
+// the program's own generated account lists
+const ROUND: u8 = 4;
+const TREASURY: u8 = 7;
+const BUYBACK_WALLET: u8 = 9;
+const SEASON: u8 = 11;
+
+fn placeholder(n: u8) -> Pubkey {
+    Pubkey::new_from_array([n; 32])
+}
+
+fn read_accounts(template: Vec<AccountMeta>, ix: &TransactionInstruction) -> Result<FinishAccounts> {
+    if ix.accounts.len() < template.len() {
+        bail!(
+            "instruction has {} accounts, expected at least {}",
+            ix.accounts.len(),
+            template.len()
+        );
+    }
+    let at = |n: u8| {
+        template
+            .iter()
+            .position(|meta| meta.pubkey == placeholder(n))
+            .map(|i| ix.accounts[i])
+    };
+    Ok(FinishAccounts {
+        round: at(ROUND).unwrap_or_default(),
+        treasury: at(TREASURY).unwrap_or_default(),
+        buyback_wallet: at(BUYBACK_WALLET).unwrap_or_default(),
+        // Anchor passes the program id for an omitted optional account
+        season: at(SEASON).filter(|key| *key != alons_box::ID),
+        remaining: ix.accounts[template.len()..].to_vec(),
+    })
+}
+
+/// Decode `ix` if it is a settle, expire or emergency_expire.
+fn decode_finish(ix: &TransactionInstruction) -> Result<Option<(Finish, FinishAccounts)>> {
+    if ix.program_id != alons_box::ID || ix.data.len() < 8 {
+        return Ok(None);
+    }
+    let (disc, mut args) = ix.data.split_at(8);
+    let p = placeholder;
+
+    if disc == instruction::Settle::DISCRIMINATOR {
+        let template = accounts::Settle {
+            authority: p(1),
+            game_state: p(2),
+            roles: p(3),
+            round: p(ROUND),
+            vault: p(5),
+            settlement: p(6),
+            treasury: p(TREASURY),
+            treasury_stream: p(8),
+            buyback_wallet: p(BUYBACK_WALLET),
+            jackpot_pool: p(10),
+            season: Some(p(SEASON)),
+            system_program: p(12),
+        };
+        let settle = instruction::Settle::deserialize(&mut args)?;
+        return Ok(Some((Finish::Settle(settle), read_accounts(template.to_account_metas(None), ix)?)));
+    }
+    if disc == instruction::Expire::DISCRIMINATOR {
+        let template = accounts::Expire {
+            authority: p(1),
+            game_state: p(2),
+            roles: p(3),
+            round: p(ROUND),
+            vault: p(5),
+            settlement: p(6),
+            treasury: p(TREASURY),
+            treasury_stream: p(8),
+            buyback_wallet: p(BUYBACK_WALLET),
+            jackpot_pool: p(10),
+            season: Some(p(SEASON)),
+            system_program: p(12),
+        };
+        let expire = instruction::Expire::deserialize(&mut args)?;
+        return Ok(Some((Finish::Expire(expire), read_accounts(template.to_account_metas(None), ix)?)));
+    }
+    if disc == instruction::EmergencyExpire::DISCRIMINATOR {
+        let template = accounts::EmergencyExpire {
+            caller: p(1),
+            game_state: p(2),
+            round: p(ROUND),
+            vault: p(5),
+            settlement: p(6),
+            treasury: p(TREASURY),
+            treasury_stream: p(8),
+            buyback_wallet: p(BUYBACK_WALLET),
+            jackpot_pool: p(10),
+            season: Some(p(SEASON)),
+            system_program: p(12),
+        };
+        return Ok(Some((Finish::EmergencyExpire, read_accounts(template.to_account_metas(None), ix)?)));
+    }
+    Ok(None)
+}
+
+struct History {
+    signature: String,
+    tx: ConfirmedTransaction,
+    finish: Finish,
+    accounts: FinishAccounts,
+    archived: Option<RoundArchived>,
+}
+
+/// Walk the Settlement PDA's transactions oldest first.
+fn history(rpc: &Rpc, round_id: u64, commitment: Commitment) -> Result<History> {
+    let settlement = pda::settlement(round_id);
+    let round = pda::round(round_id);
+
+    let mut signatures = Vec::new();
+    loop {
+        let before = signatures.last().map(|s: &SignatureInfo| s.signature.clone());
+        let page = rpc.signatures_for(&settlement, before.as_deref(), None, 1000, commitment)?;
+        let done = page.len() < 1000;
+        signatures.extend(page);
+        if done {
+            break;
+        }
+    }
+
+    let mut found: Option<History> = None;
+    for sig in signatures.iter().rev().filter(|s| !s.failed) {
+        let tx = rpc
+            .transaction(&sig.signature, commitment)?
+            .ok_or_else(|| anyhow!("transaction {} not found", sig.signature))?;
+        match &mut found {
+            None => {
+                let mut finish = None;
+                for ix in &tx.instructions {
+                    if let Some((f, accounts)) = decode_finish(ix)? {
+                        if accounts.round == round {
+                            finish = Some((f, accounts));
+                            break;
+                        }
+                    }
+                }
+                if let Some((finish, accounts)) = finish {
+                    found = Some(History {
+                        signature: sig.signature.clone(),
+                        tx,
+                        finish,
+                        accounts,
+                        archived: None,
+                    });
+                }
+            }
+            Some(history) => {
+                history.archived = events::find::<RoundArchived>(&tx.logs)
+                    .into_iter()
+                    .find(|e| e.summary.round_id == round_id);
+                if history.archived.is_some() {
+                    break;
+                }
+            }
+        }
+    }
+
+    found.ok_or_else(|| {
+        let status = match rpc.account::<Round>(&round) {
+            Ok(Some(round)) => status_name(round.status),
+            _ => "unknown",
+        };
+        anyhow!(
+            "no settle, expire or emergency_expire transaction found for round {} (status: {})",
+            round_id,
+            status
+        )
+    })
+}
+
+fn status_name(status: RoundStatus) -> &'static str {
+    match status {
+        RoundStatus::Active => "active",
+        RoundStatus::Settled => "settled",
+        RoundStatus::Expired => "expired",
+        RoundStatus::Refunding => "refunding",
+    }
+}
+
+fn tie_break_name(policy: TieBreakPolicy) -> &'static str {
+    match policy {
+        TieBreakPolicy::EqualSplit => "equal_split",
+        TieBreakPolicy::EarliestGuess => "earliest_guess",
+        TieBreakPolicy::LargestDeposit => "largest_deposit",
+    }
+}
+
+fn summary_json(summary: &RoundSummary) -> Value {
+    json!({
+        "outcome": status_name(summary.outcome),
+        "commit_hash": to_hex(&summary.commit_hash),
+        "answer_hash": to_hex(&summary.answer_hash),
+        "pool": summary.pool,
+        "rollover_in": summary.rollover_in,
+        "rollover_out": summary.rollover_out,
+        "winners": summary.winners.iter().map(Pubkey::to_string).collect::<Vec<_>>(),
+        "winner_amounts": summary.winner_amounts,
+        "evidence_total": summary.evidence_total,
+        "treasury_amount": summary.treasury_amount,
+        "buyback_amount": summary.buyback_amount,
+        "jackpot_amount": summary.jackpot_amount,
+        "season_amount": summary.season_amount,
+        "finalized_at": summary.finalized_at,
+    })
+}
+
+impl Finish {
+    /// The answer and salt the instruction revealed, if any.
+    fn reveal(&self) -> Option<(&str, &str)> {
+        match self {
+            Finish::Settle(args) => Some((args.answer.as_str(), args.salt.as_str())),
+            Finish::Expire(args) => Some((args.answer.as_str(), args.salt.as_str())),
+            Finish::EmergencyExpire => None,
+        }
+    }
+}
+
+/// The revealed answer and salt against the commit and answer hashes.
+fn reveal_checks(reveal: Option<(&str, &str)>, record: &RoundSummary) -> Vec<Check> {
+    match reveal {
+        Some((answer, salt)) => {
+            let commit = commit_hash(answer, salt);
+            let answer = answer_hash(answer);
+            vec![
+                Check::new(
+                    "commit",
+                    Status::of(commit == record.commit_hash),
+                    format!(
+                        "SHA-256(answer:salt) = {}, committed {}",
+                        to_hex(&commit),
+                        to_hex(&record.commit_hash)
+                    ),
+                ),
+                Check::new(
+                    "answer_hash",
+                    Status::of(answer == record.answer_hash),
+                    format!("SHA-256(answer) = {}, recorded {}", to_hex(&answer), to_hex(&record.answer_hash)),
+                ),
+            ]
+        }
+        None => vec![
+            Check::new(
+                "commit",
+                Status::Skip,
+                "emergency expiry: the answer was never revealed",
+            ),
+            Check::new(
+                "answer_hash",
+                Status::of(record.answer_hash == [0u8; 32]),
+                "emergency expiry records a zeroed answer hash",
+            ),
+        ],
+    }
+}
+
+/// A settle preview against the record.
+fn settle_lines(preview: &SettlePreview, record: &RoundSummary) -> Vec<(String, u64, u64)> {
+    let mut lines = vec![("pool".to_string(), preview.pool, record.pool)];
+    for (i, (&expected, winner)) in preview.winner_amounts.iter().zip(&record.winners).enumerate() {
+        let recorded = record.winner_amounts.get(i).copied().unwrap_or_default();
+        lines.push((format!("winner {}", winner), expected, recorded));
+    }
+    lines.push((
+        "evidence total".to_string(),
+        preview.evidence_amounts.iter().sum(),
+        record.evidence_total,
+    ));
+    lines.push(("treasury".to_string(), preview.treasury_amount, record.treasury_amount));
+    lines.push(("season".to_string(), preview.season_amount, record.season_amount));
+    let rollover = &preview.rollover;
+    lines.push((
+        "rollover before cap".to_string(),
+        rollover.rollover_out + rollover.to_jackpot + rollover.to_buyback,
+        record.rollover_out + record.jackpot_amount + record.buyback_amount,
+    ));
+    lines
+}
+
+/// An expire preview against the record.
+fn expire_lines(preview: &ExpirePreview, record: &RoundSummary) -> Vec<(String, u64, u64)> {
+    let rollover = &preview.rollover;
+    vec![
+        ("pool".to_string(), preview.total_deposits + preview.rollover_in, record.pool),
+        ("treasury".to_string(), preview.treasury_amount, record.treasury_amount),
+        (
+            "buyback and rollover".to_string(),
+            preview.buyback_amount + rollover.rollover_out + rollover.to_jackpot + rollover.to_buyback,
+            record.buyback_amount + record.rollover_out + record.jackpot_amount,
+        ),
+    ]
+}
+
+/// Recomputed-versus-recorded lines of a check; passes if every line agrees.
+fn compare(name: &'static str, detail: String, lines: Vec<(String, u64, u64)>) -> Check {
+    let mut check = Check::new(name, Status::of(lines.iter().all(|(_, e, r)| e == r)), detail);
+    check.rows = lines
+        .into_iter()
+        .map(|(item, expected, recorded)| json!({ "item": item, "expected": expected, "recorded": recorded }))
+        .collect();
+    check
+}
+
+/// A payee's expected balance change in the finishing transaction.
+struct Payee {
+    roles: Vec<String>,
+    amount: u64,
+    /// The amount is an upper bound: the treasury's cut may keep its
+    /// referral slice in the vault, or vest in the TreasuryStream
+    at_most: bool,
+}
+
+fn add_payee(payees: &mut BTreeMap<Pubkey, Payee>, key: Pubkey, role: String, amount: u64, at_most: bool) {
+    let payee = payees.entry(key).or_insert(Payee {
+        roles: Vec::new(),
+        amount: 0,
+        at_most: false,
+    });
+    payee.roles.push(role);
+    payee.amount += amount;
+    payee.at_most |= at_most;
+}
+
+fn transfers(tx: &ConfirmedTransaction, payees: &BTreeMap<Pubkey, Payee>) -> Check {
+    let fee_payer = tx.account_keys.first().copied().unwrap_or_default();
+    let mut ok = true;
+    let mut received: i64 = 0;
+    let mut rows = Vec::new();
+
+    for (key, payee) in payees {
+        let actual = tx.balance_change(key);
+        let status = match actual {
+            // Its change also carries the fee and the Settlement rent
+            _ if *key == fee_payer => Status::Skip,
+            Some(actual) if payee.at_most => Status::of(actual >= 0 && actual as u64 <= payee.amount),
+            Some(actual) => Status::of(actual == payee.amount as i64),
+            None => Status::of(payee.amount == 0),
+        };
+        if status == Status::Fail {
+            ok = false;
+        }
+        // The fee payer's own change cannot show its share, so take it as paid
+        received += match status {
+            Status::Skip => payee.amount as i64,
+            _ => actual.unwrap_or_default(),
+        };
+        rows.push(json!({
+            "account": key.to_string(),
+            "role": payee.roles.join(", "),
+            "expected": payee.amount,
+            "at_most": payee.at_most,
+            "actual": actual,
+            "status": status.as_str(),
+        }));
+    }
+
+    // Whatever the payees received left the vault, and nothing else did
+    let vault = tx.balance_change(&pda::vault()).unwrap_or_default();
+    let status = Status::of(vault == -received);
+    ok &= status == Status::Pass;
+    rows.push(json!({
+        "account": pda::vault().to_string(),
+        "role": "vault",
+        "expected": -received,
+        "at_most": false,
+        "actual": vault,
+        "status": status.as_str(),
+    }));
+
+    let mut check = Check::new(
+        "transfers",
+        Status::of(ok),
+        "balance changes in the finishing transaction",
+    );
+    check.rows = rows;
+    check
+}
+
+/// Verify round `round_id` and build its report.
+pub fn verify(rpc: &Rpc, round_id: u64, commitment: Commitment) -> Result<Value> {
+    let history = history(rpc, round_id, commitment)?;
+    let tx = &history.tx;
+    let accounts = &history.accounts;
+
+    let settlement = rpc.account::<Settlement>(&pda::settlement(round_id))?;
+    let record = match (&settlement, &history.archived) {
+        (Some(settlement), _) => settlement.summary(),
+        (None, Some(archived)) => archived.summary.clone(),
+        (None, None) => bail!("round {} has neither a Settlement account nor a RoundArchived event", round_id),
+    };
+    let round = rpc.account::<Round>(&pda::round(round_id))?;
+    let gs: GameState = rpc.require_account(&pda::game_state(), "GameState")?;
+    let payout = gs.payout;
+
+    let mut checks = Vec::new();
+
+    // ── Commit ──
+    let reveal = history.finish.reveal();
+    checks.extend(reveal_checks(reveal, &record));
+
+    // ── Pool ──
+    let stakes = match &round {
+        Some(round) => {
+            let stakes = Stakes::from(round);
+            checks.push(compare(
+                "pool",
+                "Round account against the settlement record".to_string(),
+                vec![
+                    ("rollover_in".to_string(), stakes.rollover_in, record.rollover_in),
+                    (
+                        "pool".to_string(),
+                        stakes.total_deposits.saturating_add(stakes.rollover_in),
+                        record.pool,
+                    ),
+                ],
+            ));
+            stakes
+        }
+        None => {
+            checks.push(Check::new(
+                "pool",
+                Status::Skip,
+                "Round account closed; total deposits taken as pool - rollover_in",
+            ));
+            Stakes {
+                total_deposits: record.pool.saturating_sub(record.rollover_in),
+                rollover_in: record.rollover_in,
+                referred_deposits: 0,
+            }
+        }
+    };
+
+    // ── Payout split, recomputed with the program's own BPS math ──
+    let config = format!(
+        "recomputed from total_deposits {} and rollover_in {} with the current GameState payout \
+         (a payout change executed since the round would also show as a mismatch)",
+        stakes.total_deposits, stakes.rollover_in
+    );
+    let mut payees = BTreeMap::new();
+    match &history.finish {
+        Finish::Settle(args) => {
+            let winner_count = args.winner_count as usize;
+            let per_winner_len = match args.tie_break {
+                TieBreakPolicy::EqualSplit => 0,
+                TieBreakPolicy::LargestDeposit | TieBreakPolicy::EarliestGuess => winner_count,
+            };
+            let winners = accounts.remaining.get(..winner_count).unwrap_or_default();
+            checks.push(Check::new(
+                "winners",
+                Status::of(winners == record.winners.as_slice()),
+                format!("{} winner(s) passed to settle, tie break {}", winner_count, tie_break_name(args.tie_break)),
+            ));
+
+            let season_bps = match accounts.season {
+                Some(key) => Some(
+                    rpc.account::<Season>(&key)?
+                        .ok_or_else(|| anyhow!("Season {} passed to settle no longer exists", key))?
+                        .season_bps,
+                ),
+                None => None,
+            };
+            // Split by the winners' Deposits or Guesses, which close_deposit
+            // or close_guess may have closed since
+            let per_winner_keys = accounts
+                .remaining
+                .get(winner_count..winner_count + per_winner_len)
+                .unwrap_or_default();
+            let mut per_winner = Vec::with_capacity(per_winner_len);
+            for key in per_winner_keys {
+                let value = match args.tie_break {
+                    TieBreakPolicy::EarliestGuess => rpc.account::<Guess>(key)?.map(|guess| guess.slot),
+                    _ => rpc.account::<Deposit>(key)?.map(|deposit| deposit.amount),
+                };
+                per_winner.extend(value);
+            }
+
+            let evidence_amounts = if per_winner.len() == per_winner_len {
+                let preview = preview_settle(
+                    &gs,
+                    &stakes,
+                    winner_count,
+                    args.tie_break,
+                    &per_winner,
+                    &args.evidence_weights,
+                    season_bps,
+                )?;
+                checks.push(compare("payout", config, settle_lines(&preview, &record)));
+                preview.evidence_amounts
+            } else {
+                checks.push(Check::new(
+                    "payout",
+                    Status::Skip,
+                    "tie-break split: the winners' Deposit or Guess accounts are closed",
+                ));
+                utils::bps_of(record.pool, gs.payout.evidence_bps)
+                    .and_then(|pool| utils::bps_shares(pool, &args.evidence_weights))
+                    .map_err(|e| anyhow!("{}", e))?
+            };
+
+            for (winner, &amount) in record.winners.iter().zip(&record.winner_amounts) {
+                add_payee(&mut payees, *winner, "winner".to_string(), amount, false);
+            }
+            let evidence = accounts.remaining.get(winner_count + per_winner_len..).unwrap_or_default();
+            for (pair, &amount) in evidence.chunks(2).zip(&evidence_amounts) {
+                add_payee(&mut payees, pair[0], "evidence".to_string(), amount, false);
+            }
+            if let Some(season) = accounts.season {
+                add_payee(&mut payees, season, "season".to_string(), record.season_amount, false);
+            }
+        }
+        Finish::Expire(_) | Finish::EmergencyExpire => {
+            let preview = preview_expire(&gs, &stakes)?;
+            checks.push(compare("payout", config, expire_lines(&preview, &record)));
+        }
+    }
+    add_payee(&mut payees, accounts.treasury, "treasury".to_string(), record.treasury_amount, true);
+    add_payee(&mut payees, accounts.buyback_wallet, "buyback".to_string(), record.buyback_amount, false);
+    add_payee(&mut payees, pda::jackpot_pool(), "jackpot pool".to_string(), record.jackpot_amount, false);
+
+    // ── Event ──
+    checks.push(event_check(&history, &record, round_id));
+
+    // ── Lamports actually moved ──
+    checks.push(transfers(tx, &payees));
+
+    // ── Archive ──
+    checks.push(match &history.archived {
+        // The leaf must hash the archived summary and, while the Settlement
+        // account exists, the record it holds
+        Some(archived) => Check::new(
+            "archive",
+            Status::of(leaf_hash(&archived.summary)? == archived.leaf && leaf_hash(&record)? == archived.leaf),
+            format!(
+                "leaf {} = {}, archive root {} when the round closed",
+                archived.leaf_index,
+                to_hex(&archived.leaf),
+                to_hex(&archived.root)
+            ),
+        ),
+        None => Check::new(
+            "archive",
+            Status::Skip,
+            "round not closed yet; the Settlement account is the record",
+        ),
+    });
+
+    let passed = checks.iter().all(|c| c.status != Status::Fail);
+    let outcome = match history.finish {
+        Finish::Settle(_) => "settled",
+        Finish::Expire(_) => "expired",
+        Finish::EmergencyExpire => "emergency_expired",
+    };
+    Ok(json!({
+        "round_id": round_id,
+        "program_id": alons_box::ID.to_string(),
+        "commitment": commitment.as_str(),
+        "outcome": outcome,
+        "transaction": {
+            "signature": history.signature,
+            "slot": tx.slot,
+            "block_time": tx.block_time,
+        },
+        "reveal": reveal.map(|(answer, salt)| json!({ "answer": answer, "salt": salt })),
+        "record": summary_json(&record),
+        "payout_config": {
+            "winner_bps": payout.winner_bps,
+            "evidence_bps": payout.evidence_bps,
+            "treasury_bps": payout.treasury_bps,
+            "expire_buyback_bps": payout.expire_buyback_bps,
+            "expire_treasury_bps": payout.expire_treasury_bps,
+        },
+        "checks": checks.iter().map(Check::to_json).collect::<Vec<_>>(),
+        "verdict": if passed { "pass" } else { "fail" },
+    }))
+}
+
+/// The event the finishing instruction emitted agrees with the record.
+fn event_check(history: &History, record: &RoundSummary, round_id: u64) -> Check {
+    let logs = &history.tx.logs;
+    let lines = match &history.finish {
+        Finish::Settle(_) => {
+            if let Some(e) = events::find::<RoundSettledV2>(logs).into_iter().find(|e| e.round_id == round_id) {
+                let mut lines = vec![
+                    ("pool".to_string(), e.pool, record.pool),
+                    ("evidence total".to_string(), e.evidence_total, record.evidence_total),
+                    ("treasury".to_string(), e.treasury_amount, record.treasury_amount),
+                    ("rollover out".to_string(), e.rollover_out, record.rollover_out),
+                    ("winners".to_string(), e.winners.len() as u64, record.winners.len() as u64),
+                ];
+                for (i, (winner, &amount)) in e.winners.iter().zip(&e.winner_amounts).enumerate() {
+                    let recorded = match record.winners.get(i) {
+                        Some(w) if w == winner => record.winner_amounts.get(i).copied().unwrap_or_default(),
+                        _ => 0,
+                    };
+                    lines.push((format!("winner {}", winner), amount, recorded));
+                }
+                Some(lines)
+            } else {
+                events::find::<RoundSettled>(logs)
+                    .into_iter()
+                    .find(|e| e.round_id == round_id)
+                    .map(|e| {
+                        vec![
+                            ("pool".to_string(), e.pool, record.pool),
+                            (
+                                format!("winner {}", e.winner),
+                                e.winner_amount,
+                                record.winner_amounts.first().copied().unwrap_or_default(),
+                            ),
+                            ("treasury".to_string(), e.treasury_amount, record.treasury_amount),
+                            ("rollover out".to_string(), e.rollover_out, record.rollover_out),
+                        ]
+                    })
+            }
+        }
+        Finish::Expire(_) => events::find::<RoundExpired>(logs)
+            .into_iter()
+            .find(|e| e.round_id == round_id)
+            .map(|e| {
+                vec![
+                    ("pool".to_string(), e.pool, record.pool),
+                    ("treasury".to_string(), e.treasury_amount, record.treasury_amount),
+                    ("rollover out".to_string(), e.rollover_out, record.rollover_out),
+                ]
+            }),
+        Finish::EmergencyExpire => events::find::<EmergencyExpired>(logs)
+            .into_iter()
+            .find(|e| e.round_id == round_id)
+            .map(|e| {
+                vec![
+                    ("pool".to_string(), e.pool, record.pool),
+                    ("treasury".to_string(), e.treasury_amount, record.treasury_amount),
+                    ("rollover out".to_string(), e.rollover_out, record.rollover_out),
+                ]
+            }),
+    };
+    match lines {
+        Some(lines) => compare("event", "emitted event against the settlement record".to_string(), lines),
+        None => Check::new("event", Status::Fail, "the finishing transaction emitted no outcome event"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alons_box_client::payout::CappedRollover;
+    use alons_box_client::tx;
+
+    fn key(n: u8) -> Pubkey {
+        Pubkey::new_from_array([100 + n; 32])
+    }
+
+    fn settle_ix(season: Option<Pubkey>, remaining: &[Pubkey]) -> TransactionInstruction {
+        let ix = tx::instruction(
+            accounts::Settle {
+                authority: key(1),
+                game_state: key(2),
+                roles: key(3),
+                round: key(4),
+                vault: pda::vault(),
+                settlement: key(6),
+                treasury: key(7),
+                treasury_stream: key(8),
+                buyback_wallet: key(9),
+                jackpot_pool: key(10),
+                season,
+                system_program: key(12),
+            },
+            instruction::Settle {
+                answer: "red panda".to_string(),
+                salt: "s4lt".to_string(),
+                winner_count: 1,
+                evidence_weights: vec![3, 1],
+                tie_break: TieBreakPolicy::EqualSplit,
+            },
+        );
+        let mut accounts: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        accounts.extend_from_slice(remaining);
+        TransactionInstruction { program_id: ix.program_id, accounts, data: ix.data }
+    }
+
+    fn record(answer: &str, salt: &str) -> RoundSummary {
+        RoundSummary {
+            round_id: 7,
+            outcome: RoundStatus::Settled,
+            commit_hash: commit_hash(answer, salt),
+            answer_hash: answer_hash(answer),
+            pool: 1_000,
+            rollover_in: 0,
+            rollover_out: 80,
+            winners: vec![key(20)],
+            winner_amounts: vec![500],
+            evidence_total: 300,
+            treasury_amount: 100,
+            buyback_amount: 10,
+            jackpot_amount: 10,
+            season_amount: 0,
+            finalized_at: 1_700_000_000,
+        }
+    }
+
+    fn transaction(changes: &[(Pubkey, i64)]) -> ConfirmedTransaction {
+        let base = 10_000_000u64;
+        ConfirmedTransaction {
+            slot: 1,
+            block_time: None,
+            failed: false,
+            fee: 5_000,
+            account_keys: changes.iter().map(|(key, _)| *key).collect(),
+            pre_balances: vec![base; changes.len()],
+            post_balances: changes.iter().map(|(_, change)| (base as i64 + change) as u64).collect(),
+            instructions: Vec::new(),
+            logs: Vec::new(),
+        }
+    }
+
+    fn payees(entries: &[(Pubkey, &str, u64, bool)]) -> BTreeMap<Pubkey, Payee> {
+        let mut payees = BTreeMap::new();
+        for &(key, role, amount, at_most) in entries {
+            add_payee(&mut payees, key, role.to_string(), amount, at_most);
+        }
+        payees
+    }
+
+    #[test]
+    fn settle_decodes_its_reveal_and_named_accounts() {
+        let winner = key(20);
+        let ix = settle_ix(Some(key(11)), &[winner]);
+        let (finish, accounts) = decode_finish(&ix).unwrap().unwrap();
+        assert_eq!(finish.reveal(), Some(("red panda", "s4lt")));
+        let Finish::Settle(args) = finish else { panic!("not a settle") };
+        assert_eq!(args.evidence_weights, vec![3, 1]);
+        assert_eq!(accounts.round, key(4));
+        assert_eq!(accounts.treasury, key(7));
+        assert_eq!(accounts.buyback_wallet, key(9));
+        assert_eq!(accounts.season, Some(key(11)));
+        assert_eq!(accounts.remaining, vec![winner]);
+    }
+
+    #[test]
+    fn omitted_season_decodes_as_none() {
+        let (_, accounts) = decode_finish(&settle_ix(None, &[])).unwrap().unwrap();
+        assert_eq!(accounts.season, None);
+    }
+
+    #[test]
+    fn other_programs_and_short_account_lists_are_rejected() {
+        let mut ix = settle_ix(None, &[]);
+        ix.program_id = key(0);
+        assert!(decode_finish(&ix).unwrap().is_none());
+
+        let mut ix = settle_ix(None, &[]);
+        ix.accounts.truncate(5);
+        assert!(decode_finish(&ix).is_err());
+    }
+
+    #[test]
+    fn reveal_matching_the_commit_passes() {
+        let checks = reveal_checks(Some(("red panda", "s4lt")), &record("red panda", "s4lt"));
+        assert!(checks.iter().all(|check| check.status == Status::Pass));
+    }
+
+    #[test]
+    fn reveal_with_another_salt_fails_only_the_commit() {
+        let checks = reveal_checks(Some(("red panda", "other")), &record("red panda", "s4lt"));
+        assert_eq!(checks[0].name, "commit");
+        assert!(checks[0].status == Status::Fail);
+        assert!(checks[1].status == Status::Pass);
+    }
+
+    #[test]
+    fn emergency_expiry_skips_the_commit_and_wants_a_zeroed_answer_hash() {
+        let mut record = record("red panda", "s4lt");
+        let checks = reveal_checks(None, &record);
+        assert!(checks[0].status == Status::Skip);
+        assert!(checks[1].status == Status::Fail);
+
+        record.answer_hash = [0; 32];
+        assert!(reveal_checks(None, &record)[1].status == Status::Pass);
+    }
+
+    #[test]
+    fn settle_payout_matches_its_record() {
+        let preview = SettlePreview {
+            pool: 1_000,
+            winner_amounts: vec![500],
+            evidence_amounts: vec![225, 75],
+            treasury_amount: 100,
+            referral_amount: 0,
+            season_amount: 0,
+            rollover: CappedRollover { rollover_out: 80, to_jackpot: 10, to_buyback: 10 },
+        };
+        let record = record("red panda", "s4lt");
+        let check = compare("payout", String::new(), settle_lines(&preview, &record));
+        assert!(check.status == Status::Pass);
+
+        let short = SettlePreview { winner_amounts: vec![499], ..preview };
+        let check = compare("payout", String::new(), settle_lines(&short, &record));
+        assert!(check.status == Status::Fail);
+        assert_eq!(check.rows[1]["expected"], json!(499));
+        assert_eq!(check.rows[1]["recorded"], json!(500));
+    }
+
+    #[test]
+    fn expire_payout_folds_buyback_and_rollover_together() {
+        let preview = ExpirePreview {
+            total_deposits: 1_000,
+            rollover_in: 0,
+            buyback_amount: 50,
+            treasury_amount: 100,
+            referral_amount: 0,
+            rollover: CappedRollover { rollover_out: 800, to_jackpot: 40, to_buyback: 10 },
+        };
+        let record = RoundSummary {
+            winners: Vec::new(),
+            winner_amounts: Vec::new(),
+            evidence_total: 0,
+            buyback_amount: 60,
+            jackpot_amount: 40,
+            rollover_out: 800,
+            ..record("red panda", "s4lt")
+        };
+        let check = compare("payout", String::new(), expire_lines(&preview, &record));
+        assert!(check.status == Status::Pass);
+    }
+
+    #[test]
+    fn transfers_out_of_the_vault_to_each_payee_pass() {
+        let (fee_payer, winner, treasury) = (key(1), key(20), key(7));
+        let tx = transaction(&[(fee_payer, -5_000), (pda::vault(), -600), (winner, 500), (treasury, 100)]);
+        let payees = payees(&[(winner, "winner", 500, false), (treasury, "treasury", 100, true)]);
+        assert!(transfers(&tx, &payees).status == Status::Pass);
+    }
+
+    #[test]
+    fn treasury_may_receive_less_than_its_cut_but_not_more() {
+        let (fee_payer, winner, treasury) = (key(1), key(20), key(7));
+        let payees = payees(&[(winner, "winner", 500, false), (treasury, "treasury", 100, true)]);
+
+        let vested = transaction(&[(fee_payer, -5_000), (pda::vault(), -540), (winner, 500), (treasury, 40)]);
+        assert!(transfers(&vested, &payees).status == Status::Pass);
+
+        let over = transaction(&[(fee_payer, -5_000), (pda::vault(), -620), (winner, 500), (treasury, 120)]);
+        assert!(transfers(&over, &payees).status == Status::Fail);
+    }
+
+    #[test]
+    fn wrong_payee_amount_fails() {
+        let (fee_payer, winner) = (key(1), key(20));
+        let tx = transaction(&[(fee_payer, -5_000), (pda::vault(), -499), (winner, 499)]);
+        let check = transfers(&tx, &payees(&[(winner, "winner", 500, false)]));
+        assert!(check.status == Status::Fail);
+        assert_eq!(check.rows[0]["status"], json!("fail"));
+    }
+
+    #[test]
+    fn vault_losing_more_than_the_payees_received_fails() {
+        let (fee_payer, winner) = (key(1), key(20));
+        let tx = transaction(&[(fee_payer, -5_000), (pda::vault(), -700), (winner, 500)]);
+        let check = transfers(&tx, &payees(&[(winner, "winner", 500, false)]));
+        assert!(check.status == Status::Fail);
+        assert_eq!(check.rows.last().unwrap()["role"], json!("vault"));
+    }
+
+    #[test]
+    fn fee_payer_payee_is_skipped() {
+        let (authority, winner) = (key(1), key(20));
+        let tx = transaction(&[(authority, -5_000 + 200), (pda::vault(), -700), (winner, 500)]);
+        let payees = payees(&[(authority, "evidence", 200, false), (winner, "winner", 500, false)]);
+        let check = transfers(&tx, &payees);
+        assert_eq!(check.rows[0]["status"], json!("skip"));
+        assert!(check.status == Status::Pass);
+    }
+}
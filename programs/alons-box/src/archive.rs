@@ -0,0 +1,264 @@
+//! Incremental Merkle accumulator behind the `RoundArchive` PDA.
+//!
+//! Leaves are `SHA-256(0x00 || borsh(RoundSummary))`, interior nodes are
+//! `SHA-256(0x01 || left || right)`, and empty subtrees hash up from an
+//! all-zero leaf. On-chain only the frontier is kept; the off-chain helpers
+//! below rebuild inclusion proofs from the ordered leaf list (replayed from
+//! `RoundArchived` events) and check them against `RoundArchive.root`.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use crate::errors::AlonsBoxError;
+use crate::state::{RoundArchive, RoundSummary, ARCHIVE_DEPTH};
+
+const LEAF_PREFIX: &[u8] = &[0x00];
+const NODE_PREFIX: &[u8] = &[0x01];
+
+/// Leaf hash committed for a closed round.
+pub fn leaf_hash(summary: &RoundSummary) -> Result<[u8; 32]> {
+    let mut data = Vec::new();
+    summary.serialize(&mut data)?;
+    Ok(hashv(&[LEAF_PREFIX, &data]).to_bytes())
+}
+
+/// Interior node hash.
+pub fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hashv(&[NODE_PREFIX, left, right]).to_bytes()
+}
+
+impl RoundArchive {
+    /// Append a leaf and recompute the root. Returns the leaf index.
+    pub fn append(&mut self, leaf: [u8; 32]) -> Result<u64> {
+        let index = self.leaf_count;
+        require!(index < 1u64 << ARCHIVE_DEPTH, AlonsBoxError::ArchiveFull);
+
+        let mut node = leaf;
+        let mut zero = [0u8; 32];
+        let mut position = index;
+        for level in 0..ARCHIVE_DEPTH {
+            if position & 1 == 0 {
+                // Left child: remember it, pair with an empty right subtree
+                self.frontier[level] = node;
+                node = node_hash(&node, &zero);
+            } else {
+                // Right child: the left sibling is complete and already stored
+                node = node_hash(&self.frontier[level], &node);
+            }
+            zero = node_hash(&zero, &zero);
+            position >>= 1;
+        }
+
+        self.root = node;
+        self.leaf_count = index + 1;
+        Ok(index)
+    }
+}
+
+/// Proof that one round summary is a leaf of the archive.
+#[cfg(not(target_os = "solana"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InclusionProof {
+    pub leaf_index: u64,
+    pub leaf: [u8; 32],
+    /// Sibling hashes from the leaf level up, `ARCHIVE_DEPTH` entries
+    pub siblings: Vec<[u8; 32]>,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl InclusionProof {
+    /// True if the proof folds up to `root`.
+    pub fn verify(&self, root: &[u8; 32]) -> bool {
+        if self.siblings.len() != ARCHIVE_DEPTH {
+            return false;
+        }
+        let mut node = self.leaf;
+        let mut position = self.leaf_index;
+        for sibling in &self.siblings {
+            node = if position & 1 == 0 {
+                node_hash(&node, sibling)
+            } else {
+                node_hash(sibling, &node)
+            };
+            position >>= 1;
+        }
+        position == 0 && node == *root
+    }
+}
+
+/// Root the program would hold after appending `summaries` in order.
+#[cfg(not(target_os = "solana"))]
+pub fn archive_root(summaries: &[RoundSummary]) -> Result<[u8; 32]> {
+    let mut archive = RoundArchive {
+        leaf_count: 0,
+        root: [0u8; 32],
+        frontier: [[0u8; 32]; ARCHIVE_DEPTH],
+        bump: 0,
+    };
+    for summary in summaries {
+        archive.append(leaf_hash(summary)?)?;
+    }
+    Ok(archive.root)
+}
+
+/// Build an inclusion proof for `round_id` from every archived summary, in
+/// leaf order. Returns `None` if the round was never archived.
+#[cfg(not(target_os = "solana"))]
+pub fn prove_round(summaries: &[RoundSummary], round_id: u64) -> Result<Option<InclusionProof>> {
+    let Some(mut position) = summaries.iter().position(|s| s.round_id == round_id) else {
+        return Ok(None);
+    };
+
+    let mut level = summaries.iter().map(leaf_hash).collect::<Result<Vec<_>>>()?;
+    let leaf = level[position];
+    let leaf_index = position as u64;
+
+    let mut zero = [0u8; 32];
+    let mut siblings = Vec::with_capacity(ARCHIVE_DEPTH);
+    for _ in 0..ARCHIVE_DEPTH {
+        siblings.push(level.get(position ^ 1).copied().unwrap_or(zero));
+        level = level
+            .chunks(2)
+            .map(|pair| node_hash(&pair[0], pair.get(1).unwrap_or(&zero)))
+            .collect();
+        zero = node_hash(&zero, &zero);
+        position >>= 1;
+    }
+
+    Ok(Some(InclusionProof { leaf_index, leaf, siblings }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::RoundStatus;
+
+    fn summary(round_id: u64) -> RoundSummary {
+        RoundSummary {
+            round_id,
+            outcome: if round_id.is_multiple_of(2) { RoundStatus::Settled } else { RoundStatus::Expired },
+            commit_hash: [round_id as u8; 32],
+            answer_hash: [0u8; 32],
+            pool: 1_000_000 * round_id,
+            rollover_in: 1000 * round_id,
+            rollover_out: 500 * round_id,
+            winners: vec![Pubkey::new_from_array([7; 32]); (round_id % 3) as usize],
+            winner_amounts: vec![42; (round_id % 3) as usize],
+            evidence_total: 0,
+            treasury_amount: 50_000 * round_id,
+            buyback_amount: 0,
+            jackpot_amount: 0,
+            season_amount: 0,
+            finalized_at: 1_700_000_000 + round_id as i64,
+        }
+    }
+
+    fn summaries(n: u64) -> Vec<RoundSummary> {
+        (1..=n).map(summary).collect()
+    }
+
+    fn empty_archive() -> RoundArchive {
+        RoundArchive {
+            leaf_count: 0,
+            root: [0u8; 32],
+            frontier: [[0u8; 32]; ARCHIVE_DEPTH],
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn single_leaf_root_hashes_up_with_empty_subtrees() {
+        let leaf = leaf_hash(&summary(1)).unwrap();
+        let mut expected = leaf;
+        let mut zero = [0u8; 32];
+        for _ in 0..ARCHIVE_DEPTH {
+            expected = node_hash(&expected, &zero);
+            zero = node_hash(&zero, &zero);
+        }
+        assert_eq!(archive_root(&summaries(1)).unwrap(), expected);
+    }
+
+    #[test]
+    fn archive_root_matches_incremental_appends() {
+        let all = summaries(9);
+        let mut archive = empty_archive();
+        for (i, s) in all.iter().enumerate() {
+            assert_eq!(archive.append(leaf_hash(s).unwrap()).unwrap(), i as u64);
+            assert_eq!(archive.root, archive_root(&all[..=i]).unwrap());
+        }
+        assert_eq!(archive.leaf_count, 9);
+    }
+
+    #[test]
+    fn every_round_proves_against_the_root() {
+        for n in [1, 2, 3, 8, 13] {
+            let all = summaries(n);
+            let root = archive_root(&all).unwrap();
+            for s in &all {
+                let proof = prove_round(&all, s.round_id).unwrap().unwrap();
+                assert_eq!(proof.leaf, leaf_hash(s).unwrap());
+                assert_eq!(proof.siblings.len(), ARCHIVE_DEPTH);
+                assert!(proof.verify(&root), "round {} of {}", s.round_id, n);
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_round_has_no_proof() {
+        assert!(prove_round(&summaries(4), 99).unwrap().is_none());
+        assert!(prove_round(&[], 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn tampered_proofs_fail() {
+        let all = summaries(6);
+        let root = archive_root(&all).unwrap();
+        let proof = prove_round(&all, 3).unwrap().unwrap();
+        assert!(proof.verify(&root));
+
+        let mut leaf = proof.clone();
+        leaf.leaf = leaf_hash(&summary(30)).unwrap();
+        assert!(!leaf.verify(&root));
+
+        let mut index = proof.clone();
+        index.leaf_index += 1;
+        assert!(!index.verify(&root));
+
+        let mut out_of_range = proof.clone();
+        out_of_range.leaf_index += 1 << ARCHIVE_DEPTH;
+        assert!(!out_of_range.verify(&root));
+
+        let mut sibling = proof.clone();
+        sibling.siblings[0][0] ^= 1;
+        assert!(!sibling.verify(&root));
+
+        let mut short = proof.clone();
+        short.siblings.pop();
+        assert!(!short.verify(&root));
+
+        assert!(!proof.verify(&archive_root(&all[..5]).unwrap()));
+    }
+
+    #[test]
+    fn proof_is_stale_after_more_rounds_are_archived() {
+        let all = summaries(5);
+        let proof = prove_round(&all[..4], 2).unwrap().unwrap();
+        assert!(proof.verify(&archive_root(&all[..4]).unwrap()));
+        assert!(!proof.verify(&archive_root(&all).unwrap()));
+        assert!(prove_round(&all, 2).unwrap().unwrap().verify(&archive_root(&all).unwrap()));
+    }
+
+    #[test]
+    fn leaf_changes_with_the_record() {
+        let base = summary(4);
+        let mut changed = base.clone();
+        changed.winner_amounts[0] += 1;
+        assert_ne!(leaf_hash(&base).unwrap(), leaf_hash(&changed).unwrap());
+    }
+
+    #[test]
+    fn full_archive_rejects_append() {
+        let mut archive = empty_archive();
+        archive.leaf_count = 1 << ARCHIVE_DEPTH;
+        assert!(archive.append([1u8; 32]).is_err());
+    }
+}
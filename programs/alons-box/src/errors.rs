@@ -8,7 +8,7 @@ pub enum AlonsBoxError {
     RoundNotActive,
     #[msg("Invalid commit hash: SHA-256 mismatch")]
     InvalidCommitHash,
-    #[msg("Invalid payout sum: evidence amounts exceed 30% pool")]
+    #[msg("Invalid payout sum: evidence weights exceed 10000 BPS")]
     InvalidPayoutSum,
     #[msg("Math overflow")]
     MathOverflow,
@@ -16,7 +16,7 @@ pub enum AlonsBoxError {
     AnswerTooLong,
     #[msg("Salt too long (max 64 bytes)")]
     SaltTooLong,
-    #[msg("Evidence wallets count != evidence amounts count")]
+    #[msg("Remaining accounts count != winners + evidence weights count")]
     EvidenceMismatch,
     #[msg("Invalid round ID")]
     InvalidRoundId,
@@ -26,4 +26,136 @@ pub enum AlonsBoxError {
     GracePeriodNotElapsed,
     #[msg("Invalid end time: must be in the future")]
     InvalidEndTime,
+    #[msg("Evidence wallet has no YES question recorded in this round")]
+    IneligibleEvidence,
+    #[msg("Invalid winner count: settle takes 1-10 winners")]
+    InvalidWinnerWeights,
+    #[msg("Duplicate winner wallet")]
+    DuplicateWinner,
+    #[msg("Round archive is full")]
+    ArchiveFull,
+    #[msg("Invalid soft-close rule: window and extension must be non-zero, cap at or after ends_at")]
+    InvalidSoftClose,
+    #[msg("Round has not reached ends_at yet")]
+    RoundNotEnded,
+    #[msg("Round has no min_pool or its deposits reached it")]
+    MinPoolReached,
+    #[msg("Round is not refunding")]
+    RoundNotRefunding,
+    #[msg("Refunds are still outstanding for this round")]
+    RefundsOutstanding,
+    #[msg("Player is self-excluded from depositing")]
+    SelfExcluded,
+    #[msg("Self-exclusion cannot be shortened while it is active")]
+    SelfExclusionLocked,
+    #[msg("Deposit exceeds the player's daily limit")]
+    DailyLimitExceeded,
+    #[msg("Deposit exceeds the player's weekly limit")]
+    WeeklyLimitExceeded,
+    #[msg("Deposit exceeds the player's per-round limit")]
+    RoundLimitExceeded,
+    #[msg("Invalid deposit policy: maximums must not be below the minimum deposit or min_pool")]
+    InvalidDepositPolicy,
+    #[msg("Deposit is below the round's minimum")]
+    DepositTooSmall,
+    #[msg("Deposit is above the round's maximum single deposit")]
+    DepositTooLarge,
+    #[msg("Deposit exceeds the round's per-player maximum")]
+    PlayerCapExceeded,
+    #[msg("Deposit exceeds the round's maximum pool")]
+    PoolCapExceeded,
+    #[msg("Unknown pause flag bits")]
+    InvalidPauseFlags,
+    #[msg("Deposits are paused")]
+    DepositsPaused,
+    #[msg("Round creation is paused")]
+    RoundCreationPaused,
+    #[msg("Settlement is paused")]
+    SettlementPaused,
+    #[msg("Unknown role capability bits")]
+    InvalidCapabilities,
+    #[msg("Roles account is full")]
+    RolesFull,
+    #[msg("Cannot remove the last config admin")]
+    LastConfigAdmin,
+    #[msg("Timelock has not elapsed for this change")]
+    TimelockNotElapsed,
+    #[msg("Invalid timelock delay: must be between 0 and 30 days")]
+    InvalidTimelockDelay,
+    #[msg("Invalid payout config: shares must not exceed 10000 BPS")]
+    InvalidPayoutConfig,
+    #[msg("Invalid governance config")]
+    InvalidGovernanceConfig,
+    #[msg("Tokens are locked until a vote they backed has ended")]
+    TokensStillLocked,
+    #[msg("Not enough locked tokens")]
+    InsufficientLockedTokens,
+    #[msg("Locked tokens are below the proposal threshold")]
+    BelowProposalThreshold,
+    #[msg("Voting on this proposal has closed")]
+    VotingClosed,
+    #[msg("Voting on this proposal is still open")]
+    VotingStillOpen,
+    #[msg("Proposal is no longer in voting")]
+    ProposalNotVoting,
+    #[msg("Invalid buyback config")]
+    InvalidBuybackConfig,
+    #[msg("Buyback vault holds no spendable SOL")]
+    NothingToBuyBack,
+    #[msg("Buyback output is below the slippage bound")]
+    BuybackSlippageExceeded,
+    #[msg("Invalid revenue split: 1-8 distinct recipients with non-zero weights summing to 10000 BPS")]
+    InvalidRevenueSplit,
+    #[msg("Remaining accounts do not match the revenue split recipients")]
+    RecipientMismatch,
+    #[msg("Treasury pool holds nothing to distribute")]
+    NothingToDistribute,
+    #[msg("Invalid vesting period: must be between 1 second and 365 days")]
+    InvalidVestingPeriod,
+    #[msg("Nothing has vested yet")]
+    NothingVested,
+    #[msg("A player cannot refer themselves")]
+    SelfReferral,
+    #[msg("Player is already bound to a different referrer")]
+    ReferrerMismatch,
+    #[msg("Deposit has no referral share to credit")]
+    NoReferralShare,
+    #[msg("Referral share of this deposit has not been credited")]
+    ReferralNotCredited,
+    #[msg("Referrer has no earnings to claim")]
+    NoReferralEarnings,
+    #[msg("Player profile does not belong to the paired wallet")]
+    ProfileMismatch,
+    #[msg("Invalid season: round range, season BPS or prize curve")]
+    InvalidSeasonConfig,
+    #[msg("Round is outside the season, or the season is finalized")]
+    SeasonMismatch,
+    #[msg("The season's last round has not finished")]
+    SeasonNotEnded,
+    #[msg("Remaining accounts do not match the season leaderboard")]
+    LeaderboardMismatch,
+    #[msg("Duplicate evidence wallet")]
+    DuplicateEvidence,
+    #[msg("Guess does not belong to the paired winner in this round, or is not the answer")]
+    WinnerGuessMismatch,
+    #[msg("Deposit does not belong to the paired winner in this round")]
+    WinnerDepositMismatch,
+    #[msg("Account is not the rent payer recorded in the Settlement")]
+    RentPayerMismatch,
+    #[msg("Round deposits are below its min_pool; only fail_round applies")]
+    BelowMinPool,
+    #[msg("Round has ended")]
+    RoundEnded,
+    #[msg("Role grants must be queued behind the timelock; set_role only removes bits")]
+    RoleGrantTimelocked,
+    #[msg("Invalid guardian: must be set and differ from the authority")]
+    InvalidGuardian,
+    #[msg("Changes queued by governance cannot be cancelled by an admin or the guardian while they can still execute")]
+    GovernanceChange,
+    #[msg("Payout changes go through governance once it is initialized")]
+    PayoutGoverned,
+    #[msg("Tokens were locked after the proposal was created")]
+    LockedAfterProposal,
+    #[msg("Round belongs to a season; pass its Season account")]
+    SeasonRequired,
 }
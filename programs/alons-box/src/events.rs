@@ -1,4 +1,8 @@
 use anchor_lang::prelude::*;
+use crate::state::{
+    BuybackMode, ConfigChange, OverflowPolicy, PayoutConfig, PendingLimits, RoundSummary,
+    SplitRecipient, TieBreakPolicy,
+};
 
 #[event]
 pub struct GameInitialized {
@@ -14,6 +18,13 @@ pub struct RoundCreated {
     pub rollover_in: u64,
 }
 
+#[event]
+pub struct JackpotReleased {
+    pub round_id: u64,
+    pub amount: u64,
+    pub rollover_in: u64,
+}
+
 #[event]
 pub struct DepositMade {
     pub round_id: u64,
@@ -22,6 +33,35 @@ pub struct DepositMade {
     pub total_deposits: u64,
 }
 
+#[event]
+pub struct QuestionRecorded {
+    pub round_id: u64,
+    pub asker: Pubkey,
+    pub question_hash: [u8; 32],
+    pub is_public: bool,
+    pub answer_hash: [u8; 32],
+    pub is_yes: bool,
+}
+
+#[event]
+pub struct GuessRecorded {
+    pub round_id: u64,
+    pub player: Pubkey,
+    pub guess_hash: [u8; 32],
+    pub slot: u64,
+}
+
+#[event]
+pub struct RoundExtended {
+    pub round_id: u64,
+    pub previous_ends_at: i64,
+    pub ends_at: i64,
+    pub original_ends_at: i64,
+}
+
+/// Original settlement event, still emitted alongside `RoundSettledV2` for
+/// consumers that predate it. With co-winners, `winner` is the first one
+/// and `winner_amount` the total paid to all of them.
 #[event]
 pub struct RoundSettled {
     pub round_id: u64,
@@ -33,6 +73,19 @@ pub struct RoundSettled {
     pub rollover_out: u64,
 }
 
+#[event]
+pub struct RoundSettledV2 {
+    pub round_id: u64,
+    pub winners: Vec<Pubkey>,
+    pub winner_amounts: Vec<u64>,
+    pub tie_break: TieBreakPolicy,
+    pub pool: u64,
+    pub winner_total: u64,
+    pub evidence_total: u64,
+    pub treasury_amount: u64,
+    pub rollover_out: u64,
+}
+
 #[event]
 pub struct RoundExpired {
     pub round_id: u64,
@@ -52,6 +105,90 @@ pub struct EmergencyExpired {
     pub caller: Pubkey,
 }
 
+#[event]
+pub struct RolloverCapped {
+    pub round_id: u64,
+    pub overflow: u64,
+    pub policy: OverflowPolicy,
+    pub rollover_out: u64,
+    pub jackpot_balance: u64,
+}
+
+#[event]
+pub struct RolloverCapUpdated {
+    pub rollover_cap: u64,
+    pub overflow_policy: OverflowPolicy,
+}
+
+#[event]
+pub struct RoleUpdated {
+    pub member: Pubkey,
+    pub previous: u8,
+    pub capabilities: u8,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct GuardianUpdated {
+    pub previous: Pubkey,
+    pub guardian: Pubkey,
+}
+
+#[event]
+pub struct ChangeQueued {
+    pub change_id: u64,
+    pub change: ConfigChange,
+    pub queued_by: Pubkey,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct ChangeExecuted {
+    pub change_id: u64,
+    pub change: ConfigChange,
+    pub executor: Pubkey,
+}
+
+#[event]
+pub struct ChangeCancelled {
+    pub change_id: u64,
+    pub change: ConfigChange,
+    pub cancelled_by: Pubkey,
+}
+
+#[event]
+pub struct PauseChanged {
+    pub previous: u8,
+    pub paused: u8,
+    pub guardian: Pubkey,
+}
+
+#[event]
+pub struct RoundFailed {
+    pub round_id: u64,
+    pub total_deposits: u64,
+    pub min_pool: u64,
+    pub rollover_balance: u64,
+    pub caller: Pubkey,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub round_id: u64,
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PlayerLimitsUpdated {
+    pub player: Pubkey,
+    pub daily_cap: u64,
+    pub weekly_cap: u64,
+    pub round_cap: u64,
+    pub excluded_until: i64,
+    pub pending: PendingLimits,
+}
+
 #[event]
 pub struct DepositClosed {
     pub round_id: u64,
@@ -59,8 +196,180 @@ pub struct DepositClosed {
     pub rent_recovered: u64,
 }
 
+#[event]
+pub struct QuestionClosed {
+    pub round_id: u64,
+    pub asker: Pubkey,
+    pub rent_recovered: u64,
+}
+
+#[event]
+pub struct GuessClosed {
+    pub round_id: u64,
+    pub player: Pubkey,
+    pub rent_recovered: u64,
+}
+
 #[event]
 pub struct RoundClosed {
     pub round_id: u64,
     pub rent_recovered: u64,
 }
+
+/// Carries the full leaf preimage so off-chain tools can rebuild archive proofs.
+#[event]
+pub struct RoundArchived {
+    pub summary: RoundSummary,
+    pub leaf_index: u64,
+    pub leaf: [u8; 32],
+    pub root: [u8; 32],
+}
+
+#[event]
+pub struct GovernanceInitialized {
+    pub mint: Pubkey,
+    pub quorum_bps: u16,
+    pub approval_bps: u16,
+    pub voting_period: i64,
+    pub proposal_threshold: u64,
+}
+
+#[event]
+pub struct TokensLocked {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub total: u64,
+}
+
+#[event]
+pub struct TokensUnlocked {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub payout: PayoutConfig,
+    pub voting_ends_at: i64,
+}
+
+#[event]
+pub struct VoteCast {
+    pub proposal_id: u64,
+    pub voter: Pubkey,
+    pub support: bool,
+    pub weight: u64,
+}
+
+/// `change_id` is the queued PendingChange, or 0 when defeated or when a
+/// passed payout no longer fit (status Failed).
+#[event]
+pub struct ProposalFinalized {
+    pub proposal_id: u64,
+    pub passed: bool,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub change_id: u64,
+}
+
+#[event]
+pub struct BuybackInitialized {
+    pub swap_program: Pubkey,
+    pub pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub mode: BuybackMode,
+    pub max_slippage_bps: u16,
+    pub max_sol_per_buyback: u64,
+}
+
+/// `tokens_burned` is 0 under `BuybackMode::Lock`.
+#[event]
+pub struct BuybackExecuted {
+    pub executor: Pubkey,
+    pub sol_spent: u64,
+    pub min_tokens_out: u64,
+    pub tokens_bought: u64,
+    pub tokens_burned: u64,
+}
+
+#[event]
+pub struct RevenueSplitQueued {
+    pub recipients: Vec<SplitRecipient>,
+    pub effective_at: i64,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct RevenueSplitApplied {
+    pub recipients: Vec<SplitRecipient>,
+}
+
+/// `amounts[i]` went to `recipients[i]`; `carried` stays in the pool.
+#[event]
+pub struct TreasuryDistributed {
+    pub recipients: Vec<Pubkey>,
+    pub amounts: Vec<u64>,
+    pub carried: u64,
+    pub executor: Pubkey,
+}
+
+#[event]
+pub struct TreasuryStreamInitialized {
+    pub vesting_period: i64,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct VestedWithdrawn {
+    pub amount: u64,
+    pub treasury: Pubkey,
+    /// Still vesting after this withdrawal
+    pub unvested: u64,
+}
+
+#[event]
+pub struct ReferrerRegistered {
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct PlayerReferred {
+    pub player: Pubkey,
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct ReferralCredited {
+    pub round_id: u64,
+    pub player: Pubkey,
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferralClaimed {
+    pub referrer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SeasonCreated {
+    pub season_id: u64,
+    pub start_round_id: u64,
+    pub end_round_id: u64,
+    pub season_bps: u16,
+    pub prize_curve: Vec<u16>,
+}
+
+/// `amounts[i]` went to `winners[i]`; unclaimed ranks and dust went to the jackpot pool.
+#[event]
+pub struct SeasonFinalized {
+    pub season_id: u64,
+    pub prize_pool: u64,
+    pub winners: Vec<Pubkey>,
+    pub amounts: Vec<u64>,
+    pub to_jackpot: u64,
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::ChangeCancelled;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CancelChange<'info> {
+    /// A config admin, or the guardian vetoing a change
+    pub canceller: Signer<'info>,
+
+    #[account(
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&canceller.key(), ROLE_CONFIG_ADMIN)
+            || game_state.guardian == canceller.key() @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        mut,
+        close = queued_by,
+        seeds = [b"pending_change", pending_change.change_id.to_le_bytes().as_ref()],
+        bump = pending_change.bump,
+        // A passed vote is not for the operators to veto, but one that can
+        // no longer execute would otherwise sit in the queue forever
+        constraint = !pending_change.from_governance
+            || pending_change.is_stale(&game_state) @ AlonsBoxError::GovernanceChange,
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    /// CHECK: Receives the PendingChange rent; must be whoever queued it
+    #[account(
+        mut,
+        constraint = queued_by.key() == pending_change.queued_by @ AlonsBoxError::Unauthorized,
+    )]
+    pub queued_by: AccountInfo<'info>,
+}
+
+pub fn handler(ctx: Context<CancelChange>) -> Result<()> {
+    let pending = &ctx.accounts.pending_change;
+
+    emit!(ChangeCancelled {
+        change_id: pending.change_id,
+        change: pending.change,
+        cancelled_by: ctx.accounts.canceller.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::VoteCast;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        constraint = proposal.status == ProposalStatus::Voting @ AlonsBoxError::ProposalNotVoting,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"vote_escrow", voter.key().as_ref()],
+        bump = vote_escrow.bump,
+    )]
+    pub vote_escrow: Account<'info, VoteEscrow>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::SIZE,
+        seeds = [b"vote", proposal.proposal_id.to_le_bytes().as_ref(), voter.key().as_ref()],
+        bump,
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CastVote>, support: bool) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+    require!(now < proposal.voting_ends_at, AlonsBoxError::VotingClosed);
+
+    let escrow = &mut ctx.accounts.vote_escrow;
+    let weight = escrow.amount;
+    require!(weight > 0, AlonsBoxError::InsufficientLockedTokens);
+    // Quorum is measured against the lock total at creation, so weight
+    // locked later would count against a base that never included it
+    require!(
+        escrow.locked_at <= proposal.created_at,
+        AlonsBoxError::LockedAfterProposal
+    );
+    // Weight stays in the vault until the vote ends
+    escrow.locked_until = escrow.locked_until.max(proposal.voting_ends_at);
+
+    if support {
+        proposal.yes_votes = proposal
+            .yes_votes
+            .checked_add(weight)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+    } else {
+        proposal.no_votes = proposal
+            .no_votes
+            .checked_add(weight)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+    }
+
+    let record = &mut ctx.accounts.vote_record;
+    record.voter = ctx.accounts.voter.key();
+    record.support = support;
+    record.weight = weight;
+    record.bump = ctx.bumps.vote_record;
+
+    emit!(VoteCast {
+        proposal_id: proposal.proposal_id,
+        voter: record.voter,
+        support,
+        weight,
+    });
+
+    Ok(())
+}
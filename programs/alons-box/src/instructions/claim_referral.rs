@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::ReferralClaimed;
+use crate::state::*;
+use crate::utils::transfer_from_vault;
+
+#[derive(Accounts)]
+pub struct ClaimReferral<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer", owner.key().as_ref()],
+        bump = referrer.bump,
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    #[account(
+        mut,
+        seeds = [b"vault"],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, Vault>,
+}
+
+pub fn handler(ctx: Context<ClaimReferral>) -> Result<()> {
+    let referrer = &mut ctx.accounts.referrer;
+    let amount = referrer.accrued;
+    require!(amount > 0, AlonsBoxError::NoReferralEarnings);
+
+    referrer.accrued = 0;
+    referrer.total_claimed = referrer
+        .total_claimed
+        .checked_add(amount)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    let game_state = &mut ctx.accounts.game_state;
+    game_state.referral_liability = game_state
+        .referral_liability
+        .checked_sub(amount)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    transfer_from_vault(
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.owner.to_account_info(),
+        amount,
+    )?;
+
+    emit!(ReferralClaimed {
+        referrer: referrer.owner,
+        amount,
+    });
+
+    Ok(())
+}
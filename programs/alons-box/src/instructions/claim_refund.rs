@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::RefundClaimed;
+use crate::profile::update_profile;
+use crate::state::*;
+use crate::utils::transfer_from_vault;
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"round", round.round_id.to_le_bytes().as_ref()],
+        bump = round.bump,
+        constraint = round.status == RoundStatus::Refunding @ AlonsBoxError::RoundNotRefunding,
+    )]
+    pub round: Account<'info, Round>,
+
+    // Closed back to the player, who paid its rent at first deposit
+    #[account(
+        mut,
+        close = player,
+        seeds = [
+            b"deposit",
+            round.round_id.to_le_bytes().as_ref(),
+            player.key().as_ref(),
+        ],
+        bump = deposit.bump,
+    )]
+    pub deposit: Account<'info, Deposit>,
+
+    #[account(
+        mut,
+        seeds = [b"vault"],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: Player's profile PDA. Address fixed by seeds; created by the
+    /// player's first deposit, so it only lacks data for pre-profile deposits.
+    #[account(
+        mut,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+    )]
+    pub player_profile: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<ClaimRefund>) -> Result<()> {
+    let amount = ctx.accounts.deposit.amount;
+
+    transfer_from_vault(
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.player.to_account_info(),
+        amount,
+    )?;
+
+    update_profile(
+        &ctx.accounts.player_profile.to_account_info(),
+        ctx.accounts.player.key,
+        |p| p.record_refund(amount),
+    )?;
+
+    let round = &mut ctx.accounts.round;
+    round.refunded = round
+        .refunded
+        .checked_add(amount)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+
+    emit!(RefundClaimed {
+        round_id: round.round_id,
+        player: ctx.accounts.player.key(),
+        amount,
+    });
+
+    Ok(())
+}
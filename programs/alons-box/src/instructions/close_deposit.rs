@@ -11,14 +11,21 @@ pub struct CloseDeposit<'info> {
     #[account(
         seeds = [b"game_state"],
         bump = game_state.bump,
-        constraint = game_state.authority == authority.key() @ AlonsBoxError::Unauthorized,
     )]
     pub game_state: Account<'info, GameState>,
 
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&authority.key(), ROLE_CLOSER) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
     #[account(
         seeds = [b"round", round.round_id.to_le_bytes().as_ref()],
         bump = round.bump,
         constraint = round.status != RoundStatus::Active @ AlonsBoxError::RoundStillActive,
+        constraint = round.status != RoundStatus::Refunding @ AlonsBoxError::RefundsOutstanding,
     )]
     pub round: Account<'info, Round>,
 
@@ -32,6 +39,9 @@ pub struct CloseDeposit<'info> {
         ],
         bump = deposit.bump,
         constraint = deposit.round_id == round.round_id,
+        constraint = deposit.referrer == Pubkey::default()
+            || deposit.referral_credited
+            || round.referrals_credited() @ AlonsBoxError::ReferralNotCredited,
     )]
     pub deposit: Account<'info, Deposit>,
 }
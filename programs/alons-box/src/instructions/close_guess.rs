@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::GuessClosed;
+use crate::state::*;
+use crate::utils::require_round_finished;
+
+#[derive(Accounts)]
+pub struct CloseGuess<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&authority.key(), ROLE_CLOSER) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
+    /// CHECK: Round PDA of the guess — must have finished, or been closed
+    #[account(
+        seeds = [b"round", guess.round_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub round: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            b"guess",
+            guess.round_id.to_le_bytes().as_ref(),
+            guess.player.as_ref(),
+        ],
+        bump = guess.bump,
+    )]
+    pub guess: Account<'info, Guess>,
+}
+
+pub fn handler(ctx: Context<CloseGuess>) -> Result<()> {
+    require_round_finished(&ctx.accounts.round)?;
+
+    let rent = ctx.accounts.guess.to_account_info().lamports();
+
+    emit!(GuessClosed {
+        round_id: ctx.accounts.guess.round_id,
+        player: ctx.accounts.guess.player,
+        rent_recovered: rent,
+    });
+
+    Ok(())
+}
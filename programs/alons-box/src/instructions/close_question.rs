@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::QuestionClosed;
+use crate::state::*;
+use crate::utils::require_round_finished;
+
+#[derive(Accounts)]
+pub struct CloseQuestion<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&authority.key(), ROLE_CLOSER) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
+    /// CHECK: Round PDA of the question — must have finished, or been closed
+    #[account(
+        seeds = [b"round", question.round_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub round: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            b"question",
+            question.round_id.to_le_bytes().as_ref(),
+            question.asker.as_ref(),
+            question.question_hash.as_ref(),
+        ],
+        bump = question.bump,
+    )]
+    pub question: Account<'info, Question>,
+}
+
+pub fn handler(ctx: Context<CloseQuestion>) -> Result<()> {
+    require_round_finished(&ctx.accounts.round)?;
+
+    let rent = ctx.accounts.question.to_account_info().lamports();
+
+    emit!(QuestionClosed {
+        round_id: ctx.accounts.question.round_id,
+        asker: ctx.accounts.question.asker,
+        rent_recovered: rent,
+    });
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
+use crate::archive::leaf_hash;
 use crate::errors::AlonsBoxError;
-use crate::events::RoundClosed;
+use crate::events::{RoundArchived, RoundClosed};
 use crate::state::*;
 
 #[derive(Accounts)]
@@ -11,26 +12,79 @@ pub struct CloseRound<'info> {
     #[account(
         seeds = [b"game_state"],
         bump = game_state.bump,
-        constraint = game_state.authority == authority.key() @ AlonsBoxError::Unauthorized,
     )]
     pub game_state: Account<'info, GameState>,
 
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&authority.key(), ROLE_CLOSER) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
     #[account(
         mut,
         close = authority,
         seeds = [b"round", round.round_id.to_le_bytes().as_ref()],
         bump = round.bump,
         constraint = round.status != RoundStatus::Active @ AlonsBoxError::RoundStillActive,
+        constraint = round.status != RoundStatus::Refunding
+            || round.refunded == round.total_deposits @ AlonsBoxError::RefundsOutstanding,
+        constraint = round.referrals_credited() @ AlonsBoxError::ReferralNotCredited,
     )]
     pub round: Account<'info, Round>,
+
+    /// Folded into the archive, then closed to its rent payer
+    #[account(
+        mut,
+        close = rent_payer,
+        seeds = [b"settlement", round.round_id.to_le_bytes().as_ref()],
+        bump = settlement.bump,
+    )]
+    pub settlement: Account<'info, Settlement>,
+
+    /// CHECK: Paid the Settlement rent; receives it back
+    #[account(
+        mut,
+        constraint = rent_payer.key() == settlement.rent_payer @ AlonsBoxError::RentPayerMismatch,
+    )]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = RoundArchive::SIZE,
+        seeds = [b"round_archive"],
+        bump,
+    )]
+    pub archive: Account<'info, RoundArchive>,
+
+    pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<CloseRound>) -> Result<()> {
-    let rent = ctx.accounts.round.to_account_info().lamports();
+    let round_rent = ctx.accounts.round.to_account_info().lamports();
+
+    // ── Fold the settlement record into the archive ──
+    // The leaf keeps the record once the Settlement is closed
+    let summary = ctx.accounts.settlement.summary();
+    let leaf = leaf_hash(&summary)?;
+
+    let archive = &mut ctx.accounts.archive;
+    archive.bump = ctx.bumps.archive;
+    let leaf_index = archive.append(leaf)?;
+    let root = archive.root;
+
+    emit!(RoundArchived {
+        summary,
+        leaf_index,
+        leaf,
+        root,
+    });
 
     emit!(RoundClosed {
         round_id: ctx.accounts.round.round_id,
-        rent_recovered: rent,
+        rent_recovered: round_rent,
     });
 
     Ok(())
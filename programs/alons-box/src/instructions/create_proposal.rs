@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::ProposalCreated;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"governance"],
+        bump = governance.bump,
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        seeds = [b"vote_escrow", proposer.key().as_ref()],
+        bump = vote_escrow.bump,
+        constraint = vote_escrow.amount >= governance.proposal_threshold @ AlonsBoxError::BelowProposalThreshold,
+    )]
+    pub vote_escrow: Account<'info, VoteEscrow>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::SIZE,
+        seeds = [b"proposal", (governance.proposal_count + 1).to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateProposal>, payout: PayoutConfig) -> Result<()> {
+    require!(payout.is_valid(), AlonsBoxError::InvalidPayoutConfig);
+
+    let governance = &mut ctx.accounts.governance;
+    // With no quorum a single voter could pass a proposal nobody else saw
+    require!(governance.quorum_bps > 0, AlonsBoxError::InvalidGovernanceConfig);
+    let now = Clock::get()?.unix_timestamp;
+    let voting_ends_at = now
+        .checked_add(governance.voting_period)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    governance.proposal_count += 1;
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.proposal_id = governance.proposal_count;
+    proposal.proposer = ctx.accounts.proposer.key();
+    proposal.payout = payout;
+    proposal.created_at = now;
+    proposal.voting_ends_at = voting_ends_at;
+    proposal.yes_votes = 0;
+    proposal.no_votes = 0;
+    proposal.total_locked = governance.total_locked;
+    proposal.status = ProposalStatus::Voting;
+    proposal.change_id = 0;
+    proposal.bump = ctx.bumps.proposal;
+
+    emit!(ProposalCreated {
+        proposal_id: proposal.proposal_id,
+        proposer: proposal.proposer,
+        payout,
+        voting_ends_at,
+    });
+
+    Ok(())
+}
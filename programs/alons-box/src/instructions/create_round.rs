@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
 use crate::errors::AlonsBoxError;
-use crate::events::RoundCreated;
+use crate::events::{JackpotReleased, RoundCreated};
+use crate::season::check_season;
 use crate::state::*;
+use crate::utils::transfer_from_vault;
 
 #[derive(Accounts)]
 #[instruction(round_id: u64)]
@@ -13,10 +15,17 @@ pub struct CreateRound<'info> {
         mut,
         seeds = [b"game_state"],
         bump = game_state.bump,
-        constraint = game_state.authority == authority.key() @ AlonsBoxError::Unauthorized,
+        constraint = !game_state.is_paused(PAUSE_ROUND_CREATION) @ AlonsBoxError::RoundCreationPaused,
     )]
     pub game_state: Account<'info, GameState>,
 
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&authority.key(), ROLE_ROUND_CREATOR) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
     #[account(
         init,
         payer = authority,
@@ -26,14 +35,38 @@ pub struct CreateRound<'info> {
     )]
     pub round: Account<'info, Round>,
 
+    #[account(
+        mut,
+        seeds = [b"vault"],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, Vault>,
+
+    #[account(
+        mut,
+        seeds = [b"jackpot_pool"],
+        bump = jackpot_pool.bump,
+    )]
+    pub jackpot_pool: Account<'info, JackpotPool>,
+
+    /// Season the round falls in — required when it falls in one; counts
+    /// the round as open
+    #[account(mut)]
+    pub season: Option<Account<'info, Season>>,
+
     pub system_program: Program<'info, System>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<CreateRound>,
     round_id: u64,
     commit_hash: [u8; 32],
     ends_at: i64,
+    soft_close: Option<SoftClose>,
+    min_pool: Option<u64>,
+    jackpot: bool,
+    deposit_policy: Option<DepositPolicy>,
 ) -> Result<()> {
     let game_state = &mut ctx.accounts.game_state;
 
@@ -47,8 +80,48 @@ pub fn handler(
     let clock = Clock::get()?;
     require!(ends_at > clock.unix_timestamp, AlonsBoxError::InvalidEndTime);
 
+    let soft_close = soft_close.unwrap_or_default();
+    if soft_close != SoftClose::default() {
+        require!(
+            soft_close.window_secs > 0
+                && soft_close.extension_secs > 0
+                && soft_close.max_ends_at >= ends_at,
+            AlonsBoxError::InvalidSoftClose
+        );
+    }
+
+    let min_pool = min_pool.unwrap_or(0);
+    let deposit_policy = deposit_policy.unwrap_or_default();
+    let floor = deposit_policy.min_deposit;
+    let at_least = |max: u64, min: u64| max == 0 || max >= min;
+    require!(
+        at_least(deposit_policy.max_deposit, floor)
+            && at_least(deposit_policy.max_per_player, floor)
+            && at_least(deposit_policy.max_pool, floor)
+            && at_least(deposit_policy.max_pool, min_pool),
+        AlonsBoxError::InvalidDepositPolicy
+    );
+
     game_state.current_round_id = round_id;
 
+    // Jackpot rounds pull the whole jackpot pool back into the vault as rollover
+    let jackpot_in = if jackpot {
+        let amount = ctx.accounts.jackpot_pool.balance;
+        transfer_from_vault(
+            &ctx.accounts.jackpot_pool.to_account_info(),
+            &ctx.accounts.vault.to_account_info(),
+            amount,
+        )?;
+        ctx.accounts.jackpot_pool.balance = 0;
+        game_state.rollover_balance = game_state
+            .rollover_balance
+            .checked_add(amount)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+        amount
+    } else {
+        0
+    };
+
     let rollover = game_state.rollover_balance;
 
     let round = &mut ctx.accounts.round;
@@ -62,6 +135,18 @@ pub fn handler(
     round.revealed_answer = String::new();
     round.revealed_salt = String::new();
     round.bump = ctx.bumps.round;
+    round.original_ends_at = ends_at;
+    round.soft_close = soft_close;
+    round.min_pool = min_pool;
+    round.refunded = 0;
+    round.jackpot_in = jackpot_in;
+    round.deposit_policy = deposit_policy;
+    round.season_id = game_state.season_for(round_id);
+
+    check_season(ctx.accounts.season.as_deref(), round.season_id)?;
+    if let Some(season) = ctx.accounts.season.as_mut() {
+        season.open_round()?;
+    }
 
     emit!(RoundCreated {
         round_id,
@@ -69,5 +154,13 @@ pub fn handler(
         rollover_in: rollover,
     });
 
+    if jackpot {
+        emit!(JackpotReleased {
+            round_id,
+            amount: jackpot_in,
+            rollover_in: rollover,
+        });
+    }
+
     Ok(())
 }
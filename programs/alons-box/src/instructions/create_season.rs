@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::SeasonCreated;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(season_id: u64)]
+pub struct CreateSeason<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&admin.key(), ROLE_CONFIG_ADMIN) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Season::SIZE,
+        seeds = [b"season", season_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub season: Account<'info, Season>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<CreateSeason>,
+    season_id: u64,
+    start_round_id: u64,
+    end_round_id: u64,
+    season_bps: u16,
+    prize_curve: Vec<u16>,
+) -> Result<()> {
+    let season = &mut ctx.accounts.season;
+    season.season_id = season_id;
+    season.start_round_id = start_round_id;
+    season.end_round_id = end_round_id;
+    season.season_bps = season_bps;
+    season.prize_curve = prize_curve;
+    season.prize_pool = 0;
+    season.leaderboard = Vec::new();
+    season.finalized = false;
+    season.open_rounds = 0;
+    season.bump = ctx.bumps.season;
+
+    // Seasons only cover rounds that have not been created yet, and follow
+    // each other: every round of the previous one exists already
+    let game_state = &mut ctx.accounts.game_state;
+    require!(
+        start_round_id > game_state.current_round_id
+            && (game_state.season_id == 0
+                || game_state.current_round_id >= game_state.season_end_round_id)
+            && season.is_valid(&game_state.payout),
+        AlonsBoxError::InvalidSeasonConfig
+    );
+    game_state.season_id = season_id;
+    game_state.season_start_round_id = start_round_id;
+    game_state.season_end_round_id = end_round_id;
+    game_state.season_bps = season_bps;
+
+    emit!(SeasonCreated {
+        season_id,
+        start_round_id,
+        end_round_id,
+        season_bps,
+        prize_curve: season.prize_curve.clone(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::ReferralCredited;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CreditReferral<'info> {
+    /// Anyone can credit a settled deposit to its referrer
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"round", round.round_id.to_le_bytes().as_ref()],
+        bump = round.bump,
+        constraint = round.status != RoundStatus::Active @ AlonsBoxError::RoundStillActive,
+        constraint = round.status != RoundStatus::Refunding @ AlonsBoxError::NoReferralShare,
+    )]
+    pub round: Account<'info, Round>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"deposit",
+            round.round_id.to_le_bytes().as_ref(),
+            deposit.user.as_ref(),
+        ],
+        bump = deposit.bump,
+        constraint = deposit.referrer != Pubkey::default()
+            && !deposit.referral_credited @ AlonsBoxError::NoReferralShare,
+    )]
+    pub deposit: Account<'info, Deposit>,
+
+    #[account(
+        mut,
+        seeds = [b"referrer", deposit.referrer.as_ref()],
+        bump = referrer.bump,
+    )]
+    pub referrer: Account<'info, Referrer>,
+}
+
+pub fn handler(ctx: Context<CreditReferral>) -> Result<()> {
+    let amount = ctx
+        .accounts
+        .round
+        .credit_referral_share(ctx.accounts.deposit.amount)?;
+    ctx.accounts.deposit.referral_credited = true;
+
+    let referrer = &mut ctx.accounts.referrer;
+    referrer.accrued = referrer
+        .accrued
+        .checked_add(amount)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    referrer.total_earned = referrer
+        .total_earned
+        .checked_add(amount)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+
+    emit!(ReferralCredited {
+        round_id: ctx.accounts.round.round_id,
+        player: ctx.accounts.deposit.user,
+        referrer: referrer.owner,
+        amount,
+    });
+
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
 use crate::errors::AlonsBoxError;
-use crate::events::DepositMade;
+use crate::events::{DepositMade, RoundExtended};
+use crate::referral::resolve_referrer;
 use crate::state::*;
 
 #[derive(Accounts)]
@@ -9,6 +10,13 @@ pub struct DepositCtx<'info> {
     #[account(mut)]
     pub player: Signer<'info>,
 
+    #[account(
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+        constraint = !game_state.is_paused(PAUSE_DEPOSITS) @ AlonsBoxError::DepositsPaused,
+    )]
+    pub game_state: Account<'info, GameState>,
+
     #[account(
         mut,
         seeds = [b"round", round.round_id.to_le_bytes().as_ref()],
@@ -37,10 +45,90 @@ pub struct DepositCtx<'info> {
     )]
     pub vault: Account<'info, Vault>,
 
+    /// CHECK: Player's limits PDA. Address fixed by seeds; it may not exist yet,
+    /// in which case the player has set no limits.
+    #[account(
+        mut,
+        seeds = [b"player_limits", player.key().as_ref()],
+        bump,
+    )]
+    pub player_limits: UncheckedAccount<'info>,
+
+    /// CHECK: Player's referral binding. Address fixed by seeds; created here
+    /// the first time the player deposits with a referrer.
+    #[account(
+        mut,
+        seeds = [b"referral", player.key().as_ref()],
+        bump,
+    )]
+    pub player_referral: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = PlayerProfile::SIZE,
+        seeds = [b"player_profile", player.key().as_ref()],
+        bump,
+    )]
+    pub player_profile: Account<'info, PlayerProfile>,
+
+    /// Registered referrer named by the `referrer` argument; only needed
+    /// when that deposit creates the binding
+    #[account(mut)]
+    pub referrer: Option<Account<'info, Referrer>>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<DepositCtx>, amount: u64) -> Result<()> {
+pub fn handler(ctx: Context<DepositCtx>, amount: u64, referrer: Option<Pubkey>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    // Deposits close at ends_at; soft close only moves ends_at out
+    require!(now < ctx.accounts.round.ends_at, AlonsBoxError::RoundEnded);
+    let round_total = ctx
+        .accounts
+        .deposit
+        .amount
+        .checked_add(amount)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+
+    // Round deposit policy (0 = no limit; zero-lamport deposits never pass)
+    let policy = ctx.accounts.round.deposit_policy;
+    let within = |value: u64, max: u64| max == 0 || value <= max;
+    require!(
+        amount > 0 && amount >= policy.min_deposit,
+        AlonsBoxError::DepositTooSmall
+    );
+    require!(within(amount, policy.max_deposit), AlonsBoxError::DepositTooLarge);
+    require!(
+        within(round_total, policy.max_per_player),
+        AlonsBoxError::PlayerCapExceeded
+    );
+    let pool_total = ctx
+        .accounts
+        .round
+        .total_deposits
+        .checked_add(amount)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    require!(within(pool_total, policy.max_pool), AlonsBoxError::PoolCapExceeded);
+
+    // Responsible-gaming limits, if the player has set any
+    let limits_info = ctx.accounts.player_limits.to_account_info();
+    if limits_info.owner == &crate::ID && !limits_info.data_is_empty() {
+        let mut limits = PlayerLimits::try_deserialize(&mut &limits_info.try_borrow_data()?[..])?;
+        limits.record_deposit(now, amount, round_total)?;
+        limits.try_serialize(&mut &mut limits_info.try_borrow_mut_data()?[..])?;
+    }
+
+    // Referral attribution: an existing binding wins, otherwise bind now
+    let referrer = resolve_referrer(
+        &ctx.accounts.player.to_account_info(),
+        &ctx.accounts.player_referral.to_account_info(),
+        ctx.bumps.player_referral,
+        ctx.accounts.referrer.as_mut(),
+        referrer,
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
     // Transfer SOL from player → vault
     system_program::transfer(
         CpiContext::new(
@@ -61,23 +149,49 @@ pub fn handler(ctx: Context<DepositCtx>, amount: u64) -> Result<()> {
     // On subsequent deposits, these fields are already set correctly.
     // The PDA seeds already guarantee the correct round_id and player,
     // so these writes are safe but unnecessary after the first deposit.
-    if deposit.user == Pubkey::default() {
+    let first_in_round = deposit.user == Pubkey::default();
+    if first_in_round {
         deposit.round_id = ctx.accounts.round.round_id;
         deposit.user = ctx.accounts.player.key();
         deposit.bump = ctx.bumps.deposit;
     }
 
+    // A binding made mid-round attributes the player's whole round deposit
+    let referred_amount = if deposit.referrer != Pubkey::default() {
+        amount
+    } else if referrer != Pubkey::default() {
+        deposit.referrer = referrer;
+        deposit
+            .amount
+            .checked_add(amount)
+            .ok_or(AlonsBoxError::MathOverflow)?
+    } else {
+        0
+    };
+
     deposit.amount = deposit
         .amount
         .checked_add(amount)
         .ok_or(AlonsBoxError::MathOverflow)?;
 
+    // Lifetime stats; the profile is created by the player's first deposit
+    let profile = &mut ctx.accounts.player_profile;
+    if profile.player == Pubkey::default() {
+        profile.player = ctx.accounts.player.key();
+        profile.bump = ctx.bumps.player_profile;
+    }
+    profile.record_deposit(ctx.accounts.round.round_id, amount, first_in_round)?;
+
     // Update round total
     let round = &mut ctx.accounts.round;
     round.total_deposits = round
         .total_deposits
         .checked_add(amount)
         .ok_or(AlonsBoxError::MathOverflow)?;
+    round.referred_deposits = round
+        .referred_deposits
+        .checked_add(referred_amount)
+        .ok_or(AlonsBoxError::MathOverflow)?;
 
     emit!(DepositMade {
         round_id: round.round_id,
@@ -86,5 +200,27 @@ pub fn handler(ctx: Context<DepositCtx>, amount: u64) -> Result<()> {
         total_deposits: round.total_deposits,
     });
 
+    // Soft close: a deposit inside the closing window pushes ends_at out, up to the cap
+    let rule = round.soft_close;
+    if rule.window_secs > 0 {
+        let remaining = round.ends_at.saturating_sub(now);
+        if remaining > 0 && remaining <= i64::from(rule.window_secs) {
+            let previous_ends_at = round.ends_at;
+            let extended = previous_ends_at
+                .checked_add(i64::from(rule.extension_secs))
+                .ok_or(AlonsBoxError::MathOverflow)?
+                .min(rule.max_ends_at);
+            if extended > previous_ends_at {
+                round.ends_at = extended;
+                emit!(RoundExtended {
+                    round_id: round.round_id,
+                    previous_ends_at,
+                    ends_at: extended,
+                    original_ends_at: round.original_ends_at,
+                });
+            }
+        }
+    }
+
     Ok(())
 }
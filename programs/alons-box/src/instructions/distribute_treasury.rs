@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::{RevenueSplitApplied, TreasuryDistributed};
+use crate::state::*;
+use crate::utils::{bps_shares, transfer_from_vault};
+
+#[derive(Accounts)]
+pub struct DistributeTreasury<'info> {
+    /// Anyone can trigger a distribution
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"revenue_split"],
+        bump = revenue_split.bump,
+    )]
+    pub revenue_split: Account<'info, RevenueSplit>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury_pool"],
+        bump = treasury_pool.bump,
+    )]
+    pub treasury_pool: Account<'info, TreasuryPool>,
+    // remaining_accounts: one writable account per recipient, in
+    // revenue_split.recipients order (after any matured pending list applies)
+}
+
+pub fn handler<'a>(ctx: Context<'_, '_, 'a, 'a, DistributeTreasury<'a>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let split = &mut ctx.accounts.revenue_split;
+    if split.apply_pending(now) {
+        emit!(RevenueSplitApplied {
+            recipients: split.recipients.clone(),
+        });
+    }
+
+    // Nothing to pay until the first list has cleared the timelock
+    require!(!split.recipients.is_empty(), AlonsBoxError::InvalidRevenueSplit);
+    require!(
+        ctx.remaining_accounts.len() == split.recipients.len(),
+        AlonsBoxError::RecipientMismatch
+    );
+    for (account, recipient) in ctx.remaining_accounts.iter().zip(split.recipients.iter()) {
+        require_keys_eq!(account.key(), recipient.key, AlonsBoxError::RecipientMismatch);
+    }
+
+    // Everything above rent, including dust carried from last time
+    let rent = Rent::get()?;
+    let pool_info = ctx.accounts.treasury_pool.to_account_info();
+    let available = pool_info
+        .lamports()
+        .saturating_sub(rent.minimum_balance(pool_info.data_len()));
+    require!(available > 0, AlonsBoxError::NothingToDistribute);
+
+    let weights: Vec<u16> = split.recipients.iter().map(|r| r.bps).collect();
+    let mut amounts = bps_shares(available, &weights)?;
+    let mut paid = 0u64;
+    for (account, amount) in ctx.remaining_accounts.iter().zip(amounts.iter_mut()) {
+        // A share too small to create the account would fail the whole
+        // run; it stays in the pool and is carried to the next one
+        if account.lamports() == 0 && *amount < rent.minimum_balance(0) {
+            *amount = 0;
+            continue;
+        }
+        transfer_from_vault(&pool_info, account, *amount)?;
+        paid = paid.checked_add(*amount).ok_or(AlonsBoxError::MathOverflow)?;
+    }
+
+    let pool = &mut ctx.accounts.treasury_pool;
+    pool.total_distributed = pool
+        .total_distributed
+        .checked_add(paid)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    pool.carried = available - paid;
+
+    emit!(TreasuryDistributed {
+        recipients: split.recipients.iter().map(|r| r.key).collect(),
+        amounts,
+        carried: pool.carried,
+        executor: ctx.accounts.executor.key(),
+    });
+
+    Ok(())
+}
@@ -1,8 +1,10 @@
 use anchor_lang::prelude::*;
 use crate::errors::AlonsBoxError;
 use crate::events::EmergencyExpired;
+use crate::season::close_season_round;
 use crate::state::*;
-use crate::utils::transfer_from_vault;
+use crate::stream::pay_treasury;
+use crate::utils::{bps_of, route_rollover_overflow, transfer_from_vault, write_settlement};
 
 #[derive(Accounts)]
 pub struct EmergencyExpire<'info> {
@@ -21,6 +23,7 @@ pub struct EmergencyExpire<'info> {
         seeds = [b"round", round.round_id.to_le_bytes().as_ref()],
         bump = round.bump,
         constraint = round.status == RoundStatus::Active @ AlonsBoxError::RoundNotActive,
+        constraint = round.min_pool_met() @ AlonsBoxError::BelowMinPool,
     )]
     pub round: Account<'info, Round>,
 
@@ -31,29 +34,60 @@ pub struct EmergencyExpire<'info> {
     )]
     pub vault: Account<'info, Vault>,
 
-    /// CHECK: Treasury — receives 5% of pool
+    /// CHECK: Settlement PDA — created in the handler after the round is validated
+    #[account(
+        mut,
+        seeds = [b"settlement", round.round_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub settlement: UncheckedAccount<'info>,
+
+    /// CHECK: Treasury — receives payout.expire_treasury_bps of deposits (vested first if treasury_stream exists)
     #[account(
         mut,
         constraint = treasury.key() == game_state.treasury @ AlonsBoxError::Unauthorized,
     )]
     pub treasury: AccountInfo<'info>,
 
-    /// CHECK: Buyback wallet — receives 47.5% of pool for $SIMULATION buyback
+    /// CHECK: TreasuryStream PDA — may be uninitialized, in which case the
+    /// treasury cut is paid to `treasury` directly
+    #[account(
+        mut,
+        seeds = [b"treasury_stream"],
+        bump,
+    )]
+    pub treasury_stream: UncheckedAccount<'info>,
+
+    /// CHECK: Buyback wallet — receives payout.expire_buyback_bps of deposits for $SIMULATION buyback
     #[account(
         mut,
         constraint = buyback_wallet.key() == game_state.buyback_wallet @ AlonsBoxError::Unauthorized,
     )]
     pub buyback_wallet: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"jackpot_pool"],
+        bump = jackpot_pool.bump,
+    )]
+    pub jackpot_pool: Account<'info, JackpotPool>,
+
+    /// Season the round belongs to — required when round.season_id is set;
+    /// counts the round as no longer open
+    #[account(mut)]
+    pub season: Option<Account<'info, Season>>,
+
+    pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<EmergencyExpire>) -> Result<()> {
     let clock = Clock::get()?;
     let round = &ctx.accounts.round;
 
-    // Only callable 24 hours after ends_at
+    // Only callable EMERGENCY_GRACE_PERIOD after ends_at
     let grace_deadline = round
         .ends_at
-        .checked_add(86400)
+        .checked_add(EMERGENCY_GRACE_PERIOD)
         .ok_or(AlonsBoxError::MathOverflow)?;
     require!(
         clock.unix_timestamp > grace_deadline,
@@ -64,19 +98,10 @@ pub fn handler(ctx: Context<EmergencyExpire>) -> Result<()> {
     let total_deposits = round.total_deposits;
     let rollover_in = round.rollover_in;
 
-    // 47.5% buyback (4750 BPS) — from deposits only
-    let buyback_amount = total_deposits
-        .checked_mul(4750)
-        .ok_or(AlonsBoxError::MathOverflow)?
-        .checked_div(10000)
-        .ok_or(AlonsBoxError::MathOverflow)?;
-
-    // 5% treasury (500 BPS) — from deposits only
-    let treasury_amount = total_deposits
-        .checked_mul(500)
-        .ok_or(AlonsBoxError::MathOverflow)?
-        .checked_div(10000)
-        .ok_or(AlonsBoxError::MathOverflow)?;
+    // Buyback and treasury cuts (BPS from game_state.payout) — from deposits only
+    let payout = ctx.accounts.game_state.payout;
+    let buyback_amount = bps_of(total_deposits, payout.expire_buyback_bps)?;
+    let treasury_amount = bps_of(total_deposits, payout.expire_treasury_bps)?;
 
     // Residual absorbs rounding dust
     let rollover_added = total_deposits
@@ -92,18 +117,68 @@ pub fn handler(ctx: Context<EmergencyExpire>) -> Result<()> {
     // Distribute from vault
     let vault_info = ctx.accounts.vault.to_account_info();
     transfer_from_vault(&vault_info, &ctx.accounts.buyback_wallet, buyback_amount)?;
-    transfer_from_vault(&vault_info, &ctx.accounts.treasury, treasury_amount)?;
-
-    // Update rollover and mark as expired (no answer reveal — answer is forfeit in emergency)
+    // Referral slice of the treasury cut stays in the vault for referrers
+    let referral_amount = ctx
+        .accounts
+        .round
+        .withhold_referrals(&mut ctx.accounts.game_state, payout.expire_treasury_bps)?;
+    pay_treasury(
+        &ctx.accounts.treasury_stream.to_account_info(),
+        &vault_info,
+        &ctx.accounts.treasury,
+        treasury_amount
+            .checked_sub(referral_amount)
+            .ok_or(AlonsBoxError::MathOverflow)?,
+    )?;
+
+    // Cap rollover, update it and mark as expired (no answer reveal — answer is forfeit in emergency)
+    let split = route_rollover_overflow(
+        ctx.accounts.round.round_id,
+        rollover_out,
+        &ctx.accounts.game_state,
+        &vault_info,
+        &mut ctx.accounts.jackpot_pool,
+        &ctx.accounts.buyback_wallet,
+    )?;
+    let rollover_out = split.rollover_out;
     ctx.accounts.game_state.rollover_balance = rollover_out;
 
-    let round = &mut ctx.accounts.round;
-    round.status = RoundStatus::Expired;
-
     let pool = total_deposits
         .checked_add(rollover_in)
         .ok_or(AlonsBoxError::MathOverflow)?;
 
+    // Persist the payout breakdown (answer_hash stays zeroed — never revealed)
+    write_settlement(
+        &ctx.accounts.settlement.to_account_info(),
+        &ctx.accounts.caller.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &Settlement {
+            round_id: ctx.accounts.round.round_id,
+            outcome: RoundStatus::Expired,
+            commit_hash: ctx.accounts.round.commit_hash,
+            answer_hash: [0u8; 32],
+            pool,
+            rollover_in,
+            rollover_out,
+            winners: Vec::new(),
+            winner_amounts: Vec::new(),
+            evidence_total: 0,
+            treasury_amount,
+            buyback_amount: buyback_amount
+                .checked_add(split.to_buyback)
+                .ok_or(AlonsBoxError::MathOverflow)?,
+            jackpot_amount: split.to_jackpot,
+            season_amount: 0,
+            finalized_at: clock.unix_timestamp,
+            rent_payer: ctx.accounts.caller.key(),
+            bump: ctx.bumps.settlement,
+        },
+    )?;
+
+    close_season_round(ctx.accounts.season.as_mut(), &ctx.accounts.round)?;
+    let round = &mut ctx.accounts.round;
+    round.status = RoundStatus::Expired;
+
     emit!(EmergencyExpired {
         round_id: round.round_id,
         pool,
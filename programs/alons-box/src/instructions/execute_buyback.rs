@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hash;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+use crate::errors::AlonsBoxError;
+use crate::events::BuybackExecuted;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ExecuteBuyback<'info> {
+    /// Anyone can crank; the program's quote floor bounds the fill whatever
+    /// min_tokens_out they pass
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"buyback"],
+        bump = buyback.bump,
+    )]
+    pub buyback: Account<'info, Buyback>,
+
+    #[account(
+        mut,
+        seeds = [b"buyback_vault"],
+        bump = buyback.vault_bump,
+    )]
+    pub buyback_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"buyback_tokens"],
+        bump = buyback.tokens_bump,
+    )]
+    pub buyback_tokens: Account<'info, TokenAccount>,
+
+    #[account(mut, address = buyback.token_mint @ AlonsBoxError::InvalidBuybackConfig)]
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: Must match buyback.swap_program
+    #[account(address = buyback.swap_program @ AlonsBoxError::InvalidBuybackConfig)]
+    pub swap_program: UncheckedAccount<'info>,
+
+    /// CHECK: Must match buyback.pool; validated by the swap program
+    #[account(mut, address = buyback.pool @ AlonsBoxError::InvalidBuybackConfig)]
+    pub pool: UncheckedAccount<'info>,
+
+    #[account(mut, address = buyback.pool_token_vault @ AlonsBoxError::InvalidBuybackConfig)]
+    pub pool_token_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Constant-product output for `amount_in` (price impact included, before
+/// fees) less `max_slippage_bps`.
+fn quote_floor(sol_reserve: u64, token_reserve: u64, amount_in: u64, max_slippage_bps: u16) -> u64 {
+    let quote = token_reserve as u128 * amount_in as u128 / (sol_reserve as u128 + amount_in as u128);
+    (quote * (10000 - max_slippage_bps as u128) / 10000) as u64
+}
+
+pub fn handler(ctx: Context<ExecuteBuyback>, min_tokens_out: u64) -> Result<()> {
+    let buyback = &ctx.accounts.buyback;
+    let rent = Rent::get()?;
+
+    // Everything above the vault's own rent exemption, capped per call
+    let mut amount_in = ctx
+        .accounts
+        .buyback_vault
+        .lamports()
+        .saturating_sub(rent.minimum_balance(0));
+    if buyback.max_sol_per_buyback > 0 {
+        amount_in = amount_in.min(buyback.max_sol_per_buyback);
+    }
+    require!(amount_in > 0, AlonsBoxError::NothingToBuyBack);
+
+    let pool_info = ctx.accounts.pool.to_account_info();
+    let sol_reserve = pool_info
+        .lamports()
+        .saturating_sub(rent.minimum_balance(pool_info.data_len()));
+    // The executor may raise the bound with an off-chain quote but never
+    // lower it below the one taken from the reserves
+    let min_out = min_tokens_out.max(quote_floor(
+        sol_reserve,
+        ctx.accounts.pool_token_vault.amount,
+        amount_in,
+        buyback.max_slippage_bps,
+    ));
+    require!(min_out > 0, AlonsBoxError::BuybackSlippageExceeded);
+
+    // swap(amount_in: u64, min_amount_out: u64) with accounts
+    // [payer, pool, token_vault, destination, token_program, system_program]
+    let mut data = hash(b"global:swap").to_bytes()[..8].to_vec();
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_out.to_le_bytes());
+    let swap_ix = Instruction {
+        program_id: buyback.swap_program,
+        accounts: vec![
+            AccountMeta::new(ctx.accounts.buyback_vault.key(), true),
+            AccountMeta::new(ctx.accounts.pool.key(), false),
+            AccountMeta::new(ctx.accounts.pool_token_vault.key(), false),
+            AccountMeta::new(ctx.accounts.buyback_tokens.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
+        ],
+        data,
+    };
+
+    let tokens_before = ctx.accounts.buyback_tokens.amount;
+    invoke_signed(
+        &swap_ix,
+        &[
+            ctx.accounts.buyback_vault.to_account_info(),
+            pool_info,
+            ctx.accounts.pool_token_vault.to_account_info(),
+            ctx.accounts.buyback_tokens.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.swap_program.to_account_info(),
+        ],
+        &[&[b"buyback_vault", &[buyback.vault_bump]]],
+    )?;
+
+    // Trust the balance change, not the swap program's own check
+    ctx.accounts.buyback_tokens.reload()?;
+    let tokens_bought = ctx
+        .accounts
+        .buyback_tokens
+        .amount
+        .checked_sub(tokens_before)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    require!(tokens_bought >= min_out, AlonsBoxError::BuybackSlippageExceeded);
+
+    let tokens_burned = if buyback.mode == BuybackMode::Burn {
+        let seeds: &[&[u8]] = &[b"buyback", &[buyback.bump]];
+        token::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    from: ctx.accounts.buyback_tokens.to_account_info(),
+                    authority: ctx.accounts.buyback.to_account_info(),
+                },
+                &[seeds],
+            ),
+            tokens_bought,
+        )?;
+        tokens_bought
+    } else {
+        0
+    };
+
+    let buyback = &mut ctx.accounts.buyback;
+    buyback.total_sol_spent = buyback
+        .total_sol_spent
+        .checked_add(amount_in)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    buyback.total_tokens_bought = buyback
+        .total_tokens_bought
+        .checked_add(tokens_bought)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    buyback.total_tokens_burned = buyback
+        .total_tokens_burned
+        .checked_add(tokens_burned)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+
+    emit!(BuybackExecuted {
+        executor: ctx.accounts.executor.key(),
+        sol_spent: amount_in,
+        min_tokens_out: min_out,
+        tokens_bought,
+        tokens_burned,
+    });
+
+    Ok(())
+}
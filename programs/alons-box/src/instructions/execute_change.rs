@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::{ChangeExecuted, GuardianUpdated, RoleUpdated, RolloverCapUpdated};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct ExecuteChange<'info> {
+    /// Anyone can execute once the delay has passed
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    /// Written only by a Role change
+    #[account(
+        mut,
+        seeds = [b"roles"],
+        bump = roles.bump,
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        mut,
+        close = queued_by,
+        seeds = [b"pending_change", pending_change.change_id.to_le_bytes().as_ref()],
+        bump = pending_change.bump,
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    /// CHECK: Receives the PendingChange rent; must be whoever queued it
+    #[account(
+        mut,
+        constraint = queued_by.key() == pending_change.queued_by @ AlonsBoxError::Unauthorized,
+    )]
+    pub queued_by: AccountInfo<'info>,
+}
+
+pub fn handler(ctx: Context<ExecuteChange>) -> Result<()> {
+    let pending = &ctx.accounts.pending_change;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now >= pending.executable_at, AlonsBoxError::TimelockNotElapsed);
+
+    let game_state = &mut ctx.accounts.game_state;
+    match pending.change {
+        ConfigChange::Treasury(treasury) => game_state.treasury = treasury,
+        ConfigChange::BuybackWallet(buyback_wallet) => game_state.buyback_wallet = buyback_wallet,
+        ConfigChange::RolloverCap {
+            rollover_cap,
+            overflow_policy,
+        } => {
+            // Takes effect at the next settle/expire; the current balance is not clipped
+            game_state.rollover_cap = rollover_cap;
+            game_state.overflow_policy = overflow_policy;
+            emit!(RolloverCapUpdated {
+                rollover_cap,
+                overflow_policy,
+            });
+        }
+        ConfigChange::Guardian(guardian) => {
+            let previous = game_state.guardian;
+            game_state.guardian = guardian;
+            emit!(GuardianUpdated { previous, guardian });
+        }
+        // Applies to changes queued from now on; already queued ones keep their time
+        ConfigChange::TimelockDelay(delay) => game_state.timelock_delay = delay,
+        // Rounds settled or expired after this use the new split. A season
+        // created since it was queued may have claimed the room it needs.
+        ConfigChange::Payout(payout) => {
+            require!(game_state.payout_fits(&payout), AlonsBoxError::InvalidPayoutConfig);
+            game_state.payout = payout;
+        }
+        // Rounds settled or expired after this withhold the new slice
+        ConfigChange::ReferralBps(bps) => game_state.referral_bps = bps,
+        ConfigChange::Role {
+            member,
+            capabilities,
+        } => {
+            let previous = ctx.accounts.roles.set(member, capabilities)?;
+            emit!(RoleUpdated {
+                member,
+                previous,
+                capabilities,
+                admin: pending.queued_by,
+            });
+        }
+        // Read by init_buyback only; an initialized Buyback keeps its program
+        ConfigChange::SwapProgram(swap_program) => game_state.swap_program = swap_program,
+    }
+
+    emit!(ChangeExecuted {
+        change_id: pending.change_id,
+        change: pending.change,
+        executor: ctx.accounts.executor.key(),
+    });
+
+    Ok(())
+}
@@ -2,8 +2,10 @@ use anchor_lang::prelude::*;
 use anchor_lang::solana_program::hash::hash;
 use crate::errors::AlonsBoxError;
 use crate::events::RoundExpired;
+use crate::season::close_season_round;
 use crate::state::*;
-use crate::utils::transfer_from_vault;
+use crate::stream::pay_treasury;
+use crate::utils::{bps_of, route_rollover_overflow, transfer_from_vault, write_settlement};
 
 #[derive(Accounts)]
 pub struct Expire<'info> {
@@ -14,15 +16,23 @@ pub struct Expire<'info> {
         mut,
         seeds = [b"game_state"],
         bump = game_state.bump,
-        constraint = game_state.authority == authority.key() @ AlonsBoxError::Unauthorized,
+        constraint = !game_state.is_paused(PAUSE_SETTLEMENT) @ AlonsBoxError::SettlementPaused,
     )]
     pub game_state: Account<'info, GameState>,
 
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&authority.key(), ROLE_SETTLER) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
     #[account(
         mut,
         seeds = [b"round", round.round_id.to_le_bytes().as_ref()],
         bump = round.bump,
         constraint = round.status == RoundStatus::Active @ AlonsBoxError::RoundNotActive,
+        constraint = round.min_pool_met() @ AlonsBoxError::BelowMinPool,
     )]
     pub round: Account<'info, Round>,
 
@@ -33,19 +43,50 @@ pub struct Expire<'info> {
     )]
     pub vault: Account<'info, Vault>,
 
-    /// CHECK: Treasury — receives 5% of pool
+    /// CHECK: Settlement PDA — created in the handler after the round is validated
+    #[account(
+        mut,
+        seeds = [b"settlement", round.round_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub settlement: UncheckedAccount<'info>,
+
+    /// CHECK: Treasury — receives payout.expire_treasury_bps of deposits (vested first if treasury_stream exists)
     #[account(
         mut,
         constraint = treasury.key() == game_state.treasury @ AlonsBoxError::Unauthorized,
     )]
     pub treasury: AccountInfo<'info>,
 
-    /// CHECK: Buyback wallet — receives 47.5% of pool for $SIMULATION buyback
+    /// CHECK: TreasuryStream PDA — may be uninitialized, in which case the
+    /// treasury cut is paid to `treasury` directly
+    #[account(
+        mut,
+        seeds = [b"treasury_stream"],
+        bump,
+    )]
+    pub treasury_stream: UncheckedAccount<'info>,
+
+    /// CHECK: Buyback wallet — receives payout.expire_buyback_bps of deposits for $SIMULATION buyback
     #[account(
         mut,
         constraint = buyback_wallet.key() == game_state.buyback_wallet @ AlonsBoxError::Unauthorized,
     )]
     pub buyback_wallet: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"jackpot_pool"],
+        bump = jackpot_pool.bump,
+    )]
+    pub jackpot_pool: Account<'info, JackpotPool>,
+
+    /// Season the round belongs to — required when round.season_id is set;
+    /// counts the round as no longer open
+    #[account(mut)]
+    pub season: Option<Account<'info, Season>>,
+
+    pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<Expire>, answer: String, salt: String) -> Result<()> {
@@ -65,19 +106,10 @@ pub fn handler(ctx: Context<Expire>, answer: String, salt: String) -> Result<()>
     let total_deposits = round.total_deposits;
     let rollover_in = round.rollover_in;
 
-    // 47.5% buyback (4750 BPS) — from deposits only
-    let buyback_amount = total_deposits
-        .checked_mul(4750)
-        .ok_or(AlonsBoxError::MathOverflow)?
-        .checked_div(10000)
-        .ok_or(AlonsBoxError::MathOverflow)?;
-
-    // 5% treasury (500 BPS) — from deposits only
-    let treasury_amount = total_deposits
-        .checked_mul(500)
-        .ok_or(AlonsBoxError::MathOverflow)?
-        .checked_div(10000)
-        .ok_or(AlonsBoxError::MathOverflow)?;
+    // Buyback and treasury cuts (BPS from game_state.payout) — from deposits only
+    let payout = ctx.accounts.game_state.payout;
+    let buyback_amount = bps_of(total_deposits, payout.expire_buyback_bps)?;
+    let treasury_amount = bps_of(total_deposits, payout.expire_treasury_bps)?;
 
     // Residual absorbs rounding dust
     let rollover_added = total_deposits
@@ -93,24 +125,75 @@ pub fn handler(ctx: Context<Expire>, answer: String, salt: String) -> Result<()>
     // ── Distribute from vault (program-owned PDA) ──
     let vault_info = ctx.accounts.vault.to_account_info();
 
-    // Buyback wallet (47.5% of deposits)
+    // Buyback wallet
     transfer_from_vault(&vault_info, &ctx.accounts.buyback_wallet, buyback_amount)?;
 
-    // Treasury (5% of deposits)
-    transfer_from_vault(&vault_info, &ctx.accounts.treasury, treasury_amount)?;
-
-    // ── Update rollover and round state ──
+    // Referral slice of the treasury cut stays in the vault for referrers
+    let referral_amount = ctx
+        .accounts
+        .round
+        .withhold_referrals(&mut ctx.accounts.game_state, payout.expire_treasury_bps)?;
+
+    // Treasury (vests in the stream when one exists)
+    pay_treasury(
+        &ctx.accounts.treasury_stream.to_account_info(),
+        &vault_info,
+        &ctx.accounts.treasury,
+        treasury_amount
+            .checked_sub(referral_amount)
+            .ok_or(AlonsBoxError::MathOverflow)?,
+    )?;
+
+    // ── Update rollover (capped, overflow routed out) and round state ──
+    let split = route_rollover_overflow(
+        ctx.accounts.round.round_id,
+        rollover_out,
+        &ctx.accounts.game_state,
+        &vault_info,
+        &mut ctx.accounts.jackpot_pool,
+        &ctx.accounts.buyback_wallet,
+    )?;
+    let rollover_out = split.rollover_out;
     ctx.accounts.game_state.rollover_balance = rollover_out;
 
+    let pool = total_deposits
+        .checked_add(rollover_in)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+
+    // ── Persist the payout breakdown ──
+    write_settlement(
+        &ctx.accounts.settlement.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &Settlement {
+            round_id: ctx.accounts.round.round_id,
+            outcome: RoundStatus::Expired,
+            commit_hash: ctx.accounts.round.commit_hash,
+            answer_hash: hash(answer.as_bytes()).to_bytes(),
+            pool,
+            rollover_in,
+            rollover_out,
+            winners: Vec::new(),
+            winner_amounts: Vec::new(),
+            evidence_total: 0,
+            treasury_amount,
+            buyback_amount: buyback_amount
+                .checked_add(split.to_buyback)
+                .ok_or(AlonsBoxError::MathOverflow)?,
+            jackpot_amount: split.to_jackpot,
+            season_amount: 0,
+            finalized_at: Clock::get()?.unix_timestamp,
+            rent_payer: ctx.accounts.authority.key(),
+            bump: ctx.bumps.settlement,
+        },
+    )?;
+
+    close_season_round(ctx.accounts.season.as_mut(), &ctx.accounts.round)?;
     let round = &mut ctx.accounts.round;
     round.status = RoundStatus::Expired;
     round.revealed_answer = answer;
     round.revealed_salt = salt;
 
-    let pool = total_deposits
-        .checked_add(rollover_in)
-        .ok_or(AlonsBoxError::MathOverflow)?;
-
     emit!(RoundExpired {
         round_id: round.round_id,
         pool,
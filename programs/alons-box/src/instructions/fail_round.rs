@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::RoundFailed;
+use crate::season::close_season_round;
+use crate::state::*;
+use crate::utils::write_settlement;
+
+#[derive(Accounts)]
+pub struct FailRound<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    #[account(
+        mut,
+        seeds = [b"round", round.round_id.to_le_bytes().as_ref()],
+        bump = round.bump,
+        constraint = round.status == RoundStatus::Active @ AlonsBoxError::RoundNotActive,
+    )]
+    pub round: Account<'info, Round>,
+
+    /// CHECK: Settlement PDA — created in the handler after the round is validated
+    #[account(
+        mut,
+        seeds = [b"settlement", round.round_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub settlement: UncheckedAccount<'info>,
+
+    /// Season the round belongs to — required when round.season_id is set;
+    /// counts the round as no longer open
+    #[account(mut)]
+    pub season: Option<Account<'info, Season>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<FailRound>) -> Result<()> {
+    let clock = Clock::get()?;
+    let round = &ctx.accounts.round;
+
+    // Permissionless once the deadline passes with deposits under the minimum
+    require!(
+        clock.unix_timestamp >= round.ends_at,
+        AlonsBoxError::RoundNotEnded
+    );
+    require!(
+        round.min_pool > 0 && round.total_deposits < round.min_pool,
+        AlonsBoxError::MinPoolReached
+    );
+
+    let pool = round
+        .total_deposits
+        .checked_add(round.rollover_in)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+
+    // Nothing leaves the vault: deposits are refunded one by one, and the
+    // rollover balance is left exactly as it was
+    let rollover_balance = ctx.accounts.game_state.rollover_balance;
+
+    write_settlement(
+        &ctx.accounts.settlement.to_account_info(),
+        &ctx.accounts.caller.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &Settlement {
+            round_id: round.round_id,
+            outcome: RoundStatus::Refunding,
+            commit_hash: round.commit_hash,
+            answer_hash: [0u8; 32],
+            pool,
+            rollover_in: round.rollover_in,
+            rollover_out: rollover_balance,
+            winners: Vec::new(),
+            winner_amounts: Vec::new(),
+            evidence_total: 0,
+            treasury_amount: 0,
+            buyback_amount: 0,
+            jackpot_amount: 0,
+            season_amount: 0,
+            finalized_at: clock.unix_timestamp,
+            rent_payer: ctx.accounts.caller.key(),
+            bump: ctx.bumps.settlement,
+        },
+    )?;
+
+    close_season_round(ctx.accounts.season.as_mut(), &ctx.accounts.round)?;
+    let round = &mut ctx.accounts.round;
+    round.status = RoundStatus::Refunding;
+
+    emit!(RoundFailed {
+        round_id: round.round_id,
+        total_deposits: round.total_deposits,
+        min_pool: round.min_pool,
+        rollover_balance,
+        caller: ctx.accounts.caller.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::ProposalFinalized;
+use crate::state::*;
+use crate::utils::{create_pda_account, queue_config_change};
+
+#[derive(Accounts)]
+pub struct FinalizeProposal<'info> {
+    /// Anyone can finalize once voting has ended; pays the PendingChange rent
+    /// and gets it back when the change is executed or cancelled
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        seeds = [b"governance"],
+        bump = governance.bump,
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+        constraint = proposal.status == ProposalStatus::Voting @ AlonsBoxError::ProposalNotVoting,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: Created in the handler only if the proposal passed and its
+    /// payout still fits
+    #[account(
+        mut,
+        seeds = [b"pending_change", (game_state.change_count + 1).to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub pending_change: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<FinalizeProposal>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let governance = &ctx.accounts.governance;
+    let proposal = &mut ctx.accounts.proposal;
+    require!(now >= proposal.voting_ends_at, AlonsBoxError::VotingStillOpen);
+
+    // Quorum against the lock total at creation; approval against votes cast
+    let cast = proposal.yes_votes as u128 + proposal.no_votes as u128;
+    let quorum = proposal.total_locked as u128 * governance.quorum_bps as u128 / 10000;
+    let passed = cast > 0
+        && cast >= quorum
+        && proposal.yes_votes as u128 * 10000 >= cast * governance.approval_bps as u128;
+
+    if passed && !ctx.accounts.game_state.payout_fits(&proposal.payout) {
+        // Valid when proposed, but a season has since claimed part of the
+        // pool; queueing would fail, leaving the proposal in Voting for good
+        proposal.status = ProposalStatus::Failed;
+    } else if passed {
+        let mut pending = queue_config_change(
+            &mut ctx.accounts.game_state,
+            ConfigChange::Payout(proposal.payout),
+            ctx.accounts.caller.key(),
+            ctx.bumps.pending_change,
+        )?;
+        pending.from_governance = true;
+
+        let change_id_bytes = pending.change_id.to_le_bytes();
+        let seeds: &[&[u8]] = &[b"pending_change", change_id_bytes.as_ref(), &[pending.bump]];
+        let pending_info = ctx.accounts.pending_change.to_account_info();
+        create_pda_account(
+            &ctx.accounts.caller.to_account_info(),
+            &pending_info,
+            &ctx.accounts.system_program.to_account_info(),
+            PendingChange::SIZE,
+            seeds,
+        )?;
+        let mut data = pending_info.try_borrow_mut_data()?;
+        pending.try_serialize(&mut &mut data[..])?;
+
+        proposal.status = ProposalStatus::Queued;
+        proposal.change_id = pending.change_id;
+    } else {
+        proposal.status = ProposalStatus::Defeated;
+    }
+
+    emit!(ProposalFinalized {
+        proposal_id: proposal.proposal_id,
+        passed,
+        yes_votes: proposal.yes_votes,
+        no_votes: proposal.no_votes,
+        change_id: proposal.change_id,
+    });
+
+    Ok(())
+}
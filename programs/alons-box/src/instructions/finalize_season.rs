@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::SeasonFinalized;
+use crate::state::*;
+use crate::utils::transfer_from_vault;
+
+#[derive(Accounts)]
+pub struct FinalizeSeason<'info> {
+    /// Anyone can finalize once the season's rounds are all over
+    pub caller: Signer<'info>,
+
+    /// Its season_bps is released once the latest season is finalized
+    #[account(
+        mut,
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    #[account(
+        mut,
+        seeds = [b"season", season.season_id.to_le_bytes().as_ref()],
+        bump = season.bump,
+        constraint = !season.finalized @ AlonsBoxError::SeasonMismatch,
+    )]
+    pub season: Account<'info, Season>,
+
+    #[account(
+        mut,
+        seeds = [b"jackpot_pool"],
+        bump = jackpot_pool.bump,
+    )]
+    pub jackpot_pool: Account<'info, JackpotPool>,
+    // remaining_accounts: leaderboard wallets (writable), best first, one per
+    // paid rank (the shorter of the leaderboard and the prize curve)
+}
+
+pub fn handler<'a>(ctx: Context<'_, '_, 'a, 'a, FinalizeSeason<'a>>) -> Result<()> {
+    // Every round of the season exists and none is still open
+    let season = &ctx.accounts.season;
+    require!(
+        ctx.accounts.game_state.current_round_id >= season.end_round_id && season.open_rounds == 0,
+        AlonsBoxError::SeasonNotEnded
+    );
+
+    let season = &mut ctx.accounts.season;
+    if ctx.accounts.game_state.season_id == season.season_id {
+        ctx.accounts.game_state.season_bps = 0;
+    }
+    let prizes = season.prizes()?;
+    require!(
+        ctx.remaining_accounts.len() == prizes.len()
+            && ctx
+                .remaining_accounts
+                .iter()
+                .zip(season.leaderboard.iter())
+                .all(|(wallet, entry)| wallet.key() == entry.player),
+        AlonsBoxError::LeaderboardMismatch
+    );
+
+    let season_info = season.to_account_info();
+    for (wallet, &amount) in ctx.remaining_accounts.iter().zip(prizes.iter()) {
+        transfer_from_vault(&season_info, wallet, amount)?;
+    }
+
+    // Unfilled ranks and rounding dust go to the jackpot pool
+    let paid = prizes
+        .iter()
+        .try_fold(0u64, |acc, &x| acc.checked_add(x))
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    let to_jackpot = season
+        .prize_pool
+        .checked_sub(paid)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    let jackpot_pool = &mut ctx.accounts.jackpot_pool;
+    transfer_from_vault(&season_info, &jackpot_pool.to_account_info(), to_jackpot)?;
+    jackpot_pool.balance = jackpot_pool
+        .balance
+        .checked_add(to_jackpot)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+
+    let prize_pool = season.prize_pool;
+    season.prize_pool = 0;
+    season.finalized = true;
+
+    emit!(SeasonFinalized {
+        season_id: season.season_id,
+        prize_pool,
+        winners: season.leaderboard.iter().take(prizes.len()).map(|e| e.player).collect(),
+        amounts: prizes,
+        to_jackpot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::errors::AlonsBoxError;
+use crate::events::BuybackInitialized;
+use crate::state::*;
+use crate::utils::create_pda_token_account;
+
+#[derive(Accounts)]
+pub struct InitBuyback<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&admin.key(), ROLE_CONFIG_ADMIN) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Buyback::SIZE,
+        seeds = [b"buyback"],
+        bump,
+    )]
+    pub buyback: Account<'info, Buyback>,
+
+    /// Holds SOL awaiting a buyback; system-owned so it can pay the swap
+    #[account(
+        mut,
+        seeds = [b"buyback_vault"],
+        bump,
+    )]
+    pub buyback_vault: SystemAccount<'info>,
+
+    /// CHECK: Token account created in the handler; receives swap output
+    #[account(
+        mut,
+        seeds = [b"buyback_tokens"],
+        bump,
+    )]
+    pub buyback_tokens: UncheckedAccount<'info>,
+
+    /// The token being bought back
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: Constant-product swap program; must be the one set through the timelock
+    #[account(
+        constraint = swap_program.executable @ AlonsBoxError::InvalidBuybackConfig,
+        constraint = swap_program.key() == game_state.swap_program @ AlonsBoxError::InvalidBuybackConfig,
+    )]
+    pub swap_program: UncheckedAccount<'info>,
+
+    /// CHECK: Pool account; must be owned by swap_program
+    #[account(constraint = *pool.owner == swap_program.key() @ AlonsBoxError::InvalidBuybackConfig)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// The pool's token reserve
+    #[account(
+        constraint = pool_token_vault.mint == token_mint.key() @ AlonsBoxError::InvalidBuybackConfig,
+        constraint = pool_token_vault.owner == pool.key() @ AlonsBoxError::InvalidBuybackConfig,
+    )]
+    pub pool_token_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<InitBuyback>,
+    mode: BuybackMode,
+    max_slippage_bps: u16,
+    max_sol_per_buyback: u64,
+) -> Result<()> {
+    require!(max_slippage_bps <= 10000, AlonsBoxError::InvalidBuybackConfig);
+
+    // Fund the vault to rent exemption so small transfers from expire land
+    let rent_exempt = Rent::get()?.minimum_balance(0);
+    let vault_lamports = ctx.accounts.buyback_vault.lamports();
+    if vault_lamports < rent_exempt {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.buyback_vault.to_account_info(),
+                },
+            ),
+            rent_exempt - vault_lamports,
+        )?;
+    }
+
+    let tokens_bump = ctx.bumps.buyback_tokens;
+    create_pda_token_account(
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.buyback_tokens.to_account_info(),
+        &ctx.accounts.token_mint.to_account_info(),
+        &ctx.accounts.buyback.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &[b"buyback_tokens", &[tokens_bump]],
+    )?;
+
+    let buyback = &mut ctx.accounts.buyback;
+    buyback.swap_program = ctx.accounts.swap_program.key();
+    buyback.pool = ctx.accounts.pool.key();
+    buyback.pool_token_vault = ctx.accounts.pool_token_vault.key();
+    buyback.token_mint = ctx.accounts.token_mint.key();
+    buyback.mode = mode;
+    buyback.max_slippage_bps = max_slippage_bps;
+    buyback.max_sol_per_buyback = max_sol_per_buyback;
+    buyback.total_sol_spent = 0;
+    buyback.total_tokens_bought = 0;
+    buyback.total_tokens_burned = 0;
+    buyback.bump = ctx.bumps.buyback;
+    buyback.vault_bump = ctx.bumps.buyback_vault;
+    buyback.tokens_bump = tokens_bump;
+
+    emit!(BuybackInitialized {
+        swap_program: buyback.swap_program,
+        pool: buyback.pool,
+        token_mint: buyback.token_mint,
+        mode,
+        max_slippage_bps,
+        max_sol_per_buyback,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+use crate::errors::AlonsBoxError;
+use crate::events::GovernanceInitialized;
+use crate::state::*;
+use crate::utils::create_pda_token_account;
+
+#[derive(Accounts)]
+pub struct InitGovernance<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&admin.key(), ROLE_CONFIG_ADMIN) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Governance::SIZE,
+        seeds = [b"governance"],
+        bump,
+    )]
+    pub governance: Account<'info, Governance>,
+
+    /// The governance token
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: Token account created in the handler; holds every locked
+    /// token with the governance PDA as its authority
+    #[account(
+        mut,
+        seeds = [b"governance_vault"],
+        bump,
+    )]
+    pub governance_vault: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<InitGovernance>,
+    quorum_bps: u16,
+    approval_bps: u16,
+    voting_period: i64,
+    proposal_threshold: u64,
+) -> Result<()> {
+    require!(
+        (1..=10000).contains(&quorum_bps)
+            && (1..=10000).contains(&approval_bps)
+            && voting_period > 0,
+        AlonsBoxError::InvalidGovernanceConfig
+    );
+
+    let vault_bump = ctx.bumps.governance_vault;
+    create_pda_token_account(
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.governance_vault.to_account_info(),
+        &ctx.accounts.mint.to_account_info(),
+        &ctx.accounts.governance.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &[b"governance_vault", &[vault_bump]],
+    )?;
+
+    let governance = &mut ctx.accounts.governance;
+    governance.mint = ctx.accounts.mint.key();
+    governance.quorum_bps = quorum_bps;
+    governance.approval_bps = approval_bps;
+    governance.voting_period = voting_period;
+    governance.proposal_threshold = proposal_threshold;
+    governance.total_locked = 0;
+    governance.proposal_count = 0;
+    governance.bump = ctx.bumps.governance;
+    governance.vault_bump = vault_bump;
+
+    emit!(GovernanceInitialized {
+        mint: governance.mint,
+        quorum_bps,
+        approval_bps,
+        voting_period,
+        proposal_threshold,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::TreasuryStreamInitialized;
+use crate::state::*;
+use crate::stream::MAX_VESTING_PERIOD;
+
+#[derive(Accounts)]
+pub struct InitTreasuryStream<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&admin.key(), ROLE_CONFIG_ADMIN) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = TreasuryStream::SIZE,
+        seeds = [b"treasury_stream"],
+        bump,
+    )]
+    pub treasury_stream: Account<'info, TreasuryStream>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitTreasuryStream>, vesting_period: i64) -> Result<()> {
+    require!(
+        (1..=MAX_VESTING_PERIOD).contains(&vesting_period),
+        AlonsBoxError::InvalidVestingPeriod
+    );
+
+    let stream = &mut ctx.accounts.treasury_stream;
+    stream.vesting_period = vesting_period;
+    stream.unvested = 0;
+    stream.vested = 0;
+    stream.tranches = [Tranche::default(); STREAM_TRANCHES];
+    stream.total_credited = 0;
+    stream.total_withdrawn = 0;
+    stream.bump = ctx.bumps.treasury_stream;
+
+    emit!(TreasuryStreamInitialized {
+        vesting_period,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
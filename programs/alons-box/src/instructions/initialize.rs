@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
 use crate::events::GameInitialized;
 use crate::state::*;
 
@@ -25,10 +26,39 @@ pub struct Initialize<'info> {
     )]
     pub vault: Account<'info, Vault>,
 
+    #[account(
+        init,
+        payer = authority,
+        space = JackpotPool::SIZE,
+        seeds = [b"jackpot_pool"],
+        bump,
+    )]
+    pub jackpot_pool: Account<'info, JackpotPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Roles::SIZE,
+        seeds = [b"roles"],
+        bump,
+    )]
+    pub roles: Account<'info, Roles>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<Initialize>, treasury: Pubkey, buyback_wallet: Pubkey) -> Result<()> {
+pub fn handler(
+    ctx: Context<Initialize>,
+    treasury: Pubkey,
+    buyback_wallet: Pubkey,
+    guardian: Pubkey,
+) -> Result<()> {
+    // A separate key, so pausing does not hinge on the deployer's key
+    require!(
+        guardian != Pubkey::default() && guardian != ctx.accounts.authority.key(),
+        AlonsBoxError::InvalidGuardian
+    );
+
     let game_state = &mut ctx.accounts.game_state;
     game_state.authority = ctx.accounts.authority.key();
     game_state.treasury = treasury;
@@ -36,10 +66,39 @@ pub fn handler(ctx: Context<Initialize>, treasury: Pubkey, buyback_wallet: Pubke
     game_state.current_round_id = 0;
     game_state.bump = ctx.bumps.game_state;
     game_state.rollover_balance = 0;
+    game_state.rollover_cap = 0;
+    game_state.overflow_policy = OverflowPolicy::Jackpot;
+    game_state.guardian = guardian;
+    game_state.paused = 0;
+    // Zero until the deployer queues a TimelockDelay change before launch
+    game_state.timelock_delay = 0;
+    game_state.change_count = 0;
+    game_state.payout = PayoutConfig::default();
+    // Referrals earn nothing until a ReferralBps change is executed
+    game_state.referral_bps = 0;
+    game_state.referral_liability = 0;
+    game_state.season_id = 0;
+    game_state.season_start_round_id = 0;
+    game_state.season_end_round_id = 0;
+    game_state.season_bps = 0;
+    // init_buyback is closed until a SwapProgram change is executed
+    game_state.swap_program = Pubkey::default();
 
     let vault = &mut ctx.accounts.vault;
     vault.bump = ctx.bumps.vault;
 
+    let jackpot_pool = &mut ctx.accounts.jackpot_pool;
+    jackpot_pool.balance = 0;
+    jackpot_pool.bump = ctx.bumps.jackpot_pool;
+
+    // The deployer starts with every capability and delegates from there
+    let roles = &mut ctx.accounts.roles;
+    roles.members = vec![RoleMember {
+        key: ctx.accounts.authority.key(),
+        capabilities: ROLE_ALL,
+    }];
+    roles.bump = ctx.bumps.roles;
+
     emit!(GameInitialized {
         authority: game_state.authority,
         treasury,
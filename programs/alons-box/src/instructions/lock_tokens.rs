@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::errors::AlonsBoxError;
+use crate::events::TokensLocked;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct LockTokens<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"governance"],
+        bump = governance.bump,
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = VoteEscrow::SIZE,
+        seeds = [b"vote_escrow", owner.key().as_ref()],
+        bump,
+    )]
+    pub vote_escrow: Account<'info, VoteEscrow>,
+
+    #[account(
+        mut,
+        constraint = owner_tokens.mint == governance.mint @ ErrorCode::ConstraintTokenMint,
+        constraint = owner_tokens.owner == owner.key() @ AlonsBoxError::Unauthorized,
+    )]
+    pub owner_tokens: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"governance_vault"],
+        bump = governance.vault_bump,
+    )]
+    pub governance_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<LockTokens>, amount: u64) -> Result<()> {
+    require!(amount > 0, AlonsBoxError::InsufficientLockedTokens);
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.owner_tokens.to_account_info(),
+                to: ctx.accounts.governance_vault.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    let escrow = &mut ctx.accounts.vote_escrow;
+    escrow.owner = ctx.accounts.owner.key();
+    escrow.amount = escrow
+        .amount
+        .checked_add(amount)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    escrow.bump = ctx.bumps.vote_escrow;
+    escrow.locked_at = Clock::get()?.unix_timestamp;
+
+    let governance = &mut ctx.accounts.governance;
+    governance.total_locked = governance
+        .total_locked
+        .checked_add(amount)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+
+    emit!(TokensLocked {
+        owner: escrow.owner,
+        amount,
+        total: escrow.amount,
+    });
+
+    Ok(())
+}
@@ -1,18 +1,76 @@
+pub mod cancel_change;
+pub mod cast_vote;
+pub mod claim_referral;
+pub mod claim_refund;
 pub mod close_deposit;
+pub mod close_guess;
+pub mod close_question;
 pub mod close_round;
+pub mod create_proposal;
 pub mod create_round;
+pub mod create_season;
+pub mod credit_referral;
 pub mod deposit;
+pub mod distribute_treasury;
 pub mod emergency_expire;
+pub mod execute_buyback;
+pub mod execute_change;
 pub mod expire;
+pub mod fail_round;
+pub mod finalize_proposal;
+pub mod finalize_season;
+pub mod init_buyback;
+pub mod init_governance;
+pub mod init_treasury_stream;
 pub mod initialize;
+pub mod lock_tokens;
+pub mod queue_change;
+pub mod record_guess;
+pub mod record_question;
+pub mod register_referrer;
+pub mod set_paused;
+pub mod set_player_limits;
+pub mod set_revenue_split;
+pub mod set_role;
 pub mod settle;
+pub mod unlock_tokens;
+pub mod withdraw_vested;
 
 #[allow(ambiguous_glob_reexports)]
+pub use cancel_change::*;
+pub use cast_vote::*;
+pub use claim_referral::*;
+pub use claim_refund::*;
 pub use close_deposit::*;
+pub use close_guess::*;
+pub use close_question::*;
 pub use close_round::*;
+pub use create_proposal::*;
 pub use create_round::*;
+pub use create_season::*;
+pub use credit_referral::*;
 pub use deposit::*;
+pub use distribute_treasury::*;
 pub use emergency_expire::*;
+pub use execute_buyback::*;
+pub use execute_change::*;
 pub use expire::*;
+pub use fail_round::*;
+pub use finalize_proposal::*;
+pub use finalize_season::*;
+pub use init_buyback::*;
+pub use init_governance::*;
+pub use init_treasury_stream::*;
 pub use initialize::*;
+pub use lock_tokens::*;
+pub use queue_change::*;
+pub use record_guess::*;
+pub use record_question::*;
+pub use register_referrer::*;
+pub use set_paused::*;
+pub use set_player_limits::*;
+pub use set_revenue_split::*;
+pub use set_role::*;
 pub use settle::*;
+pub use unlock_tokens::*;
+pub use withdraw_vested::*;
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::state::*;
+use crate::utils::queue_config_change;
+
+#[derive(Accounts)]
+pub struct QueueChange<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&admin.key(), ROLE_CONFIG_ADMIN) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
+    /// CHECK: Governance PDA; may not exist. Payout changes are rejected once it does
+    #[account(
+        seeds = [b"governance"],
+        bump,
+    )]
+    pub governance: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = PendingChange::SIZE,
+        seeds = [b"pending_change", (game_state.change_count + 1).to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub pending_change: Account<'info, PendingChange>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<QueueChange>, change: ConfigChange) -> Result<()> {
+    if let ConfigChange::Payout(_) = change {
+        require!(ctx.accounts.governance.data_is_empty(), AlonsBoxError::PayoutGoverned);
+    }
+
+    let pending = queue_config_change(
+        &mut ctx.accounts.game_state,
+        change,
+        ctx.accounts.admin.key(),
+        ctx.bumps.pending_change,
+    )?;
+    ctx.accounts.pending_change.set_inner(pending);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::GuessRecorded;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RecordGuess<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&authority.key(), ROLE_ROUND_CREATOR) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        seeds = [b"round", round.round_id.to_le_bytes().as_ref()],
+        bump = round.bump,
+        constraint = round.status == RoundStatus::Active @ AlonsBoxError::RoundNotActive,
+    )]
+    pub round: Account<'info, Round>,
+
+    /// CHECK: Player who guessed — only used as a PDA seed and recorded
+    pub player: AccountInfo<'info>,
+
+    /// One per player per round: the first correct guess is the one that counts
+    #[account(
+        init,
+        payer = authority,
+        space = Guess::SIZE,
+        seeds = [
+            b"guess",
+            round.round_id.to_le_bytes().as_ref(),
+            player.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub guess: Account<'info, Guess>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RecordGuess>, guess_hash: [u8; 32]) -> Result<()> {
+    let guess = &mut ctx.accounts.guess;
+    guess.round_id = ctx.accounts.round.round_id;
+    guess.player = ctx.accounts.player.key();
+    guess.guess_hash = guess_hash;
+    guess.slot = Clock::get()?.slot;
+    guess.bump = ctx.bumps.guess;
+
+    emit!(GuessRecorded {
+        round_id: guess.round_id,
+        player: guess.player,
+        guess_hash,
+        slot: guess.slot,
+    });
+
+    Ok(())
+}
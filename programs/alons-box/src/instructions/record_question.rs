@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::QuestionRecorded;
+use crate::state::*;
+
+#[derive(Accounts)]
+#[instruction(question_hash: [u8; 32])]
+pub struct RecordQuestion<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&authority.key(), ROLE_ROUND_CREATOR) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        seeds = [b"round", round.round_id.to_le_bytes().as_ref()],
+        bump = round.bump,
+        constraint = round.status == RoundStatus::Active @ AlonsBoxError::RoundNotActive,
+    )]
+    pub round: Account<'info, Round>,
+
+    /// CHECK: Player who asked the question — only used as a PDA seed and recorded
+    pub asker: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Question::SIZE,
+        seeds = [
+            b"question",
+            round.round_id.to_le_bytes().as_ref(),
+            asker.key().as_ref(),
+            question_hash.as_ref(),
+        ],
+        bump,
+    )]
+    pub question: Account<'info, Question>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<RecordQuestion>,
+    question_hash: [u8; 32],
+    is_public: bool,
+    answer_hash: [u8; 32],
+    is_yes: bool,
+) -> Result<()> {
+    let question = &mut ctx.accounts.question;
+    question.round_id = ctx.accounts.round.round_id;
+    question.asker = ctx.accounts.asker.key();
+    question.question_hash = question_hash;
+    question.is_public = is_public;
+    question.answer_hash = answer_hash;
+    question.is_yes = is_yes;
+    question.bump = ctx.bumps.question;
+
+    emit!(QuestionRecorded {
+        round_id: question.round_id,
+        asker: question.asker,
+        question_hash,
+        is_public,
+        answer_hash,
+        is_yes,
+    });
+
+    Ok(())
+}
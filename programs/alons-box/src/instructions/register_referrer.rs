@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::events::ReferrerRegistered;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    /// Any wallet can register as a referrer; pays rent
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = Referrer::SIZE,
+        seeds = [b"referrer", owner.key().as_ref()],
+        bump,
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RegisterReferrer>) -> Result<()> {
+    let referrer = &mut ctx.accounts.referrer;
+    referrer.owner = ctx.accounts.owner.key();
+    referrer.referred_players = 0;
+    referrer.accrued = 0;
+    referrer.total_earned = 0;
+    referrer.total_claimed = 0;
+    referrer.registered_at = Clock::get()?.unix_timestamp;
+    referrer.bump = ctx.bumps.referrer;
+
+    emit!(ReferrerRegistered {
+        referrer: referrer.owner,
+    });
+
+    Ok(())
+}
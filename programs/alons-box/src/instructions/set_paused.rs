@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::PauseChanged;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub guardian: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+        constraint = game_state.guardian == guardian.key() @ AlonsBoxError::Unauthorized,
+    )]
+    pub game_state: Account<'info, GameState>,
+}
+
+pub fn handler(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+    require!(paused & !PAUSE_ALL == 0, AlonsBoxError::InvalidPauseFlags);
+
+    // Replaces the whole mask; pass 0 to resume everything
+    let game_state = &mut ctx.accounts.game_state;
+    let previous = game_state.paused;
+    game_state.paused = paused;
+
+    emit!(PauseChanged {
+        previous,
+        paused,
+        guardian: ctx.accounts.guardian.key(),
+    });
+
+    Ok(())
+}
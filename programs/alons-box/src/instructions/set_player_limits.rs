@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::events::PlayerLimitsUpdated;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct SetPlayerLimits<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = PlayerLimits::SIZE,
+        seeds = [b"player_limits", player.key().as_ref()],
+        bump,
+    )]
+    pub player_limits: Account<'info, PlayerLimits>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<SetPlayerLimits>,
+    daily_cap: u64,
+    weekly_cap: u64,
+    round_cap: u64,
+    excluded_until: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let limits = &mut ctx.accounts.player_limits;
+    if limits.player == Pubkey::default() {
+        limits.player = ctx.accounts.player.key();
+        limits.bump = ctx.bumps.player_limits;
+    }
+
+    // Tighter values apply now; looser ones wait out LIMIT_COOLDOWN_SECS
+    limits.request(now, daily_cap, weekly_cap, round_cap, excluded_until)?;
+
+    emit!(PlayerLimitsUpdated {
+        player: limits.player,
+        daily_cap: limits.daily_cap,
+        weekly_cap: limits.weekly_cap,
+        round_cap: limits.round_cap,
+        excluded_until: limits.excluded_until,
+        pending: limits.pending,
+    });
+
+    Ok(())
+}
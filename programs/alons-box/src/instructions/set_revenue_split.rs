@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::{RevenueSplitApplied, RevenueSplitQueued};
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct SetRevenueSplit<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&admin.key(), ROLE_CONFIG_ADMIN) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = RevenueSplit::SIZE,
+        seeds = [b"revenue_split"],
+        bump,
+    )]
+    pub revenue_split: Account<'info, RevenueSplit>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = TreasuryPool::SIZE,
+        seeds = [b"treasury_pool"],
+        bump,
+    )]
+    pub treasury_pool: Account<'info, TreasuryPool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetRevenueSplit>, recipients: Vec<SplitRecipient>) -> Result<()> {
+    require!(
+        (1..=MAX_SPLIT_RECIPIENTS).contains(&recipients.len()),
+        AlonsBoxError::InvalidRevenueSplit
+    );
+    let total = recipients.iter().map(|r| r.bps as u32).sum::<u32>();
+    require!(
+        total == 10000 && recipients.iter().all(|r| r.bps > 0),
+        AlonsBoxError::InvalidRevenueSplit
+    );
+    for (i, recipient) in recipients.iter().enumerate() {
+        require!(
+            !recipients[..i].iter().any(|other| other.key == recipient.key),
+            AlonsBoxError::InvalidRevenueSplit
+        );
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let effective_at = now
+        .checked_add(ctx.accounts.game_state.timelock_delay)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+
+    let split = &mut ctx.accounts.revenue_split;
+    split.bump = ctx.bumps.revenue_split;
+    ctx.accounts.treasury_pool.bump = ctx.bumps.treasury_pool;
+
+    // Replaces any earlier pending list
+    split.pending = recipients.clone();
+    split.pending_effective_at = effective_at;
+    emit!(RevenueSplitQueued {
+        recipients,
+        effective_at,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    if split.apply_pending(now) {
+        emit!(RevenueSplitApplied {
+            recipients: split.recipients.clone(),
+        });
+    }
+
+    Ok(())
+}
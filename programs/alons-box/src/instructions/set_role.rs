@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::RoleUpdated;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct SetRole<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&admin.key(), ROLE_CONFIG_ADMIN) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+}
+
+pub fn handler(ctx: Context<SetRole>, member: Pubkey, capabilities: u8) -> Result<()> {
+    require!(capabilities & !ROLE_ALL == 0, AlonsBoxError::InvalidCapabilities);
+
+    // Revocations are instant so a compromised key can be cut off at once;
+    // adding bits goes through queue_change and waits out the timelock
+    let previous = ctx.accounts.roles.capabilities(&member);
+    require!(
+        capabilities & !previous == 0,
+        AlonsBoxError::RoleGrantTimelocked
+    );
+    ctx.accounts.roles.set(member, capabilities)?;
+
+    emit!(RoleUpdated {
+        member,
+        previous,
+        capabilities,
+        admin: ctx.accounts.admin.key(),
+    });
+
+    Ok(())
+}
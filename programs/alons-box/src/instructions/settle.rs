@@ -1,9 +1,14 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::hash::hash;
 use crate::errors::AlonsBoxError;
-use crate::events::RoundSettled;
+use crate::events::{RoundSettled, RoundSettledV2};
+use crate::profile::update_profile;
+use crate::season::close_season_round;
 use crate::state::*;
-use crate::utils::transfer_from_vault;
+use crate::stream::pay_treasury;
+use crate::utils::{
+    bps_of, bps_shares, route_rollover_overflow, transfer_from_vault, winner_shares, write_settlement,
+};
 
 #[derive(Accounts)]
 pub struct Settle<'info> {
@@ -14,15 +19,23 @@ pub struct Settle<'info> {
         mut,
         seeds = [b"game_state"],
         bump = game_state.bump,
-        constraint = game_state.authority == authority.key() @ AlonsBoxError::Unauthorized,
+        constraint = !game_state.is_paused(PAUSE_SETTLEMENT) @ AlonsBoxError::SettlementPaused,
     )]
     pub game_state: Account<'info, GameState>,
 
+    #[account(
+        seeds = [b"roles"],
+        bump = roles.bump,
+        constraint = roles.has(&authority.key(), ROLE_SETTLER) @ AlonsBoxError::Unauthorized,
+    )]
+    pub roles: Account<'info, Roles>,
+
     #[account(
         mut,
         seeds = [b"round", round.round_id.to_le_bytes().as_ref()],
         bump = round.bump,
         constraint = round.status == RoundStatus::Active @ AlonsBoxError::RoundNotActive,
+        constraint = round.min_pool_met() @ AlonsBoxError::BelowMinPool,
     )]
     pub round: Account<'info, Round>,
 
@@ -33,26 +46,63 @@ pub struct Settle<'info> {
     )]
     pub vault: Account<'info, Vault>,
 
-    /// CHECK: Winner wallet — receives 50% of pool
-    #[account(mut)]
-    pub winner: AccountInfo<'info>,
+    /// CHECK: Settlement PDA — created in the handler after the round is validated
+    #[account(
+        mut,
+        seeds = [b"settlement", round.round_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub settlement: UncheckedAccount<'info>,
 
-    /// CHECK: Treasury — receives 5% of pool
+    /// CHECK: Treasury — receives payout.treasury_bps of pool (vested first if treasury_stream exists)
     #[account(
         mut,
         constraint = treasury.key() == game_state.treasury @ AlonsBoxError::Unauthorized,
     )]
     pub treasury: AccountInfo<'info>,
 
+    /// CHECK: TreasuryStream PDA — may be uninitialized, in which case the
+    /// treasury cut is paid to `treasury` directly
+    #[account(
+        mut,
+        seeds = [b"treasury_stream"],
+        bump,
+    )]
+    pub treasury_stream: UncheckedAccount<'info>,
+
+    /// CHECK: Buyback wallet — receives rollover above the cap under the Buyback policy
+    #[account(
+        mut,
+        constraint = buyback_wallet.key() == game_state.buyback_wallet @ AlonsBoxError::Unauthorized,
+    )]
+    pub buyback_wallet: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"jackpot_pool"],
+        bump = jackpot_pool.bump,
+    )]
+    pub jackpot_pool: Account<'info, JackpotPool>,
+
+    /// Season the round belongs to — required when round.season_id is set;
+    /// receives season_bps of the pool
+    #[account(mut)]
+    pub season: Option<Account<'info, Season>>,
+
     pub system_program: Program<'info, System>,
-    // remaining_accounts: evidence wallets (writable, unchecked)
+    // remaining_accounts: winner_count winner wallets (writable), then one
+    // Deposit PDA (LargestDeposit) or Guess PDA (EarliestGuess) per winner, then [evidence wallet
+    // (writable), Question PDA] pairs, then optionally one PlayerProfile PDA
+    // (writable) per winner and per evidence wallet
 }
 
 pub fn handler<'a>(
     ctx: Context<'_, '_, 'a, 'a, Settle<'a>>,
     answer: String,
     salt: String,
-    evidence_amounts: Vec<u64>,
+    winner_count: u8,
+    evidence_weights: Vec<u16>,
+    tie_break: TieBreakPolicy,
 ) -> Result<()> {
     require!(answer.len() <= 64, AlonsBoxError::AnswerTooLong);
     require!(salt.len() <= 64, AlonsBoxError::SaltTooLong);
@@ -65,38 +115,105 @@ pub fn handler<'a>(
         AlonsBoxError::InvalidCommitHash
     );
 
-    // ── Verify evidence accounts match amounts ──
+    // ── Verify remaining accounts: N winners, their Deposits or Guesses, then
+    //    (wallet + Question PDA) per evidence entry, then optionally a profile per winner
+    //    and evidence wallet ──
+    let winner_count = winner_count as usize;
+    require!(
+        winner_count > 0 && winner_count <= MAX_WINNERS,
+        AlonsBoxError::InvalidWinnerWeights
+    );
+    let per_winner_len = match tie_break {
+        TieBreakPolicy::EqualSplit => 0,
+        TieBreakPolicy::LargestDeposit | TieBreakPolicy::EarliestGuess => winner_count,
+    };
+    let payout_len = winner_count + per_winner_len + evidence_weights.len() * 2;
+    let profile_len = winner_count + evidence_weights.len();
     require!(
-        ctx.remaining_accounts.len() == evidence_amounts.len(),
+        ctx.remaining_accounts.len() == payout_len
+            || ctx.remaining_accounts.len() == payout_len + profile_len,
         AlonsBoxError::EvidenceMismatch
     );
+    let (payout_accounts, profile_accounts) = ctx.remaining_accounts.split_at(payout_len);
+    let (winner_accounts, rest) = payout_accounts.split_at(winner_count);
+    let (per_winner_accounts, evidence_accounts) = rest.split_at(per_winner_len);
+
+    // ── Verify the season: the one the round was created in, and only that ──
+    close_season_round(ctx.accounts.season.as_mut(), &ctx.accounts.round)?;
+
+    // ── Verify winners: no duplicates ──
+    for (i, winner) in winner_accounts.iter().enumerate() {
+        require!(
+            winner_accounts[..i].iter().all(|w| w.key() != winner.key()),
+            AlonsBoxError::DuplicateWinner
+        );
+    }
+
+    // ── Verify each winner's Deposit, or Guess of the answer, in this round ──
+    let round_id = ctx.accounts.round.round_id;
+    let answer_hash = hash(answer.as_bytes()).to_bytes();
+    let mut per_winner = Vec::with_capacity(per_winner_len);
+    for (winner, info) in winner_accounts.iter().zip(per_winner_accounts) {
+        if tie_break == TieBreakPolicy::EarliestGuess {
+            let guess = Account::<Guess>::try_from(info)
+                .map_err(|_| AlonsBoxError::WinnerGuessMismatch)?;
+            require!(
+                guess.round_id == round_id
+                    && guess.player == winner.key()
+                    && guess.guess_hash == answer_hash,
+                AlonsBoxError::WinnerGuessMismatch
+            );
+            per_winner.push(guess.slot);
+        } else {
+            let deposit = Account::<Deposit>::try_from(info)
+                .map_err(|_| AlonsBoxError::WinnerDepositMismatch)?;
+            require!(
+                deposit.round_id == round_id && deposit.user == winner.key(),
+                AlonsBoxError::WinnerDepositMismatch
+            );
+            per_winner.push(deposit.amount);
+        }
+    }
 
-    // ── Calculate pool and payouts (BPS) ──
+    // ── Verify each evidence wallet appears once and holds a YES question in this round ──
+    for (i, pair) in evidence_accounts.chunks(2).enumerate() {
+        require!(
+            evidence_accounts[..i * 2].chunks(2).all(|p| p[0].key() != pair[0].key()),
+            AlonsBoxError::DuplicateEvidence
+        );
+        let question = Account::<Question>::try_from(&pair[1])
+            .map_err(|_| AlonsBoxError::IneligibleEvidence)?;
+        require!(
+            question.round_id == round_id
+                && question.asker == pair[0].key()
+                && question.is_yes,
+            AlonsBoxError::IneligibleEvidence
+        );
+    }
+
+    // ── Calculate pool and payouts (BPS from game_state.payout) ──
+    let payout = ctx.accounts.game_state.payout;
     let round = &ctx.accounts.round;
     let pool = round
         .total_deposits
         .checked_add(round.rollover_in)
         .ok_or(AlonsBoxError::MathOverflow)?;
 
-    let winner_amount = pool
-        .checked_mul(5000)
-        .ok_or(AlonsBoxError::MathOverflow)?
-        .checked_div(10000)
-        .ok_or(AlonsBoxError::MathOverflow)?;
+    let winner_pool = bps_of(pool, payout.winner_bps)?;
+    let evidence_pool = bps_of(pool, payout.evidence_bps)?;
+    let treasury_amount = bps_of(pool, payout.treasury_bps)?;
 
-    let evidence_pool = pool
-        .checked_mul(3000)
-        .ok_or(AlonsBoxError::MathOverflow)?
-        .checked_div(10000)
+    // Winner shares follow the tie-break policy; any rounding dust rolls over
+    let winner_amounts = winner_shares(winner_pool, winner_count, tie_break, &per_winner)?;
+    let total_winner: u64 = winner_amounts
+        .iter()
+        .try_fold(0u64, |acc, &x| acc.checked_add(x))
         .ok_or(AlonsBoxError::MathOverflow)?;
 
-    let treasury_amount = pool
-        .checked_mul(500)
-        .ok_or(AlonsBoxError::MathOverflow)?
-        .checked_div(10000)
-        .ok_or(AlonsBoxError::MathOverflow)?;
+    // Evidence shares are computed on-chain from BPS weights of the evidence pool
+    let evidence_amounts = bps_shares(evidence_pool, &evidence_weights)?;
 
-    // Verify evidence doesn't exceed 30% (F-01: use checked arithmetic)
+    // Verify evidence doesn't exceed the evidence pool (F-01: use checked arithmetic)
     let total_evidence: u64 = evidence_amounts
         .iter()
         .try_fold(0u64, |acc, &x| acc.checked_add(x))
@@ -106,29 +223,124 @@ pub fn handler<'a>(
     // ── Distribute from vault (program-owned PDA) ──
     let vault_info = ctx.accounts.vault.to_account_info();
 
-    // Winner (50%)
-    transfer_from_vault(&vault_info, &ctx.accounts.winner, winner_amount)?;
+    // Winners (split by tie-break policy)
+    for (winner, &amount) in winner_accounts.iter().zip(winner_amounts.iter()) {
+        transfer_from_vault(&vault_info, winner, amount)?;
+    }
 
-    // Evidence wallets (up to 30%)
-    for (i, evidence_wallet) in ctx.remaining_accounts.iter().enumerate() {
+    // Evidence wallets (up to the evidence pool)
+    for (i, pair) in evidence_accounts.chunks(2).enumerate() {
         if evidence_amounts[i] > 0 {
-            transfer_from_vault(&vault_info, evidence_wallet, evidence_amounts[i])?;
+            transfer_from_vault(&vault_info, &pair[0], evidence_amounts[i])?;
+        }
+    }
+
+    // Referral slice of the treasury cut stays in the vault for referrers
+    let referral_amount = ctx
+        .accounts
+        .round
+        .withhold_referrals(&mut ctx.accounts.game_state, payout.treasury_bps)?;
+
+    // Lifetime stats of winners and evidence wallets that have a profile
+    if !profile_accounts.is_empty() {
+        let (winner_profiles, evidence_profiles) = profile_accounts.split_at(winner_count);
+        for ((winner, profile), &amount) in winner_accounts
+            .iter()
+            .zip(winner_profiles)
+            .zip(winner_amounts.iter())
+        {
+            update_profile(profile, winner.key, |p| p.record_win(amount))?;
+        }
+        for ((pair, profile), &amount) in evidence_accounts
+            .chunks(2)
+            .zip(evidence_profiles)
+            .zip(evidence_amounts.iter())
+        {
+            if amount > 0 {
+                update_profile(profile, pair[0].key, |p| p.record_evidence(amount))?;
+            }
         }
     }
 
-    // Treasury (5%)
-    transfer_from_vault(&vault_info, &ctx.accounts.treasury, treasury_amount)?;
+    // Treasury (vests in the stream when one exists)
+    pay_treasury(
+        &ctx.accounts.treasury_stream.to_account_info(),
+        &vault_info,
+        &ctx.accounts.treasury,
+        treasury_amount
+            .checked_sub(referral_amount)
+            .ok_or(AlonsBoxError::MathOverflow)?,
+    )?;
+
+    // ── Season: prize pool slice and leaderboard ──
+    let season_amount = match ctx.accounts.season.as_mut() {
+        Some(season) => {
+            season.accept_round(round_id)?;
+            let amount = bps_of(pool, season.season_bps)?;
+            transfer_from_vault(&vault_info, &season.to_account_info(), amount)?;
+            season.prize_pool = season
+                .prize_pool
+                .checked_add(amount)
+                .ok_or(AlonsBoxError::MathOverflow)?;
+            for (winner, &won) in winner_accounts.iter().zip(winner_amounts.iter()) {
+                season.record_win(winner.key(), won)?;
+            }
+            amount
+        }
+        None => 0,
+    };
 
-    // ── Update rollover: residual captures rounding dust ──
+    // ── Update rollover: residual captures rounding dust and unweighted evidence ──
     let rollover_out = pool
-        .checked_sub(winner_amount)
+        .checked_sub(total_winner)
         .ok_or(AlonsBoxError::MathOverflow)?
         .checked_sub(total_evidence)
         .ok_or(AlonsBoxError::MathOverflow)?
         .checked_sub(treasury_amount)
+        .ok_or(AlonsBoxError::MathOverflow)?
+        .checked_sub(season_amount)
         .ok_or(AlonsBoxError::MathOverflow)?;
+
+    // ── Anything above the rollover cap leaves the vault ──
+    let split = route_rollover_overflow(
+        ctx.accounts.round.round_id,
+        rollover_out,
+        &ctx.accounts.game_state,
+        &vault_info,
+        &mut ctx.accounts.jackpot_pool,
+        &ctx.accounts.buyback_wallet,
+    )?;
+    let rollover_out = split.rollover_out;
     ctx.accounts.game_state.rollover_balance = rollover_out;
 
+    // ── Persist the payout breakdown ──
+    let winners: Vec<Pubkey> = winner_accounts.iter().map(|w| w.key()).collect();
+    let round = &ctx.accounts.round;
+    write_settlement(
+        &ctx.accounts.settlement.to_account_info(),
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &Settlement {
+            round_id: round.round_id,
+            outcome: RoundStatus::Settled,
+            commit_hash: round.commit_hash,
+            answer_hash,
+            pool,
+            rollover_in: round.rollover_in,
+            rollover_out,
+            winners: winners.clone(),
+            winner_amounts: winner_amounts.clone(),
+            evidence_total: total_evidence,
+            treasury_amount,
+            buyback_amount: split.to_buyback,
+            jackpot_amount: split.to_jackpot,
+            season_amount,
+            finalized_at: Clock::get()?.unix_timestamp,
+            rent_payer: ctx.accounts.authority.key(),
+            bump: ctx.bumps.settlement,
+        },
+    )?;
+
     // ── Update round state ──
     let round = &mut ctx.accounts.round;
     round.status = RoundStatus::Settled;
@@ -137,9 +349,20 @@ pub fn handler<'a>(
 
     emit!(RoundSettled {
         round_id: round.round_id,
-        winner: ctx.accounts.winner.key(),
+        winner: winners[0],
+        pool,
+        winner_amount: total_winner,
+        evidence_total: total_evidence,
+        treasury_amount,
+        rollover_out,
+    });
+    emit!(RoundSettledV2 {
+        round_id: round.round_id,
+        winners,
+        winner_amounts,
+        tie_break,
         pool,
-        winner_amount,
+        winner_total: total_winner,
         evidence_total: total_evidence,
         treasury_amount,
         rollover_out,
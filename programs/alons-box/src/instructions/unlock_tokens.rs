@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::errors::AlonsBoxError;
+use crate::events::TokensUnlocked;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct UnlockTokens<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"governance"],
+        bump = governance.bump,
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [b"vote_escrow", owner.key().as_ref()],
+        bump = vote_escrow.bump,
+    )]
+    pub vote_escrow: Account<'info, VoteEscrow>,
+
+    #[account(
+        mut,
+        constraint = owner_tokens.mint == governance.mint @ ErrorCode::ConstraintTokenMint,
+    )]
+    pub owner_tokens: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"governance_vault"],
+        bump = governance.vault_bump,
+    )]
+    pub governance_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<UnlockTokens>, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let escrow = &mut ctx.accounts.vote_escrow;
+    require!(now >= escrow.locked_until, AlonsBoxError::TokensStillLocked);
+    require!(
+        amount > 0 && amount <= escrow.amount,
+        AlonsBoxError::InsufficientLockedTokens
+    );
+    escrow.amount -= amount;
+
+    let governance = &mut ctx.accounts.governance;
+    governance.total_locked = governance
+        .total_locked
+        .checked_sub(amount)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+
+    let seeds: &[&[u8]] = &[b"governance", &[governance.bump]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.governance_vault.to_account_info(),
+                to: ctx.accounts.owner_tokens.to_account_info(),
+                authority: governance.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )?;
+
+    emit!(TokensUnlocked {
+        owner: escrow.owner,
+        amount,
+        remaining: escrow.amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::VestedWithdrawn;
+use crate::state::*;
+use crate::utils::transfer_from_vault;
+
+#[derive(Accounts)]
+pub struct WithdrawVested<'info> {
+    /// Anyone can push vested funds to the treasury
+    pub caller: Signer<'info>,
+
+    #[account(
+        seeds = [b"game_state"],
+        bump = game_state.bump,
+    )]
+    pub game_state: Account<'info, GameState>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury_stream"],
+        bump = treasury_stream.bump,
+    )]
+    pub treasury_stream: Account<'info, TreasuryStream>,
+
+    #[account(
+        mut,
+        seeds = [b"vault"],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: Treasury — receives the vested amount
+    #[account(
+        mut,
+        constraint = treasury.key() == game_state.treasury @ AlonsBoxError::Unauthorized,
+    )]
+    pub treasury: AccountInfo<'info>,
+}
+
+pub fn handler(ctx: Context<WithdrawVested>) -> Result<()> {
+    let stream = &mut ctx.accounts.treasury_stream;
+    stream.accrue(Clock::get()?.unix_timestamp);
+    let amount = stream.vested;
+    require!(amount > 0, AlonsBoxError::NothingVested);
+
+    stream.vested = 0;
+    stream.total_withdrawn = stream
+        .total_withdrawn
+        .checked_add(amount)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    transfer_from_vault(
+        &ctx.accounts.vault.to_account_info(),
+        &ctx.accounts.treasury,
+        amount,
+    )?;
+
+    emit!(VestedWithdrawn {
+        amount,
+        treasury: ctx.accounts.treasury.key(),
+        unvested: stream.unvested,
+    });
+
+    Ok(())
+}
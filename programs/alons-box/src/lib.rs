@@ -8,13 +8,23 @@
 
 use anchor_lang::prelude::*;
 
+pub mod archive;
 pub mod errors;
 pub mod events;
 pub mod instructions;
+pub mod limits;
+pub mod profile;
+pub mod referral;
+pub mod roles;
+pub mod season;
 pub mod state;
+pub mod stream;
 pub mod utils;
 
 use instructions::*;
+use state::{
+    BuybackMode, ConfigChange, DepositPolicy, PayoutConfig, SoftClose, SplitRecipient, TieBreakPolicy,
+};
 
 declare_id!("J5LMxDvUSz5Agbo3bjpJZN17p4BNfqGNbrhU5vqNYrEa");
 
@@ -22,30 +32,192 @@ declare_id!("J5LMxDvUSz5Agbo3bjpJZN17p4BNfqGNbrhU5vqNYrEa");
 pub mod alons_box {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, treasury: Pubkey, buyback_wallet: Pubkey) -> Result<()> {
-        instructions::initialize::handler(ctx, treasury, buyback_wallet)
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        treasury: Pubkey,
+        buyback_wallet: Pubkey,
+        guardian: Pubkey,
+    ) -> Result<()> {
+        instructions::initialize::handler(ctx, treasury, buyback_wallet, guardian)
+    }
+
+    pub fn set_role(ctx: Context<SetRole>, member: Pubkey, capabilities: u8) -> Result<()> {
+        instructions::set_role::handler(ctx, member, capabilities)
+    }
+
+    pub fn queue_change(ctx: Context<QueueChange>, change: ConfigChange) -> Result<()> {
+        instructions::queue_change::handler(ctx, change)
+    }
+
+    pub fn execute_change(ctx: Context<ExecuteChange>) -> Result<()> {
+        instructions::execute_change::handler(ctx)
+    }
+
+    pub fn cancel_change(ctx: Context<CancelChange>) -> Result<()> {
+        instructions::cancel_change::handler(ctx)
+    }
+
+    pub fn init_governance(
+        ctx: Context<InitGovernance>,
+        quorum_bps: u16,
+        approval_bps: u16,
+        voting_period: i64,
+        proposal_threshold: u64,
+    ) -> Result<()> {
+        instructions::init_governance::handler(ctx, quorum_bps, approval_bps, voting_period, proposal_threshold)
+    }
+
+    pub fn lock_tokens(ctx: Context<LockTokens>, amount: u64) -> Result<()> {
+        instructions::lock_tokens::handler(ctx, amount)
+    }
+
+    pub fn unlock_tokens(ctx: Context<UnlockTokens>, amount: u64) -> Result<()> {
+        instructions::unlock_tokens::handler(ctx, amount)
+    }
+
+    pub fn create_proposal(ctx: Context<CreateProposal>, payout: PayoutConfig) -> Result<()> {
+        instructions::create_proposal::handler(ctx, payout)
+    }
+
+    pub fn cast_vote(ctx: Context<CastVote>, support: bool) -> Result<()> {
+        instructions::cast_vote::handler(ctx, support)
+    }
+
+    pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
+        instructions::finalize_proposal::handler(ctx)
+    }
+
+    pub fn init_buyback(
+        ctx: Context<InitBuyback>,
+        mode: BuybackMode,
+        max_slippage_bps: u16,
+        max_sol_per_buyback: u64,
+    ) -> Result<()> {
+        instructions::init_buyback::handler(ctx, mode, max_slippage_bps, max_sol_per_buyback)
+    }
+
+    pub fn execute_buyback(ctx: Context<ExecuteBuyback>, min_tokens_out: u64) -> Result<()> {
+        instructions::execute_buyback::handler(ctx, min_tokens_out)
+    }
+
+    pub fn set_revenue_split(ctx: Context<SetRevenueSplit>, recipients: Vec<SplitRecipient>) -> Result<()> {
+        instructions::set_revenue_split::handler(ctx, recipients)
+    }
+
+    pub fn distribute_treasury<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeTreasury<'info>>,
+    ) -> Result<()> {
+        instructions::distribute_treasury::handler(ctx)
+    }
+
+    pub fn init_treasury_stream(ctx: Context<InitTreasuryStream>, vesting_period: i64) -> Result<()> {
+        instructions::init_treasury_stream::handler(ctx, vesting_period)
+    }
+
+    pub fn withdraw_vested(ctx: Context<WithdrawVested>) -> Result<()> {
+        instructions::withdraw_vested::handler(ctx)
+    }
+
+    pub fn create_season(
+        ctx: Context<CreateSeason>,
+        season_id: u64,
+        start_round_id: u64,
+        end_round_id: u64,
+        season_bps: u16,
+        prize_curve: Vec<u16>,
+    ) -> Result<()> {
+        instructions::create_season::handler(
+            ctx,
+            season_id,
+            start_round_id,
+            end_round_id,
+            season_bps,
+            prize_curve,
+        )
+    }
+
+    pub fn finalize_season<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FinalizeSeason<'info>>,
+    ) -> Result<()> {
+        instructions::finalize_season::handler(ctx)
+    }
+
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        instructions::register_referrer::handler(ctx)
+    }
+
+    pub fn credit_referral(ctx: Context<CreditReferral>) -> Result<()> {
+        instructions::credit_referral::handler(ctx)
+    }
+
+    pub fn claim_referral(ctx: Context<ClaimReferral>) -> Result<()> {
+        instructions::claim_referral::handler(ctx)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+        instructions::set_paused::handler(ctx, paused)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn create_round(
         ctx: Context<CreateRound>,
         round_id: u64,
         commit_hash: [u8; 32],
         ends_at: i64,
+        soft_close: Option<SoftClose>,
+        min_pool: Option<u64>,
+        jackpot: bool,
+        deposit_policy: Option<DepositPolicy>,
     ) -> Result<()> {
-        instructions::create_round::handler(ctx, round_id, commit_hash, ends_at)
+        instructions::create_round::handler(
+            ctx,
+            round_id,
+            commit_hash,
+            ends_at,
+            soft_close,
+            min_pool,
+            jackpot,
+            deposit_policy,
+        )
     }
 
-    pub fn deposit(ctx: Context<DepositCtx>, amount: u64) -> Result<()> {
-        instructions::deposit::handler(ctx, amount)
+    pub fn deposit(ctx: Context<DepositCtx>, amount: u64, referrer: Option<Pubkey>) -> Result<()> {
+        instructions::deposit::handler(ctx, amount, referrer)
+    }
+
+    pub fn set_player_limits(
+        ctx: Context<SetPlayerLimits>,
+        daily_cap: u64,
+        weekly_cap: u64,
+        round_cap: u64,
+        excluded_until: i64,
+    ) -> Result<()> {
+        instructions::set_player_limits::handler(ctx, daily_cap, weekly_cap, round_cap, excluded_until)
+    }
+
+    pub fn record_question(
+        ctx: Context<RecordQuestion>,
+        question_hash: [u8; 32],
+        is_public: bool,
+        answer_hash: [u8; 32],
+        is_yes: bool,
+    ) -> Result<()> {
+        instructions::record_question::handler(ctx, question_hash, is_public, answer_hash, is_yes)
+    }
+
+    pub fn record_guess(ctx: Context<RecordGuess>, guess_hash: [u8; 32]) -> Result<()> {
+        instructions::record_guess::handler(ctx, guess_hash)
     }
 
     pub fn settle<'info>(
         ctx: Context<'_, '_, 'info, 'info, Settle<'info>>,
         answer: String,
         salt: String,
-        evidence_amounts: Vec<u64>,
+        winner_count: u8,
+        evidence_weights: Vec<u16>,
+        tie_break: TieBreakPolicy,
     ) -> Result<()> {
-        instructions::settle::handler(ctx, answer, salt, evidence_amounts)
+        instructions::settle::handler(ctx, answer, salt, winner_count, evidence_weights, tie_break)
     }
 
     pub fn expire(ctx: Context<Expire>, answer: String, salt: String) -> Result<()> {
@@ -56,10 +228,26 @@ pub mod alons_box {
         instructions::emergency_expire::handler(ctx)
     }
 
+    pub fn fail_round(ctx: Context<FailRound>) -> Result<()> {
+        instructions::fail_round::handler(ctx)
+    }
+
+    pub fn claim_refund(ctx: Context<ClaimRefund>) -> Result<()> {
+        instructions::claim_refund::handler(ctx)
+    }
+
     pub fn close_deposit(ctx: Context<CloseDeposit>) -> Result<()> {
         instructions::close_deposit::handler(ctx)
     }
 
+    pub fn close_question(ctx: Context<CloseQuestion>) -> Result<()> {
+        instructions::close_question::handler(ctx)
+    }
+
+    pub fn close_guess(ctx: Context<CloseGuess>) -> Result<()> {
+        instructions::close_guess::handler(ctx)
+    }
+
     pub fn close_round(ctx: Context<CloseRound>) -> Result<()> {
         instructions::close_round::handler(ctx)
     }
@@ -0,0 +1,141 @@
+//! Responsible-gaming limits behind the `PlayerLimits` PDA.
+//!
+//! A new request that tightens a cap (or lengthens self-exclusion) takes
+//! effect at once. Anything looser is staged in `pending` and only applies
+//! after `LIMIT_COOLDOWN_SECS`. Deposit totals are tracked in ring buffers:
+//! 24 hour buckets for the daily window and 7 day buckets for the weekly
+//! window. Each window therefore ends on the current bucket and reaches back
+//! to the start of the oldest one.
+
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::state::{PendingLimits, PlayerLimits};
+
+/// Delay before a looser limit replaces a tighter one (24 hours)
+pub const LIMIT_COOLDOWN_SECS: i64 = 24 * 60 * 60;
+
+const HOUR_SECS: i64 = 60 * 60;
+const DAY_SECS: i64 = 24 * HOUR_SECS;
+
+/// 0 means "no limit".
+fn effective_cap(cap: u64) -> u64 {
+    if cap == 0 {
+        u64::MAX
+    } else {
+        cap
+    }
+}
+
+/// Keep whichever of the two caps is stricter.
+fn tighter(current: u64, requested: u64) -> u64 {
+    if effective_cap(requested) <= effective_cap(current) {
+        requested
+    } else {
+        current
+    }
+}
+
+/// Zero every bucket whose slot started after `last` and up to `current`.
+fn clear_elapsed(buckets: &mut [u64], last: i64, current: i64) {
+    let len = buckets.len() as i64;
+    if current.saturating_sub(last) >= len {
+        buckets.fill(0);
+        return;
+    }
+    for slot in (last + 1)..=current {
+        buckets[slot.rem_euclid(len) as usize] = 0;
+    }
+}
+
+impl PlayerLimits {
+    /// Apply a matured pending change. Returns true if one was applied.
+    pub fn apply_pending(&mut self, now: i64) -> bool {
+        if self.pending.effective_at == 0 || now < self.pending.effective_at {
+            return false;
+        }
+        self.daily_cap = self.pending.daily_cap;
+        self.weekly_cap = self.pending.weekly_cap;
+        self.round_cap = self.pending.round_cap;
+        self.pending = PendingLimits::default();
+        true
+    }
+
+    /// Record a player's requested limits. The request replaces any earlier
+    /// pending change.
+    pub fn request(
+        &mut self,
+        now: i64,
+        daily_cap: u64,
+        weekly_cap: u64,
+        round_cap: u64,
+        excluded_until: i64,
+    ) -> Result<()> {
+        require!(
+            excluded_until >= self.excluded_until || self.excluded_until <= now,
+            AlonsBoxError::SelfExclusionLocked
+        );
+        self.excluded_until = excluded_until;
+
+        self.apply_pending(now);
+        let loosens = effective_cap(daily_cap) > effective_cap(self.daily_cap)
+            || effective_cap(weekly_cap) > effective_cap(self.weekly_cap)
+            || effective_cap(round_cap) > effective_cap(self.round_cap);
+
+        self.daily_cap = tighter(self.daily_cap, daily_cap);
+        self.weekly_cap = tighter(self.weekly_cap, weekly_cap);
+        self.round_cap = tighter(self.round_cap, round_cap);
+
+        self.pending = if loosens {
+            PendingLimits {
+                daily_cap,
+                weekly_cap,
+                round_cap,
+                effective_at: now
+                    .checked_add(LIMIT_COOLDOWN_SECS)
+                    .ok_or(AlonsBoxError::MathOverflow)?,
+            }
+        } else {
+            PendingLimits::default()
+        };
+        Ok(())
+    }
+
+    /// Check a deposit against the limits and add it to the rolling windows.
+    /// `round_total` is the player's total in the round including `amount`.
+    pub fn record_deposit(&mut self, now: i64, amount: u64, round_total: u64) -> Result<()> {
+        self.apply_pending(now);
+        require!(now >= self.excluded_until, AlonsBoxError::SelfExcluded);
+        require!(
+            round_total <= effective_cap(self.round_cap),
+            AlonsBoxError::RoundLimitExceeded
+        );
+
+        let hour = now.div_euclid(HOUR_SECS);
+        let day = now.div_euclid(DAY_SECS);
+        clear_elapsed(&mut self.hour_buckets, self.last_deposit_at.div_euclid(HOUR_SECS), hour);
+        clear_elapsed(&mut self.day_buckets, self.last_deposit_at.div_euclid(DAY_SECS), day);
+
+        let daily = window_total(&self.hour_buckets, amount)?;
+        require!(daily <= effective_cap(self.daily_cap), AlonsBoxError::DailyLimitExceeded);
+        let weekly = window_total(&self.day_buckets, amount)?;
+        require!(weekly <= effective_cap(self.weekly_cap), AlonsBoxError::WeeklyLimitExceeded);
+
+        let hour_slot = hour.rem_euclid(self.hour_buckets.len() as i64) as usize;
+        let day_slot = day.rem_euclid(self.day_buckets.len() as i64) as usize;
+        self.hour_buckets[hour_slot] = self.hour_buckets[hour_slot]
+            .checked_add(amount)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+        self.day_buckets[day_slot] = self.day_buckets[day_slot]
+            .checked_add(amount)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+        self.last_deposit_at = self.last_deposit_at.max(now);
+        Ok(())
+    }
+}
+
+fn window_total(buckets: &[u64], amount: u64) -> Result<u64> {
+    buckets
+        .iter()
+        .try_fold(amount, |total, bucket| total.checked_add(*bucket))
+        .ok_or_else(|| error!(AlonsBoxError::MathOverflow))
+}
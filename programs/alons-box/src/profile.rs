@@ -0,0 +1,68 @@
+//! Cross-round player statistics behind the `PlayerProfile` PDA.
+//!
+//! `deposit` creates the profile and counts rounds and lamports deposited.
+//! `settle` credits wins and evidence payouts to the profiles passed after
+//! its payout accounts; `claim_refund` records refunds. Profiles outlive
+//! the Deposit PDAs, which are closed once a round is over.
+
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::state::PlayerProfile;
+
+impl PlayerProfile {
+    /// Count `amount` deposited into `round_id`; `first` is the player's
+    /// first deposit into that round.
+    pub fn record_deposit(&mut self, round_id: u64, amount: u64, first: bool) -> Result<()> {
+        if first {
+            self.rounds_played += 1;
+            self.last_round_id = round_id;
+        }
+        self.total_deposited = self
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_win(&mut self, amount: u64) -> Result<()> {
+        self.wins += 1;
+        self.total_winnings = self
+            .total_winnings
+            .checked_add(amount)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_evidence(&mut self, amount: u64) -> Result<()> {
+        self.evidence_earned = self
+            .evidence_earned
+            .checked_add(amount)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_refund(&mut self, amount: u64) -> Result<()> {
+        self.total_refunded = self
+            .total_refunded
+            .checked_add(amount)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+/// Apply `update` to `wallet`'s profile. A profile that was never created
+/// (the wallet has not deposited) is skipped; any other program-owned
+/// account must be that wallet's PlayerProfile.
+pub fn update_profile(
+    profile_info: &AccountInfo,
+    wallet: &Pubkey,
+    update: impl FnOnce(&mut PlayerProfile) -> Result<()>,
+) -> Result<()> {
+    if profile_info.owner != &crate::ID || profile_info.data_is_empty() {
+        return Ok(());
+    }
+    let mut profile = PlayerProfile::try_deserialize(&mut &profile_info.try_borrow_data()?[..])?;
+    require!(profile.player == *wallet, AlonsBoxError::ProfileMismatch);
+    update(&mut profile)?;
+    profile.try_serialize(&mut &mut profile_info.try_borrow_mut_data()?[..])
+}
@@ -0,0 +1,106 @@
+//! Referral attribution and fee share.
+//!
+//! A player is bound to a referrer by the first deposit that names one, via
+//! the `PlayerReferral` PDA. Each later deposit copies the binding into the
+//! Deposit PDA and counts toward `Round.referred_deposits`. When the round
+//! settles or expires, `referral_bps` of the treasury cut on those deposits
+//! is withheld in the vault as `GameState.referral_liability`;
+//! `credit_referral` then moves each deposit's pro-rata share to its
+//! referrer, who withdraws it with `claim_referral`.
+
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::events::PlayerReferred;
+use crate::state::{GameState, PlayerReferral, Referrer, Round};
+use crate::utils::{bps_of, create_pda_account};
+
+impl Round {
+    /// Withhold the referral slice of this round's treasury cut, charged at
+    /// `treasury_bps` on referred deposits. Returns the amount withheld.
+    pub fn withhold_referrals(&mut self, game_state: &mut GameState, treasury_bps: u16) -> Result<u64> {
+        let reserve = bps_of(
+            bps_of(self.referred_deposits, treasury_bps)?,
+            game_state.referral_bps,
+        )?;
+        self.referral_reserve = reserve;
+        game_state.referral_liability = game_state
+            .referral_liability
+            .checked_add(reserve)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+        Ok(reserve)
+    }
+
+    /// Credit the share of `referral_reserve` owed for a referred deposit of
+    /// `amount`. Shares are pro rata and rounded down; the last deposit
+    /// credited takes the remainder so the reserve is used exactly.
+    pub fn credit_referral_share(&mut self, amount: u64) -> Result<u64> {
+        let uncredited = self
+            .referred_deposits
+            .checked_sub(self.referred_credited)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+        let share = if amount >= uncredited {
+            self.referral_reserve - self.referral_credited
+        } else {
+            (self.referral_reserve as u128 * amount as u128 / self.referred_deposits as u128) as u64
+        };
+        self.referred_credited += amount;
+        self.referral_credited += share;
+        Ok(share)
+    }
+
+    /// Every referral share of this round has been credited.
+    pub fn referrals_credited(&self) -> bool {
+        self.referral_credited == self.referral_reserve
+    }
+}
+
+/// Resolve the referrer a deposit is attributed to (`Pubkey::default()` for
+/// none). An existing binding always wins; otherwise a `requested` referrer
+/// is bound permanently, which needs its registered Referrer account.
+pub fn resolve_referrer<'info>(
+    player: &AccountInfo<'info>,
+    binding_info: &AccountInfo<'info>,
+    binding_bump: u8,
+    referrer: Option<&mut Account<'info, Referrer>>,
+    requested: Option<Pubkey>,
+    system_program: &AccountInfo<'info>,
+) -> Result<Pubkey> {
+    if binding_info.owner == &crate::ID && !binding_info.data_is_empty() {
+        let binding = PlayerReferral::try_deserialize(&mut &binding_info.try_borrow_data()?[..])?;
+        if let Some(key) = requested {
+            require!(key == binding.referrer, AlonsBoxError::ReferrerMismatch);
+        }
+        return Ok(binding.referrer);
+    }
+
+    let Some(requested) = requested else {
+        return Ok(Pubkey::default());
+    };
+    require!(requested != player.key(), AlonsBoxError::SelfReferral);
+    let referrer = referrer.ok_or(AlonsBoxError::ReferrerMismatch)?;
+    require!(referrer.owner == requested, AlonsBoxError::ReferrerMismatch);
+    referrer.referred_players += 1;
+
+    let player_key = player.key();
+    create_pda_account(
+        player,
+        binding_info,
+        system_program,
+        PlayerReferral::SIZE,
+        &[b"referral", player_key.as_ref(), &[binding_bump]],
+    )?;
+    PlayerReferral {
+        player: player_key,
+        referrer: requested,
+        bound_at: Clock::get()?.unix_timestamp,
+        bump: binding_bump,
+    }
+    .try_serialize(&mut &mut binding_info.try_borrow_mut_data()?[..])?;
+
+    emit!(PlayerReferred {
+        player: player_key,
+        referrer: requested,
+    });
+
+    Ok(requested)
+}
@@ -0,0 +1,38 @@
+//! Membership updates for the `Roles` PDA.
+//!
+//! `set_role` applies revocations at once; grants are queued as a
+//! `ConfigChange::Role` and applied by `execute_change` after the timelock.
+
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::state::*;
+
+impl Roles {
+    /// Replace `member`'s bits (0 removes the member) and return the old ones.
+    pub fn set(&mut self, member: Pubkey, capabilities: u8) -> Result<u8> {
+        require!(capabilities & !ROLE_ALL == 0, AlonsBoxError::InvalidCapabilities);
+        let previous = self.capabilities(&member);
+
+        match self.members.iter().position(|m| m.key == member) {
+            Some(index) if capabilities == 0 => {
+                self.members.swap_remove(index);
+            }
+            Some(index) => self.members[index].capabilities = capabilities,
+            None if capabilities == 0 => {}
+            None => {
+                require!(self.members.len() < MAX_ROLE_MEMBERS, AlonsBoxError::RolesFull);
+                self.members.push(RoleMember {
+                    key: member,
+                    capabilities,
+                });
+            }
+        }
+
+        // Someone must always be able to manage roles
+        require!(
+            self.members.iter().any(|m| m.capabilities & ROLE_CONFIG_ADMIN != 0),
+            AlonsBoxError::LastConfigAdmin
+        );
+        Ok(previous)
+    }
+}
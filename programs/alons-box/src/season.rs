@@ -0,0 +1,109 @@
+//! Seasons: a prize pool and leaderboard spanning a range of rounds.
+//!
+//! `settle` moves `season_bps` of each pool in the season's range into the
+//! Season PDA and adds the round's winners to a leaderboard of at most
+//! `MAX_SEASON_RANKS` players ranked by winnings. The board is bounded, so a
+//! player who drops off it and wins again re-enters with that win only.
+//! `finalize_season` pays rank `i` its `prize_curve[i]` share of the pool,
+//! once every round of the season has been created and none is still open.
+
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::state::{PayoutConfig, Round, Season, SeasonEntry, MAX_SEASON_RANKS};
+use crate::utils::bps_of;
+
+impl Season {
+    /// Season terms are well-formed and the slice fits beside `payout`'s
+    /// winner, evidence and treasury shares.
+    pub fn is_valid(&self, payout: &PayoutConfig) -> bool {
+        let curve_total: u64 = self.prize_curve.iter().map(|&b| b as u64).sum();
+        self.start_round_id <= self.end_round_id
+            && self.season_bps > 0
+            && payout.settle_bps() + self.season_bps as u64 <= 10000
+            && !self.prize_curve.is_empty()
+            && self.prize_curve.len() <= MAX_SEASON_RANKS
+            && self.prize_curve.iter().all(|&b| b > 0)
+            && curve_total == 10000
+    }
+
+    /// Check `round_id` can still contribute to this season.
+    pub fn accept_round(&self, round_id: u64) -> Result<()> {
+        require!(
+            !self.finalized && (self.start_round_id..=self.end_round_id).contains(&round_id),
+            AlonsBoxError::SeasonMismatch
+        );
+        Ok(())
+    }
+
+    /// Add a win of `amount` for `player` and keep the board sorted, best first.
+    pub fn record_win(&mut self, player: Pubkey, amount: u64) -> Result<()> {
+        if let Some(entry) = self.leaderboard.iter_mut().find(|e| e.player == player) {
+            entry.winnings = entry
+                .winnings
+                .checked_add(amount)
+                .ok_or(AlonsBoxError::MathOverflow)?;
+            entry.wins += 1;
+        } else {
+            let entry = SeasonEntry {
+                player,
+                winnings: amount,
+                wins: 1,
+            };
+            if self.leaderboard.len() < MAX_SEASON_RANKS {
+                self.leaderboard.push(entry);
+            } else if let Some(last) = self
+                .leaderboard
+                .last_mut()
+                .filter(|last| last.winnings < amount)
+            {
+                *last = entry;
+            } else {
+                return Ok(());
+            }
+        }
+        // Stable sort: on equal winnings the earlier entry keeps the higher rank
+        self.leaderboard.sort_by_key(|e| std::cmp::Reverse(e.winnings));
+        Ok(())
+    }
+
+    /// Count a round of the season as created.
+    pub fn open_round(&mut self) -> Result<()> {
+        self.open_rounds = self
+            .open_rounds
+            .checked_add(1)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Prize per ranked player, best first. Ranks the board never filled
+    /// get nothing.
+    pub fn prizes(&self) -> Result<Vec<u64>> {
+        self.prize_curve
+            .iter()
+            .take(self.leaderboard.len())
+            .map(|&bps| bps_of(self.prize_pool, bps))
+            .collect()
+    }
+}
+
+/// Check `season` is the one `season_id` names, and that one is passed
+/// whenever it is set (0 = no season).
+pub fn check_season(season: Option<&Season>, season_id: u64) -> Result<()> {
+    match season {
+        Some(season) => require!(season.season_id == season_id, AlonsBoxError::SeasonMismatch),
+        None => require!(season_id == 0, AlonsBoxError::SeasonRequired),
+    }
+    Ok(())
+}
+
+/// Count `round` as no longer open in its season, if it has one.
+pub fn close_season_round(season: Option<&mut Account<Season>>, round: &Round) -> Result<()> {
+    check_season(season.as_deref().map(|s| &**s), round.season_id)?;
+    if let Some(season) = season {
+        season.open_rounds = season
+            .open_rounds
+            .checked_sub(1)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+    }
+    Ok(())
+}
@@ -9,10 +9,540 @@ pub struct GameState {
     pub current_round_id: u64,
     pub bump: u8,
     pub rollover_balance: u64,
+    /// Rollover kept in the vault after a round (0 = uncapped)
+    pub rollover_cap: u64,
+    pub overflow_policy: OverflowPolicy,
+    /// May toggle `paused`; separate key from `authority`
+    pub guardian: Pubkey,
+    /// Bitmask of PAUSE_* flags
+    pub paused: u8,
+    /// Seconds a queued config change waits before execute_change
+    pub timelock_delay: i64,
+    /// Number of changes ever queued; the next PendingChange id
+    pub change_count: u64,
+    /// Pool split used by settle and expire; changed only through the timelock
+    pub payout: PayoutConfig,
+    /// Share of the treasury cut on referred deposits owed to the referrer
+    pub referral_bps: u16,
+    /// Referral slices withheld from the treasury and not yet claimed
+    pub referral_liability: u64,
+    /// Latest season (0 = none); create_round tags the rounds in its range
+    pub season_id: u64,
+    pub season_start_round_id: u64,
+    pub season_end_round_id: u64,
+    /// The latest season's slice of each settled pool, until it is finalized
+    pub season_bps: u16,
+    /// Program init_buyback may swap through; default until a SwapProgram
+    /// change is executed
+    pub swap_program: Pubkey,
 }
 
 impl GameState {
-    pub const SIZE: usize = 8 + 32 + 32 + 32 + 8 + 1 + 8;
+    pub const SIZE: usize = 8 + 32 + 32 + 32 + 8 + 1 + 8 + 8 + 1 + 32 + 1 + 8 + 8
+        + PayoutConfig::SIZE + 2 + 8 + 8 + 8 + 8 + 2 + 32;
+
+    pub fn is_paused(&self, flag: u8) -> bool {
+        self.paused & flag != 0
+    }
+
+    /// Season a new round `round_id` belongs to (0 = none)
+    pub fn season_for(&self, round_id: u64) -> u64 {
+        if self.season_id != 0
+            && (self.season_start_round_id..=self.season_end_round_id).contains(&round_id)
+        {
+            self.season_id
+        } else {
+            0
+        }
+    }
+
+    /// `payout` is well-formed and leaves room for the running season's slice
+    pub fn payout_fits(&self, payout: &PayoutConfig) -> bool {
+        payout.is_valid() && payout.settle_bps() + self.season_bps as u64 <= 10000
+    }
+}
+
+// ── Payout config ── BPS splits of the round pool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PayoutConfig {
+    /// settle: winner share of the pool
+    pub winner_bps: u16,
+    /// settle: evidence share of the pool
+    pub evidence_bps: u16,
+    /// settle: treasury share of the pool
+    pub treasury_bps: u16,
+    /// expire: buyback share of deposits
+    pub expire_buyback_bps: u16,
+    /// expire: treasury share of deposits
+    pub expire_treasury_bps: u16,
+}
+
+impl PayoutConfig {
+    pub const SIZE: usize = 2 * 5;
+
+    /// Winner, evidence and treasury shares taken at settle
+    pub fn settle_bps(&self) -> u64 {
+        self.winner_bps as u64 + self.evidence_bps as u64 + self.treasury_bps as u64
+    }
+
+    /// Each outcome's cuts must leave a non-negative rollover
+    pub fn is_valid(&self) -> bool {
+        self.winner_bps as u32 + self.evidence_bps as u32 + self.treasury_bps as u32 <= 10000
+            && self.expire_buyback_bps as u32 + self.expire_treasury_bps as u32 <= 10000
+    }
+}
+
+impl Default for PayoutConfig {
+    fn default() -> Self {
+        Self {
+            winner_bps: 5000,
+            evidence_bps: 3000,
+            treasury_bps: 500,
+            expire_buyback_bps: 4750,
+            expire_treasury_bps: 500,
+        }
+    }
+}
+
+// ── Roles PDA ── seeds: ["roles"]
+// Capability bits per operator key. Instructions check the specific
+// ROLE_* bit instead of game_state.authority.
+#[account]
+pub struct Roles {
+    pub members: Vec<RoleMember>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct RoleMember {
+    pub key: Pubkey,
+    pub capabilities: u8,
+}
+
+/// Upper bound on keys holding a role
+pub const MAX_ROLE_MEMBERS: usize = 16;
+
+impl Roles {
+    pub const SIZE: usize = 8 + (4 + MAX_ROLE_MEMBERS * (32 + 1)) + 1;
+
+    pub fn capabilities(&self, key: &Pubkey) -> u8 {
+        self.members
+            .iter()
+            .find(|member| member.key == *key)
+            .map_or(0, |member| member.capabilities)
+    }
+
+    pub fn has(&self, key: &Pubkey, capability: u8) -> bool {
+        self.capabilities(key) & capability != 0
+    }
+}
+
+// ── Role capabilities ── bits of RoleMember.capabilities
+/// create_round, record_question
+pub const ROLE_ROUND_CREATOR: u8 = 1 << 0;
+/// settle, expire
+pub const ROLE_SETTLER: u8 = 1 << 1;
+/// close_deposit, close_question, close_round
+pub const ROLE_CLOSER: u8 = 1 << 2;
+/// set_role (revocations), queue_change, cancel_change
+pub const ROLE_CONFIG_ADMIN: u8 = 1 << 3;
+/// No longer checked: execute_buyback is permissionless. Kept so masks
+/// granted before stay valid.
+pub const ROLE_BUYBACK: u8 = 1 << 4;
+pub const ROLE_ALL: u8 =
+    ROLE_ROUND_CREATOR | ROLE_SETTLER | ROLE_CLOSER | ROLE_CONFIG_ADMIN | ROLE_BUYBACK;
+
+// ── Config change enum ── a typed GameState mutation queued behind the timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigChange {
+    Treasury(Pubkey),
+    BuybackWallet(Pubkey),
+    RolloverCap {
+        rollover_cap: u64,
+        overflow_policy: OverflowPolicy,
+    },
+    Guardian(Pubkey),
+    TimelockDelay(i64),
+    Payout(PayoutConfig),
+    ReferralBps(u16),
+    /// Replaces the member's capability bits; set_role can only remove bits
+    Role {
+        member: Pubkey,
+        capabilities: u8,
+    },
+    /// Program init_buyback accepts; it must speak the mock-amm swap ABI
+    SwapProgram(Pubkey),
+}
+
+impl ConfigChange {
+    // 1 tag + largest variant (Role: Pubkey + u8; Payout is 10 bytes)
+    pub const SIZE: usize = 1 + 32 + 1;
+}
+
+/// Longest timelock_delay a change may set (30 days)
+pub const MAX_TIMELOCK_DELAY: i64 = 30 * 24 * 60 * 60;
+
+// ── PendingChange PDA ── seeds: ["pending_change", change_id (u64 LE)]
+// Closed back to the queuer by execute_change or cancel_change.
+#[account]
+pub struct PendingChange {
+    pub change_id: u64,
+    pub change: ConfigChange,
+    pub queued_by: Pubkey,
+    pub queued_at: i64,
+    pub executable_at: i64,
+    pub bump: u8,
+    /// Queued by finalize_proposal; admins and the guardian cannot cancel it
+    /// unless it can no longer execute
+    pub from_governance: bool,
+}
+
+impl PendingChange {
+    pub const SIZE: usize = 8 + 8 + ConfigChange::SIZE + 32 + 8 + 8 + 1 + 1;
+
+    /// Whether execute_change would reject this change against `game_state`
+    /// as it stands (ignoring the timelock)
+    pub fn is_stale(&self, game_state: &GameState) -> bool {
+        match self.change {
+            ConfigChange::Payout(payout) => !game_state.payout_fits(&payout),
+            _ => false,
+        }
+    }
+}
+
+// ── Governance PDA ── seeds: ["governance"]
+// Token holders lock `mint` into the governance vault (["governance_vault"])
+// and vote on PayoutConfig proposals. Passed proposals are queued as a
+// PendingChange, so they still wait out the timelock. Once this account
+// exists, queue_change no longer accepts Payout changes.
+#[account]
+pub struct Governance {
+    pub mint: Pubkey,
+    /// Share of total_locked (at proposal creation) that must vote
+    pub quorum_bps: u16,
+    /// Share of votes cast that must be yes
+    pub approval_bps: u16,
+    /// Seconds a proposal stays open for voting
+    pub voting_period: i64,
+    /// Locked tokens needed to create a proposal
+    pub proposal_threshold: u64,
+    /// Tokens currently held by the vault
+    pub total_locked: u64,
+    /// Number of proposals ever created; the next Proposal id
+    pub proposal_count: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl Governance {
+    pub const SIZE: usize = 8 + 32 + 2 + 2 + 8 + 8 + 8 + 8 + 1 + 1;
+}
+
+// ── VoteEscrow PDA ── seeds: ["vote_escrow", owner]
+// Voting weight is the locked amount. Voting extends locked_until to the end
+// of the proposal so the same tokens cannot vote twice from another wallet,
+// and only escrows last topped up by the proposal's creation can vote on it.
+#[account]
+pub struct VoteEscrow {
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub locked_until: i64,
+    pub bump: u8,
+    /// Time of the last lock_tokens
+    pub locked_at: i64,
+}
+
+impl VoteEscrow {
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 1 + 8;
+}
+
+// ── Proposal status enum ──
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalStatus {
+    Voting,
+    /// Passed and queued as PendingChange `change_id`
+    Queued,
+    Defeated,
+    /// Passed, but its payout did not fit alongside the running season's
+    /// share, so nothing was queued
+    Failed,
+}
+
+// ── Proposal PDA ── seeds: ["proposal", proposal_id (u64 LE)]
+#[account]
+pub struct Proposal {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub payout: PayoutConfig,
+    pub created_at: i64,
+    pub voting_ends_at: i64,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    /// governance.total_locked when the proposal was created
+    pub total_locked: u64,
+    pub status: ProposalStatus,
+    /// PendingChange id once queued (0 = not queued)
+    pub change_id: u64,
+    pub bump: u8,
+}
+
+impl Proposal {
+    pub const SIZE: usize = 8 + 8 + 32 + PayoutConfig::SIZE + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1;
+}
+
+// ── VoteRecord PDA ── seeds: ["vote", proposal_id (u64 LE), voter]
+// Exists once per voter per proposal; init fails on a second vote.
+#[account]
+pub struct VoteRecord {
+    pub voter: Pubkey,
+    pub support: bool,
+    pub weight: u64,
+    pub bump: u8,
+}
+
+impl VoteRecord {
+    pub const SIZE: usize = 8 + 32 + 1 + 8 + 1;
+}
+
+// ── Buyback mode enum ── what happens to tokens bought by execute_buyback
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BuybackMode {
+    Burn,
+    /// Kept in ["buyback_tokens"], which no instruction withdraws from
+    Lock,
+}
+
+// ── Buyback PDA ── seeds: ["buyback"]
+// SOL accumulates in the system-owned ["buyback_vault"] PDA once
+// game_state.buyback_wallet points at it. execute_buyback swaps it through
+// swap_program and burns or locks the tokens in ["buyback_tokens"]. The swap
+// CPI speaks the mock-amm ABI, so init_buyback only accepts the program the
+// timelocked game_state.swap_program names.
+#[account]
+pub struct Buyback {
+    pub swap_program: Pubkey,
+    pub pool: Pubkey,
+    pub pool_token_vault: Pubkey,
+    pub token_mint: Pubkey,
+    pub mode: BuybackMode,
+    /// Most the output may fall below the constant-product quote, price
+    /// impact included; a floor under the executor's own min_tokens_out
+    pub max_slippage_bps: u16,
+    /// Lamports spent per execute_buyback (0 = whole balance)
+    pub max_sol_per_buyback: u64,
+    pub total_sol_spent: u64,
+    pub total_tokens_bought: u64,
+    pub total_tokens_burned: u64,
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub tokens_bump: u8,
+}
+
+impl Buyback {
+    pub const SIZE: usize = 8 + 32 + 32 + 32 + 32 + 1 + 2 + 8 + 8 + 8 + 8 + 1 + 1 + 1;
+}
+
+// ── RevenueSplit PDA ── seeds: ["revenue_split"]
+// Who receives the TreasuryPool and in what BPS share. New recipient lists
+// wait out game_state.timelock_delay in `pending` before replacing the
+// active list.
+#[account]
+pub struct RevenueSplit {
+    pub recipients: Vec<SplitRecipient>,
+    pub pending: Vec<SplitRecipient>,
+    /// When `pending` replaces `recipients` (0 = nothing pending)
+    pub pending_effective_at: i64,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct SplitRecipient {
+    pub key: Pubkey,
+    pub bps: u16,
+}
+
+/// Upper bound on revenue split recipients
+pub const MAX_SPLIT_RECIPIENTS: usize = 8;
+
+impl RevenueSplit {
+    pub const SIZE: usize = 8 + 2 * (4 + MAX_SPLIT_RECIPIENTS * (32 + 2)) + 8 + 1;
+
+    /// Promote a matured pending list. Returns true if one was applied.
+    pub fn apply_pending(&mut self, now: i64) -> bool {
+        if self.pending_effective_at == 0 || now < self.pending_effective_at {
+            return false;
+        }
+        self.recipients = std::mem::take(&mut self.pending);
+        self.pending_effective_at = 0;
+        true
+    }
+}
+
+// ── TreasuryPool PDA ── seeds: ["treasury_pool"]
+// Receives treasury payouts once game_state.treasury points at it. Holds
+// lamports above rent; distribute_treasury pays them out per RevenueSplit
+// and leaves rounding dust for the next distribution.
+#[account]
+pub struct TreasuryPool {
+    pub total_distributed: u64,
+    /// Dust and skipped sub-rent shares left behind by the last distribution
+    pub carried: u64,
+    pub bump: u8,
+}
+
+impl TreasuryPool {
+    pub const SIZE: usize = 8 + 8 + 8 + 1;
+}
+
+// ── TreasuryStream PDA ── seeds: ["treasury_stream"]
+// Once created, settle and expire credit treasury cuts here instead of
+// paying game_state.treasury. The lamports stay in the vault until vested;
+// see stream.rs for the schedule.
+#[account]
+pub struct TreasuryStream {
+    /// Seconds a credit takes to vest fully
+    pub vesting_period: i64,
+    /// Still vesting across `tranches`
+    pub unvested: u64,
+    /// Vested and not yet withdrawn
+    pub vested: u64,
+    /// Credits grouped by when they arrived, each on its own schedule
+    pub tranches: [Tranche; STREAM_TRANCHES],
+    pub total_credited: u64,
+    pub total_withdrawn: u64,
+    pub bump: u8,
+}
+
+impl TreasuryStream {
+    pub const SIZE: usize = 8 + 8 + 8 + 8 + Tranche::SIZE * STREAM_TRANCHES + 8 + 8 + 1;
+}
+
+/// Tranches a TreasuryStream holds; each takes credits for
+/// vesting_period / STREAM_TRANCHES seconds after it opens
+pub const STREAM_TRANCHES: usize = 8;
+
+/// Credits that vest together; an empty slot has `released == amount`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Tranche {
+    /// Lamports credited into the tranche
+    pub amount: u64,
+    /// Of `amount`, already moved to `vested`
+    pub released: u64,
+    /// Time of the tranche's first credit
+    pub opened_at: i64,
+    /// Amount-weighted mean credit time; vests linearly from here
+    pub starts_at: i64,
+}
+
+impl Tranche {
+    pub const SIZE: usize = 8 + 8 + 8 + 8;
+}
+
+// ── Season PDA ── seeds: ["season", season_id (u64 LE)]
+// Groups rounds start_round_id..=end_round_id. settle moves season_bps of
+// each pool in range into this account's lamports and ranks the winners;
+// finalize_season pays the leaderboard along prize_curve. See season.rs.
+#[account]
+pub struct Season {
+    pub season_id: u64,
+    pub start_round_id: u64,
+    pub end_round_id: u64,
+    /// Share of each settled pool added to the prize pool
+    pub season_bps: u16,
+    /// Share of the prize pool per rank, best first; sums to 10000
+    pub prize_curve: Vec<u16>,
+    /// Lamports held for prizes, on top of rent
+    pub prize_pool: u64,
+    /// Best first, at most MAX_SEASON_RANKS entries
+    pub leaderboard: Vec<SeasonEntry>,
+    pub finalized: bool,
+    pub bump: u8,
+    /// Rounds of the season created and not yet settled, expired or failed
+    pub open_rounds: u32,
+}
+
+impl Season {
+    pub const SIZE: usize = 8 + 8 + 8 + 8 + 2
+        + (4 + 2 * MAX_SEASON_RANKS)
+        + 8
+        + (4 + SeasonEntry::SIZE * MAX_SEASON_RANKS)
+        + 1 + 1 + 4;
+}
+
+/// Ranks a season leaderboard and prize curve can hold
+pub const MAX_SEASON_RANKS: usize = 10;
+
+// ── Season entry ── one leaderboard row
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct SeasonEntry {
+    pub player: Pubkey,
+    /// Lamports won in the season's rounds while on the board
+    pub winnings: u64,
+    pub wins: u32,
+}
+
+impl SeasonEntry {
+    pub const SIZE: usize = 32 + 8 + 4;
+}
+
+// ── Referrer PDA ── seeds: ["referrer", owner pubkey]
+// Created by register_referrer. Earnings are credited per referred deposit
+// once its round settles or expires, then claimed by the owner.
+#[account]
+pub struct Referrer {
+    pub owner: Pubkey,
+    pub referred_players: u64,
+    /// Credited and not yet claimed
+    pub accrued: u64,
+    pub total_earned: u64,
+    pub total_claimed: u64,
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+impl Referrer {
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 1;
+}
+
+// ── PlayerReferral PDA ── seeds: ["referral", player pubkey]
+// Permanent binding of a player to a referrer, created by deposit.
+#[account]
+pub struct PlayerReferral {
+    pub player: Pubkey,
+    pub referrer: Pubkey,
+    pub bound_at: i64,
+    pub bump: u8,
+}
+
+impl PlayerReferral {
+    pub const SIZE: usize = 8 + 32 + 32 + 8 + 1;
+}
+
+// ── Pause flags ── bits of GameState.paused
+// emergency_expire, fail_round and claim_refund ignore them so funds can always leave.
+pub const PAUSE_DEPOSITS: u8 = 1 << 0;
+pub const PAUSE_ROUND_CREATION: u8 = 1 << 1;
+pub const PAUSE_SETTLEMENT: u8 = 1 << 2;
+pub const PAUSE_ALL: u8 = PAUSE_DEPOSITS | PAUSE_ROUND_CREATION | PAUSE_SETTLEMENT;
+
+// ── Overflow policy enum ── where rollover above the cap goes
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    #[default]
+    Jackpot,
+    Buyback,
+}
+
+// ── JackpotPool PDA ── seeds: ["jackpot_pool"]
+// Holds rollover overflow as lamports until a jackpot round pulls it back in
+#[account]
+pub struct JackpotPool {
+    pub balance: u64,
+    pub bump: u8,
+}
+
+impl JackpotPool {
+    pub const SIZE: usize = 8 + 8 + 1;
 }
 
 // ── Round status enum ──
@@ -21,8 +551,62 @@ pub enum RoundStatus {
     Active,
     Settled,
     Expired,
+    Refunding,
+}
+
+// ── Tie-break policy enum ── how settle splits the winner pool between co-winners
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreakPolicy {
+    /// Even split; the first winner takes the remainder
+    EqualSplit,
+    /// Even split between the winners whose Guess landed in the earliest
+    /// slot; later winners get nothing
+    EarliestGuess,
+    /// Pro rata to each winner's Deposit in the round
+    LargestDeposit,
 }
 
+// ── Soft-close rule ── set at create_round; late deposits push ends_at out
+// All zero means the round ends at a fixed time.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SoftClose {
+    /// A deposit with at most this many seconds left extends the round
+    pub window_secs: u32,
+    /// Seconds added to ends_at per extension
+    pub extension_secs: u32,
+    /// Hard cap — ends_at never moves past this
+    pub max_ends_at: i64,
+}
+
+impl SoftClose {
+    pub const SIZE: usize = 4 + 4 + 8;
+}
+
+// ── Deposit policy ── set at create_round; bounds on what deposit accepts
+// Each limit is in lamports, 0 = no limit. Zero-lamport deposits are always refused.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DepositPolicy {
+    /// Smallest single deposit
+    pub min_deposit: u64,
+    /// Largest single deposit
+    pub max_deposit: u64,
+    /// Largest cumulative deposit per player in the round
+    pub max_per_player: u64,
+    /// Largest total_deposits the round will take
+    pub max_pool: u64,
+}
+
+impl DepositPolicy {
+    pub const SIZE: usize = 8 + 8 + 8 + 8;
+}
+
+/// Upper bound on co-winners paid by a single settle
+pub const MAX_WINNERS: usize = 10;
+
+/// How long past ends_at a round must sit Active before anyone may
+/// emergency_expire it (24 hours)
+pub const EMERGENCY_GRACE_PERIOD: i64 = 24 * 60 * 60;
+
 // ── Round PDA ── seeds: ["round", round_id (u64 LE)]
 #[account]
 pub struct Round {
@@ -36,11 +620,35 @@ pub struct Round {
     pub revealed_answer: String,
     pub revealed_salt: String,
     pub bump: u8,
+    pub original_ends_at: i64,
+    pub soft_close: SoftClose,
+    /// Deposits below this at ends_at let anyone fail the round (0 = no minimum)
+    pub min_pool: u64,
+    /// Lamports handed back so far while Refunding
+    pub refunded: u64,
+    /// Jackpot pool pulled in at creation (already counted in rollover_in)
+    pub jackpot_in: u64,
+    pub deposit_policy: DepositPolicy,
+    /// Deposits made by players bound to a referrer
+    pub referred_deposits: u64,
+    /// Withheld from the treasury cut at settle/expire for referrers
+    pub referral_reserve: u64,
+    /// Referred deposits credited so far, and the lamports credited for them
+    pub referred_credited: u64,
+    pub referral_credited: u64,
+    /// Season whose range held round_id at creation (0 = none); settle pays into it
+    pub season_id: u64,
 }
 
 impl Round {
-    // 8 disc + 8 + 32 + 32 + 8 + 1 + 8 + 8 + (4+64) + (4+64) + 1 = 242
-    pub const SIZE: usize = 8 + 8 + 32 + 32 + 8 + 1 + 8 + 8 + (4 + 64) + (4 + 64) + 1;
+    // 8 disc + 8 + 32 + 32 + 8 + 1 + 8 + 8 + (4+64) + (4+64) + 1 + 8 + 16 + 8 + 8 + 8 + 32 + 8 + 8 + 8 + 8 + 8 = 362
+    pub const SIZE: usize = 8 + 8 + 32 + 32 + 8 + 1 + 8 + 8 + (4 + 64) + (4 + 64) + 1 + 8
+        + SoftClose::SIZE + 8 + 8 + 8 + DepositPolicy::SIZE + 8 + 8 + 8 + 8 + 8;
+
+    /// A round under its min_pool can only be failed and refunded
+    pub fn min_pool_met(&self) -> bool {
+        self.min_pool == 0 || self.total_deposits >= self.min_pool
+    }
 }
 
 // ── Deposit PDA ── seeds: ["deposit", round_id (u64 LE), user pubkey]
@@ -50,10 +658,207 @@ pub struct Deposit {
     pub user: Pubkey,
     pub amount: u64,
     pub bump: u8,
+    /// Referrer the player was bound to at deposit time (default = none)
+    pub referrer: Pubkey,
+    /// Referral share already credited to the referrer
+    pub referral_credited: bool,
 }
 
 impl Deposit {
-    pub const SIZE: usize = 8 + 8 + 32 + 8 + 1;
+    pub const SIZE: usize = 8 + 8 + 32 + 8 + 1 + 32 + 1;
+}
+
+// ── PlayerLimits PDA ── seeds: ["player_limits", player pubkey]
+// Self-set responsible-gaming limits, enforced by deposit. Caps are in
+// lamports (0 = no limit). Rolling windows are kept as hour buckets for the
+// daily cap and day buckets for the weekly cap.
+#[account]
+pub struct PlayerLimits {
+    pub player: Pubkey,
+    pub daily_cap: u64,
+    pub weekly_cap: u64,
+    /// Cap on one wallet's total deposit into a single round
+    pub round_cap: u64,
+    /// Deposits are refused until this timestamp
+    pub excluded_until: i64,
+    /// Looser limits waiting out the cooldown
+    pub pending: PendingLimits,
+    pub hour_buckets: [u64; 24],
+    pub day_buckets: [u64; 7],
+    pub last_deposit_at: i64,
+    pub bump: u8,
+}
+
+impl PlayerLimits {
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 8 + 8 + PendingLimits::SIZE + 8 * 24 + 8 * 7 + 8 + 1;
+}
+
+// ── PlayerProfile PDA ── seeds: ["player_profile", player pubkey]
+// Lifetime statistics, created by the player's first deposit. Unlike
+// Deposit PDAs it is never closed.
+#[account]
+pub struct PlayerProfile {
+    pub player: Pubkey,
+    pub rounds_played: u64,
+    pub total_deposited: u64,
+    pub wins: u64,
+    pub total_winnings: u64,
+    pub evidence_earned: u64,
+    pub total_refunded: u64,
+    pub last_round_id: u64,
+    pub bump: u8,
+}
+
+impl PlayerProfile {
+    pub const SIZE: usize = 8 + 32 + 8 * 7 + 1;
+}
+
+// ── Pending limits ── requested caps that apply once effective_at passes
+// effective_at == 0 means nothing is pending.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PendingLimits {
+    pub daily_cap: u64,
+    pub weekly_cap: u64,
+    pub round_cap: u64,
+    pub effective_at: i64,
+}
+
+impl PendingLimits {
+    pub const SIZE: usize = 8 + 8 + 8 + 8;
+}
+
+// ── Question PDA ── seeds: ["question", round_id (u64 LE), asker pubkey, question_hash]
+// On-chain log of a question asked during a round and the authority's verdict.
+// Settle only pays evidence to wallets holding a "yes" Question in that round.
+// close_question reclaims the rent once the round has finished.
+#[account]
+pub struct Question {
+    pub round_id: u64,
+    pub asker: Pubkey,
+    pub question_hash: [u8; 32],
+    pub is_public: bool,
+    pub answer_hash: [u8; 32],
+    pub is_yes: bool,
+    pub bump: u8,
+}
+
+impl Question {
+    pub const SIZE: usize = 8 + 8 + 32 + 32 + 1 + 32 + 1 + 1;
+}
+
+// ── Guess PDA ── seeds: ["guess", round_id (u64 LE), player pubkey]
+// A player's correct guess and the slot it was recorded in. Settle orders
+// co-winners by these under EarliestGuess. close_guess reclaims the rent once
+// the round has finished.
+#[account]
+pub struct Guess {
+    pub round_id: u64,
+    pub player: Pubkey,
+    /// SHA-256 of the guess; settle requires it to be SHA-256 of the answer
+    pub guess_hash: [u8; 32],
+    pub slot: u64,
+    pub bump: u8,
+}
+
+impl Guess {
+    pub const SIZE: usize = 8 + 8 + 32 + 32 + 8 + 1;
+}
+
+// ── Settlement PDA ── seeds: ["settlement", round_id (u64 LE)]
+// Payout record written at settle/expire. close_round folds it into the
+// RoundArchive as a leaf and closes it with the Round, refunding its rent
+// payer; the archive leaf and RoundArchived event keep the record.
+#[account]
+pub struct Settlement {
+    pub round_id: u64,
+    pub outcome: RoundStatus,
+    pub commit_hash: [u8; 32],
+    pub answer_hash: [u8; 32],
+    pub pool: u64,
+    pub rollover_in: u64,
+    pub rollover_out: u64,
+    pub winners: Vec<Pubkey>,
+    pub winner_amounts: Vec<u64>,
+    pub evidence_total: u64,
+    pub treasury_amount: u64,
+    pub buyback_amount: u64,
+    pub jackpot_amount: u64,
+    /// Moved into the season prize pool (settle only)
+    pub season_amount: u64,
+    pub finalized_at: i64,
+    /// Signer of the finishing instruction, which paid the rent;
+    /// refunded when close_round closes the Settlement
+    pub rent_payer: Pubkey,
+    pub bump: u8,
+}
+
+impl Settlement {
+    // Sized for the exact number of winners recorded (0 for expired rounds)
+    pub fn size(winner_count: usize) -> usize {
+        8 + 8 + 1 + 32 + 32 + 8 + 8 + 8
+            + (4 + 32 * winner_count)
+            + (4 + 8 * winner_count)
+            + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 1
+    }
+
+    // Everything but the rent payer and bump — the leaf preimage for the RoundArchive
+    pub fn summary(&self) -> RoundSummary {
+        RoundSummary {
+            round_id: self.round_id,
+            outcome: self.outcome,
+            commit_hash: self.commit_hash,
+            answer_hash: self.answer_hash,
+            pool: self.pool,
+            rollover_in: self.rollover_in,
+            rollover_out: self.rollover_out,
+            winners: self.winners.clone(),
+            winner_amounts: self.winner_amounts.clone(),
+            evidence_total: self.evidence_total,
+            treasury_amount: self.treasury_amount,
+            buyback_amount: self.buyback_amount,
+            jackpot_amount: self.jackpot_amount,
+            season_amount: self.season_amount,
+            finalized_at: self.finalized_at,
+        }
+    }
+}
+
+// ── Round summary ── leaf preimage appended to the RoundArchive by close_round
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct RoundSummary {
+    pub round_id: u64,
+    pub outcome: RoundStatus,
+    pub commit_hash: [u8; 32],
+    pub answer_hash: [u8; 32],
+    pub pool: u64,
+    pub rollover_in: u64,
+    pub rollover_out: u64,
+    pub winners: Vec<Pubkey>,
+    pub winner_amounts: Vec<u64>,
+    pub evidence_total: u64,
+    pub treasury_amount: u64,
+    pub buyback_amount: u64,
+    pub jackpot_amount: u64,
+    pub season_amount: u64,
+    pub finalized_at: i64,
+}
+
+/// Depth of the round archive tree (2^20 rounds)
+pub const ARCHIVE_DEPTH: usize = 20;
+
+// ── RoundArchive PDA ── seeds: ["round_archive"]
+// Append-only incremental Merkle tree over closed rounds. Only the right-most
+// frontier is stored; proofs are rebuilt off-chain from RoundArchived events.
+#[account]
+pub struct RoundArchive {
+    pub leaf_count: u64,
+    pub root: [u8; 32],
+    pub frontier: [[u8; 32]; ARCHIVE_DEPTH],
+    pub bump: u8,
+}
+
+impl RoundArchive {
+    pub const SIZE: usize = 8 + 8 + 32 + 32 * ARCHIVE_DEPTH + 1;
 }
 
 // ── Vault PDA ── seeds: ["vault"]
@@ -0,0 +1,188 @@
+//! Linear vesting of protocol fees behind the `TreasuryStream` PDA.
+//!
+//! Vesting lamports stay in the game vault as a liability. Each credit vests
+//! linearly over one full `vesting_period` from when it arrives, so a new
+//! credit never pushes back what is already vesting. Credits are grouped
+//! into at most `STREAM_TRANCHES` tranches: one arriving within
+//! `vesting_period / STREAM_TRANCHES` of the newest tranche opening joins it,
+//! moving the tranche's start to the amount-weighted mean. That can release
+//! the later credits up to that window early, never anything late.
+//! `withdraw_vested` pays out the vested part.
+
+use anchor_lang::prelude::*;
+use crate::errors::AlonsBoxError;
+use crate::state::{Tranche, TreasuryStream, STREAM_TRANCHES};
+use crate::utils::transfer_from_vault;
+
+/// Longest vesting_period a stream may use (365 days)
+pub const MAX_VESTING_PERIOD: i64 = 365 * 24 * 60 * 60;
+
+impl Tranche {
+    fn is_empty(&self) -> bool {
+        self.released == self.amount
+    }
+
+    /// Of `amount`, the part vested by `now`; never less than already released.
+    fn vested_at(&self, now: i64, vesting_period: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.starts_at).max(0);
+        let due = if elapsed >= vesting_period {
+            self.amount
+        } else {
+            (self.amount as u128 * elapsed as u128 / vesting_period as u128) as u64
+        };
+        due.max(self.released)
+    }
+
+    /// Join `amount` credited at `now`, moving the start to the weighted mean.
+    fn add(&mut self, now: i64, amount: u64) -> Result<()> {
+        let total = self
+            .amount
+            .checked_add(amount)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+        let weighted = self.amount as i128 * self.starts_at as i128 + amount as i128 * now as i128;
+        self.starts_at = (weighted / total as i128) as i64;
+        self.amount = total;
+        Ok(())
+    }
+}
+
+impl TreasuryStream {
+    /// Move every tranche's newly vested part from `unvested` to `vested`,
+    /// freeing the slots of those fully vested.
+    pub fn accrue(&mut self, now: i64) {
+        for tranche in self.tranches.iter_mut().filter(|t| !t.is_empty()) {
+            let due = tranche.vested_at(now, self.vesting_period);
+            let released = due - tranche.released;
+            self.unvested -= released;
+            self.vested += released;
+            tranche.released = due;
+            if tranche.is_empty() {
+                *tranche = Tranche::default();
+            }
+        }
+    }
+
+    /// Add `amount`, vesting over one vesting_period from `now`.
+    pub fn credit(&mut self, now: i64, amount: u64) -> Result<()> {
+        self.accrue(now);
+        let window = (self.vesting_period / STREAM_TRANCHES as i64).max(1);
+        let newest = (0..STREAM_TRANCHES)
+            .filter(|&i| !self.tranches[i].is_empty())
+            .max_by_key(|&i| self.tranches[i].opened_at);
+        let free = self.tranches.iter().position(Tranche::is_empty);
+        match (newest, free) {
+            (Some(i), _) if now < self.tranches[i].opened_at + window => {
+                self.tranches[i].add(now, amount)?
+            }
+            (_, Some(i)) => {
+                self.tranches[i] = Tranche {
+                    amount,
+                    released: 0,
+                    opened_at: now,
+                    starts_at: now,
+                }
+            }
+            // Every slot busy: join the newest, which only vests sooner
+            (Some(i), None) => self.tranches[i].add(now, amount)?,
+            (None, None) => unreachable!("an empty tranche is free"),
+        }
+        self.unvested = self
+            .unvested
+            .checked_add(amount)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+        self.total_credited = self
+            .total_credited
+            .checked_add(amount)
+            .ok_or(AlonsBoxError::MathOverflow)?;
+        Ok(())
+    }
+
+/// Lamports the vault owes the stream.
+    pub fn liability(&self) -> u64 {
+        self.unvested + self.vested
+    }
+}
+
+/// Pay a treasury cut: into the stream (left in the vault) if the
+/// TreasuryStream PDA exists, otherwise straight to the treasury wallet.
+pub fn pay_treasury<'info>(
+    stream_info: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    treasury: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if stream_info.owner != &crate::ID || stream_info.data_is_empty() {
+        return transfer_from_vault(vault, treasury, amount);
+    }
+    let mut stream = TreasuryStream::try_deserialize(&mut &stream_info.try_borrow_data()?[..])?;
+    stream.credit(Clock::get()?.unix_timestamp, amount)?;
+    stream.try_serialize(&mut &mut stream_info.try_borrow_mut_data()?[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PERIOD: i64 = 1000;
+
+    fn stream() -> TreasuryStream {
+        TreasuryStream {
+            vesting_period: PERIOD,
+            unvested: 0,
+            vested: 0,
+            tranches: [Tranche::default(); STREAM_TRANCHES],
+            total_credited: 0,
+            total_withdrawn: 0,
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn credits_half_a_period_apart_each_vest_on_their_own_schedule() {
+        let mut s = stream();
+        s.credit(0, 1000).unwrap();
+        s.credit(PERIOD / 2, 1000).unwrap();
+        // The first credit is half vested and keeps going at its own rate
+        assert_eq!(s.vested, 500);
+
+        s.accrue(PERIOD);
+        assert_eq!(s.vested, 1000 + 500);
+        s.accrue(PERIOD + PERIOD / 2);
+        assert_eq!(s.vested, 2000);
+        assert_eq!(s.unvested, 0);
+        assert!(s.tranches.iter().all(|t| *t == Tranche::default()));
+    }
+
+    #[test]
+    fn credits_within_a_window_share_a_tranche_and_never_vest_late() {
+        let mut s = stream();
+        s.credit(0, 1000).unwrap();
+        s.credit(100, 1000).unwrap();
+        assert_eq!(s.tranches.iter().filter(|t| !t.is_empty()).count(), 1);
+        assert_eq!(s.tranches[0].starts_at, 50);
+
+        // By the first credit's end, at least it has vested
+        s.accrue(PERIOD);
+        assert!(s.vested >= 1000);
+        s.accrue(PERIOD + 100);
+        assert_eq!(s.vested, 2000);
+    }
+
+    #[test]
+    fn a_full_stream_folds_into_the_newest_tranche() {
+        // The window rounds down, so one more tranche can open than fit
+        let mut s = stream();
+        s.vesting_period = PERIOD + 7;
+        let window = s.vesting_period / STREAM_TRANCHES as i64;
+        for i in 0..=STREAM_TRANCHES as i64 {
+            s.credit(i * window, 100).unwrap();
+        }
+        let newest = s.tranches[STREAM_TRANCHES - 1];
+        assert_eq!(newest.amount, 200);
+        assert!(newest.starts_at > newest.opened_at);
+
+        s.accrue(STREAM_TRANCHES as i64 * window + s.vesting_period);
+        assert_eq!(s.vested, 100 * (STREAM_TRANCHES as u64 + 1));
+        assert_eq!(s.unvested, 0);
+    }
+}
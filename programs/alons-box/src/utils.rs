@@ -1,4 +1,12 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, InitializeAccount3, TokenAccount};
+use crate::errors::AlonsBoxError;
+use crate::events::{ChangeQueued, RolloverCapped};
+use crate::state::{
+    ConfigChange, GameState, JackpotPool, OverflowPolicy, PendingChange, Round, RoundStatus,
+    Settlement, TieBreakPolicy, MAX_TIMELOCK_DELAY, MAX_WINNERS, ROLE_ALL,
+};
 
 /// Transfer lamports from vault PDA (program-owned) to a recipient.
 pub fn transfer_from_vault<'info>(
@@ -13,3 +21,325 @@ pub fn transfer_from_vault<'info>(
     **to.try_borrow_mut_lamports()? += amount;
     Ok(())
 }
+
+/// Where the part of a round's rollover above `game_state.rollover_cap` went.
+pub struct RolloverSplit {
+    pub rollover_out: u64,
+    pub to_jackpot: u64,
+    pub to_buyback: u64,
+}
+
+/// Clip `rollover` to the configured cap and move the excess out of the vault,
+/// into the JackpotPool or to the buyback wallet per the overflow policy.
+pub fn route_rollover_overflow<'info>(
+    round_id: u64,
+    rollover: u64,
+    game_state: &GameState,
+    vault: &AccountInfo<'info>,
+    jackpot_pool: &mut Account<'info, JackpotPool>,
+    buyback_wallet: &AccountInfo<'info>,
+) -> Result<RolloverSplit> {
+    let cap = game_state.rollover_cap;
+    if cap == 0 || rollover <= cap {
+        return Ok(RolloverSplit { rollover_out: rollover, to_jackpot: 0, to_buyback: 0 });
+    }
+
+    let overflow = rollover - cap;
+    let split = match game_state.overflow_policy {
+        OverflowPolicy::Jackpot => {
+            transfer_from_vault(vault, &jackpot_pool.to_account_info(), overflow)?;
+            jackpot_pool.balance = jackpot_pool
+                .balance
+                .checked_add(overflow)
+                .ok_or(AlonsBoxError::MathOverflow)?;
+            RolloverSplit { rollover_out: cap, to_jackpot: overflow, to_buyback: 0 }
+        }
+        OverflowPolicy::Buyback => {
+            transfer_from_vault(vault, buyback_wallet, overflow)?;
+            RolloverSplit { rollover_out: cap, to_jackpot: 0, to_buyback: overflow }
+        }
+    };
+
+    emit!(RolloverCapped {
+        round_id,
+        overflow,
+        policy: game_state.overflow_policy,
+        rollover_out: cap,
+        jackpot_balance: jackpot_pool.balance,
+    });
+
+    Ok(split)
+}
+
+/// Validate `change` and build the PendingChange with id
+/// `game_state.change_count + 1`. Shared by queue_change and
+/// finalize_proposal so governance waits out the same timelock.
+pub fn queue_config_change(
+    game_state: &mut GameState,
+    change: ConfigChange,
+    queued_by: Pubkey,
+    bump: u8,
+) -> Result<PendingChange> {
+    match change {
+        ConfigChange::TimelockDelay(delay) => require!(
+            (0..=MAX_TIMELOCK_DELAY).contains(&delay),
+            AlonsBoxError::InvalidTimelockDelay
+        ),
+        ConfigChange::Payout(payout) => {
+            require!(game_state.payout_fits(&payout), AlonsBoxError::InvalidPayoutConfig)
+        }
+        ConfigChange::ReferralBps(bps) => {
+            require!(bps <= 10000, AlonsBoxError::InvalidPayoutConfig)
+        }
+        ConfigChange::Role { capabilities, .. } => {
+            require!(capabilities & !ROLE_ALL == 0, AlonsBoxError::InvalidCapabilities)
+        }
+        ConfigChange::Guardian(guardian) => {
+            require!(guardian != Pubkey::default(), AlonsBoxError::InvalidGuardian)
+        }
+        ConfigChange::SwapProgram(swap_program) => {
+            require!(swap_program != Pubkey::default(), AlonsBoxError::InvalidBuybackConfig)
+        }
+        _ => {}
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let executable_at = now
+        .checked_add(game_state.timelock_delay)
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    game_state.change_count += 1;
+
+    emit!(ChangeQueued {
+        change_id: game_state.change_count,
+        change,
+        queued_by,
+        executable_at,
+    });
+
+    Ok(PendingChange {
+        change_id: game_state.change_count,
+        change,
+        queued_by,
+        queued_at: now,
+        executable_at,
+        bump,
+        from_governance: false,
+    })
+}
+
+/// `bps` basis points of `amount`, rounded down.
+pub fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    amount
+        .checked_mul(bps as u64)
+        .ok_or(AlonsBoxError::MathOverflow)?
+        .checked_div(10000)
+        .ok_or(AlonsBoxError::MathOverflow.into())
+}
+
+/// Split `amount` by per-recipient weights, each in BPS of `amount`.
+/// Weights may sum to less than 10000; every share rounds down and the
+/// undistributed remainder is captured by the caller's residual rollover.
+pub fn bps_shares(amount: u64, weights_bps: &[u16]) -> Result<Vec<u64>> {
+    let total_weight = weights_bps
+        .iter()
+        .try_fold(0u64, |acc, &w| acc.checked_add(w as u64))
+        .ok_or(AlonsBoxError::MathOverflow)?;
+    require!(total_weight <= 10000, AlonsBoxError::InvalidPayoutSum);
+
+    weights_bps
+        .iter()
+        .map(|&w| {
+            amount
+                .checked_mul(w as u64)
+                .ok_or(AlonsBoxError::MathOverflow)?
+                .checked_div(10000)
+                .ok_or(AlonsBoxError::MathOverflow.into())
+        })
+        .collect()
+}
+
+/// Split the winner pool between `winner_count` co-winners as `tie_break`
+/// dictates. `EqualSplit` pays each `amount / n` and the first winner the
+/// remainder. `LargestDeposit` pays pro rata to `per_winner` (each winner's
+/// deposit), rounding down so the dust rolls over. `EarliestGuess` splits
+/// evenly between the winners with the lowest `per_winner` (each winner's
+/// guess slot), the first of them taking the remainder.
+pub fn winner_shares(
+    amount: u64,
+    winner_count: usize,
+    tie_break: TieBreakPolicy,
+    per_winner: &[u64],
+) -> Result<Vec<u64>> {
+    require!(
+        winner_count > 0 && winner_count <= MAX_WINNERS,
+        AlonsBoxError::InvalidWinnerWeights
+    );
+    match tie_break {
+        TieBreakPolicy::EqualSplit => {
+            let share = amount / winner_count as u64;
+            let mut shares = vec![share; winner_count];
+            shares[0] += amount % winner_count as u64;
+            Ok(shares)
+        }
+        TieBreakPolicy::LargestDeposit => {
+            require!(per_winner.len() == winner_count, AlonsBoxError::WinnerDepositMismatch);
+            let deposits = per_winner;
+            let total = deposits
+                .iter()
+                .try_fold(0u64, |acc, &d| acc.checked_add(d))
+                .ok_or(AlonsBoxError::MathOverflow)?;
+            require!(total > 0, AlonsBoxError::WinnerDepositMismatch);
+            deposits
+                .iter()
+                .map(|&d| {
+                    u64::try_from(amount as u128 * d as u128 / total as u128)
+                        .map_err(|_| AlonsBoxError::MathOverflow.into())
+                })
+                .collect()
+        }
+        TieBreakPolicy::EarliestGuess => {
+            require!(per_winner.len() == winner_count, AlonsBoxError::WinnerGuessMismatch);
+            let earliest = per_winner.iter().copied().min().unwrap_or_default();
+            let first = per_winner.iter().filter(|&&slot| slot == earliest).count() as u64;
+            let share = amount / first;
+            let mut remainder = amount % first;
+            Ok(per_winner
+                .iter()
+                .map(|&slot| if slot == earliest { share + std::mem::take(&mut remainder) } else { 0 })
+                .collect())
+        }
+    }
+}
+
+/// Create a program-owned PDA funded by `payer`. Mirrors Anchor's `init`,
+/// including the top-up path for addresses pre-funded by a third party, but
+/// runs from the handler so account constraints are checked first.
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let required = Rent::get()?.minimum_balance(space).max(1);
+    let current = account.lamports();
+
+    if current == 0 {
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                &[signer_seeds],
+            ),
+            required,
+            space as u64,
+            &crate::ID,
+        )?;
+        return Ok(());
+    }
+
+    if required > current {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            required - current,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate {
+                account_to_allocate: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Assign {
+                account_to_assign: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        &crate::ID,
+    )?;
+    Ok(())
+}
+
+/// Create a token account at a PDA of this program, owned by `authority`.
+/// Used instead of Anchor's `token::` init constraints for program vaults.
+pub fn create_pda_token_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::CreateAccount {
+                from: payer.clone(),
+                to: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        Rent::get()?.minimum_balance(TokenAccount::LEN),
+        TokenAccount::LEN as u64,
+        token_program.key,
+    )?;
+    token::initialize_account3(CpiContext::new(
+        token_program.clone(),
+        InitializeAccount3 {
+            account: account.clone(),
+            mint: mint.clone(),
+            authority: authority.clone(),
+        },
+    ))
+}
+
+/// Require the Round PDA of a Question or Guess to have finished. A closed
+/// Round counts as finished: close_round only closes finished rounds, and
+/// the record proves the round was created.
+pub fn require_round_finished(round: &AccountInfo) -> Result<()> {
+    if round.owner != &crate::ID || round.data_is_empty() {
+        return Ok(());
+    }
+    let round = Round::try_deserialize(&mut &round.try_borrow_data()?[..])?;
+    require!(round.status != RoundStatus::Active, AlonsBoxError::RoundStillActive);
+    Ok(())
+}
+
+/// Create the Settlement PDA for `record.round_id` and write the record.
+/// Fails if the round already has one, so each round is finalized once.
+pub fn write_settlement<'info>(
+    settlement_info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    record: &Settlement,
+) -> Result<()> {
+    let round_id_bytes = record.round_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[b"settlement", round_id_bytes.as_ref(), &[record.bump]];
+    create_pda_account(
+        payer,
+        settlement_info,
+        system_program,
+        Settlement::size(record.winners.len()),
+        seeds,
+    )?;
+
+    let mut data = settlement_info.try_borrow_mut_data()?;
+    record.try_serialize(&mut &mut data[..])
+}
@@ -0,0 +1,151 @@
+//! Test-only constant-product pool swapping SOL for one SPL token.
+//!
+//! The pool PDA holds the SOL reserve as lamports above rent; the token
+//! reserve sits in a token account owned by the pool PDA. Only the
+//! SOL -> token direction exists because that is all the buyback needs.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+
+declare_id!("6DfCWrwNa6bkca2tZRGtzZ3AdVZQ9ktXVoeSuPRjn5D2");
+
+/// Swap fee in BPS of the input
+pub const FEE_BPS: u64 = 30;
+
+#[program]
+pub mod mock_amm {
+    use super::*;
+
+    /// Create the pool over an existing token account owned by the pool PDA
+    /// and seed it with `sol_amount` lamports. Tokens are seeded by a plain
+    /// transfer into `token_vault`.
+    pub fn init_pool(ctx: Context<InitPool>, sol_amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.mint = ctx.accounts.mint.key();
+        pool.token_vault = ctx.accounts.token_vault.key();
+        pool.bump = ctx.bumps.pool;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: pool.to_account_info(),
+                },
+            ),
+            sol_amount,
+        )
+    }
+
+    /// Sell `amount_in` lamports for tokens; fails if fewer than
+    /// `min_amount_out` would come back.
+    pub fn swap(ctx: Context<Swap>, amount_in: u64, min_amount_out: u64) -> Result<()> {
+        let pool_info = ctx.accounts.pool.to_account_info();
+        let sol_reserve = pool_info.lamports() - Rent::get()?.minimum_balance(pool_info.data_len());
+        let amount_out = quote(sol_reserve, ctx.accounts.token_vault.amount, amount_in)?;
+        require!(amount_out >= min_amount_out, MockAmmError::SlippageExceeded);
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: pool_info,
+                },
+            ),
+            amount_in,
+        )?;
+
+        let mint = ctx.accounts.pool.mint;
+        let seeds: &[&[u8]] = &[b"pool", mint.as_ref(), &[ctx.accounts.pool.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.token_vault.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount_out,
+        )
+    }
+}
+
+/// x * y = k output for `amount_in` after the fee.
+pub fn quote(sol_reserve: u64, token_reserve: u64, amount_in: u64) -> Result<u64> {
+    let in_after_fee = amount_in as u128 * (10000 - FEE_BPS) as u128 / 10000;
+    let denominator = sol_reserve as u128 + in_after_fee;
+    require!(denominator > 0, MockAmmError::EmptyPool);
+    Ok((token_reserve as u128 * in_after_fee / denominator) as u64)
+}
+
+#[account]
+pub struct Pool {
+    pub mint: Pubkey,
+    pub token_vault: Pubkey,
+    pub bump: u8,
+}
+
+impl Pool {
+    pub const SIZE: usize = 8 + 32 + 32 + 1;
+}
+
+#[derive(Accounts)]
+pub struct InitPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = Pool::SIZE,
+        seeds = [b"pool", mint.key().as_ref()],
+        bump,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        constraint = token_vault.mint == mint.key() @ MockAmmError::InvalidVault,
+        constraint = token_vault.owner == pool.key() @ MockAmmError::InvalidVault,
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", pool.mint.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(mut, address = pool.token_vault @ MockAmmError::InvalidVault)]
+    pub token_vault: Account<'info, TokenAccount>,
+
+    #[account(mut, constraint = destination.mint == pool.mint @ MockAmmError::InvalidVault)]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[error_code]
+pub enum MockAmmError {
+    #[msg("Output is below the minimum")]
+    SlippageExceeded,
+    #[msg("Token vault does not belong to this pool")]
+    InvalidVault,
+    #[msg("Pool has no liquidity")]
+    EmptyPool,
+}
@@ -13,6 +13,7 @@ describe("alons-box", () => {
     const authority = (provider.wallet as anchor.Wallet).payer;
     const treasuryKeypair = anchor.web3.Keypair.generate();
     const buybackKeypair = anchor.web3.Keypair.generate();
+    const guardianKeypair = anchor.web3.Keypair.generate();
 
     // Test players
     const player1 = anchor.web3.Keypair.generate();
@@ -22,6 +23,9 @@ describe("alons-box", () => {
     // PDAs
     let gameStatePDA: anchor.web3.PublicKey;
     let vaultPDA: anchor.web3.PublicKey;
+    let jackpotPoolPDA: anchor.web3.PublicKey;
+    let rolesPDA: anchor.web3.PublicKey;
+    let treasuryStreamPDA: anchor.web3.PublicKey;
 
     // ── Helpers ──
 
@@ -51,6 +55,97 @@ describe("alons-box", () => {
         );
     }
 
+    function getQuestionPDA(
+        roundId: number,
+        asker: anchor.web3.PublicKey,
+        questionHash: number[]
+    ): [anchor.web3.PublicKey, number] {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(roundId));
+        return anchor.web3.PublicKey.findProgramAddressSync(
+            [
+                Buffer.from("question"),
+                buf,
+                asker.toBuffer(),
+                Buffer.from(questionHash),
+            ],
+            program.programId
+        );
+    }
+
+    function getPlayerLimitsPDA(
+        player: anchor.web3.PublicKey
+    ): [anchor.web3.PublicKey, number] {
+        return anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("player_limits"), player.toBuffer()],
+            program.programId
+        );
+    }
+
+    function getPlayerProfilePDA(
+        player: anchor.web3.PublicKey
+    ): [anchor.web3.PublicKey, number] {
+        return anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("player_profile"), player.toBuffer()],
+            program.programId
+        );
+    }
+
+    function getPlayerReferralPDA(
+        player: anchor.web3.PublicKey
+    ): [anchor.web3.PublicKey, number] {
+        return anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("referral"), player.toBuffer()],
+            program.programId
+        );
+    }
+
+    function getSettlementPDA(roundId: number): [anchor.web3.PublicKey, number] {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(roundId));
+        return anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("settlement"), buf],
+            program.programId
+        );
+    }
+
+    function sha256(text: string): number[] {
+        return Array.from(createHash("sha256").update(text).digest());
+    }
+
+    /** Record a question for `asker` and return its Question PDA. */
+    async function recordQuestion(
+        roundId: number,
+        asker: anchor.web3.PublicKey,
+        question: string,
+        isYes: boolean,
+        isPublic = true
+    ): Promise<anchor.web3.PublicKey> {
+        const questionHash = sha256(question);
+        const [questionPDA] = getQuestionPDA(roundId, asker, questionHash);
+        const [roundPDA] = getRoundPDA(roundId);
+
+        await program.methods
+            .recordQuestion(
+                questionHash,
+                isPublic,
+                sha256(isYes ? "yes" : "no"),
+                isYes
+            )
+            .accounts({
+                authority: authority.publicKey,
+                gameState: gameStatePDA,
+                round: roundPDA,
+                asker,
+                question: questionPDA,
+                roles: rolesPDA,
+                systemProgram: SystemProgram.programId,
+            })
+            .rpc();
+
+        return questionPDA;
+    }
+
     // ── Setup ──
 
     before(async () => {
@@ -62,6 +157,18 @@ describe("alons-box", () => {
             [Buffer.from("vault")],
             program.programId
         );
+        [jackpotPoolPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("jackpot_pool")],
+            program.programId
+        );
+        [rolesPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("roles")],
+            program.programId
+        );
+        [treasuryStreamPDA] = anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("treasury_stream")],
+            program.programId
+        );
 
         // Airdrop to players
         for (const player of [player1, player2, player3]) {
@@ -76,20 +183,35 @@ describe("alons-box", () => {
     // ── Tests ──
 
     it("Initializes the game state", async () => {
-        await program.methods
-            .initialize(treasuryKeypair.publicKey, buybackKeypair.publicKey)
-            .accounts({
-                authority: authority.publicKey,
-                gameState: gameStatePDA,
-                vault: vaultPDA,
-                systemProgram: SystemProgram.programId,
-            })
-            .rpc();
+        const initialize = (guardian: anchor.web3.PublicKey) =>
+            program.methods
+                .initialize(treasuryKeypair.publicKey, buybackKeypair.publicKey, guardian)
+                .accounts({
+                    authority: authority.publicKey,
+                    gameState: gameStatePDA,
+                    vault: vaultPDA,
+                    jackpotPool: jackpotPoolPDA,
+                    roles: rolesPDA,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
+        // The guardian must be a separate key
+        for (const guardian of [authority.publicKey, anchor.web3.PublicKey.default]) {
+            try {
+                await initialize(guardian);
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "InvalidGuardian");
+            }
+        }
+        await initialize(guardianKeypair.publicKey);
 
         const gs = await program.account.gameState.fetch(gameStatePDA);
         assert.ok(gs.authority.equals(authority.publicKey));
         assert.ok(gs.treasury.equals(treasuryKeypair.publicKey));
         assert.ok(gs.buybackWallet.equals(buybackKeypair.publicKey));
+        assert.ok(gs.guardian.equals(guardianKeypair.publicKey));
         assert.equal(gs.currentRoundId.toNumber(), 0);
     });
 
@@ -106,11 +228,14 @@ describe("alons-box", () => {
             [roundPDA] = getRoundPDA(1);
 
             await program.methods
-                .createRound(new anchor.BN(1), commitHash, new anchor.BN(endsAt))
+                .createRound(new anchor.BN(1), commitHash, new anchor.BN(endsAt), null, null, false, null)
                 .accounts({
                     authority: authority.publicKey,
                     gameState: gameStatePDA,
                     round: roundPDA,
+                    vault: vaultPDA,
+                    jackpotPool: jackpotPoolPDA,
+                    roles: rolesPDA,
                     systemProgram: SystemProgram.programId,
                 })
                 .rpc();
@@ -128,12 +253,17 @@ describe("alons-box", () => {
             const [depositPDA] = getDepositPDA(1, player1.publicKey);
 
             await program.methods
-                .deposit(new anchor.BN(amount))
+                .deposit(new anchor.BN(amount), null)
                 .accounts({
                     player: player1.publicKey,
+                    gameState: gameStatePDA,
                     round: roundPDA,
                     deposit: depositPDA,
                     vault: vaultPDA,
+                    playerLimits: getPlayerLimitsPDA(player1.publicKey)[0],
+                    playerReferral: getPlayerReferralPDA(player1.publicKey)[0],
+                    referrer: null,
+                    playerProfile: getPlayerProfilePDA(player1.publicKey)[0],
                     systemProgram: SystemProgram.programId,
                 })
                 .signers([player1])
@@ -153,24 +283,34 @@ describe("alons-box", () => {
             const [dep3PDA] = getDepositPDA(1, player3.publicKey);
 
             await program.methods
-                .deposit(new anchor.BN(amt2))
+                .deposit(new anchor.BN(amt2), null)
                 .accounts({
                     player: player2.publicKey,
+                    gameState: gameStatePDA,
                     round: roundPDA,
                     deposit: dep2PDA,
                     vault: vaultPDA,
+                    playerLimits: getPlayerLimitsPDA(player2.publicKey)[0],
+                    playerReferral: getPlayerReferralPDA(player2.publicKey)[0],
+                    referrer: null,
+                    playerProfile: getPlayerProfilePDA(player2.publicKey)[0],
                     systemProgram: SystemProgram.programId,
                 })
                 .signers([player2])
                 .rpc();
 
             await program.methods
-                .deposit(new anchor.BN(amt3))
+                .deposit(new anchor.BN(amt3), null)
                 .accounts({
                     player: player3.publicKey,
+                    gameState: gameStatePDA,
                     round: roundPDA,
                     deposit: dep3PDA,
                     vault: vaultPDA,
+                    playerLimits: getPlayerLimitsPDA(player3.publicKey)[0],
+                    playerReferral: getPlayerReferralPDA(player3.publicKey)[0],
+                    referrer: null,
+                    playerProfile: getPlayerProfilePDA(player3.publicKey)[0],
                     systemProgram: SystemProgram.programId,
                 })
                 .signers([player3])
@@ -200,24 +340,47 @@ describe("alons-box", () => {
                 player2.publicKey
             );
 
-            // Player2 asked a YES question → gets all evidence pool
+            // Player2 asked a YES question → weighted 10000 BPS, gets all evidence pool
+            const questionPDA = await recordQuestion(
+                1,
+                player2.publicKey,
+                "Is it a fruit?",
+                true
+            );
+            const question = await program.account.question.fetch(questionPDA);
+            assert.ok(question.asker.equals(player2.publicKey));
+            assert.equal(question.roundId.toNumber(), 1);
+            assert.isTrue(question.isYes);
+            assert.isTrue(question.isPublic);
+
             await program.methods
-                .settle(answer, salt, [new anchor.BN(evidencePool)])
+                .settle(answer, salt, 1, [10000], { equalSplit: {} })
                 .accounts({
                     authority: authority.publicKey,
                     gameState: gameStatePDA,
                     round: roundPDA,
+                    settlement: getSettlementPDA(1)[0],
                     vault: vaultPDA,
-                    winner: player1.publicKey,
                     treasury: treasuryKeypair.publicKey,
+                    buybackWallet: buybackKeypair.publicKey,
+                    jackpotPool: jackpotPoolPDA,
+                    roles: rolesPDA,
+                    treasuryStream: treasuryStreamPDA,
+                    season: null,
                     systemProgram: SystemProgram.programId,
                 })
                 .remainingAccounts([
+                    { pubkey: player1.publicKey, isSigner: false, isWritable: true },
                     {
                         pubkey: player2.publicKey,
                         isSigner: false,
                         isWritable: true,
                     },
+                    {
+                        pubkey: questionPDA,
+                        isSigner: false,
+                        isWritable: false,
+                    },
                 ])
                 .rpc();
 
@@ -257,11 +420,14 @@ describe("alons-box", () => {
             [roundPDA] = getRoundPDA(2);
 
             await program.methods
-                .createRound(new anchor.BN(2), commitHash, new anchor.BN(endsAt))
+                .createRound(new anchor.BN(2), commitHash, new anchor.BN(endsAt), null, null, false, null)
                 .accounts({
                     authority: authority.publicKey,
                     gameState: gameStatePDA,
                     round: roundPDA,
+                    vault: vaultPDA,
+                    jackpotPool: jackpotPoolPDA,
+                    roles: rolesPDA,
                     systemProgram: SystemProgram.programId,
                 })
                 .rpc();
@@ -276,12 +442,17 @@ describe("alons-box", () => {
             const [depositPDA] = getDepositPDA(2, player1.publicKey);
 
             await program.methods
-                .deposit(new anchor.BN(0.5 * LAMPORTS_PER_SOL))
+                .deposit(new anchor.BN(0.5 * LAMPORTS_PER_SOL), null)
                 .accounts({
                     player: player1.publicKey,
+                    gameState: gameStatePDA,
                     round: roundPDA,
                     deposit: depositPDA,
                     vault: vaultPDA,
+                    playerLimits: getPlayerLimitsPDA(player1.publicKey)[0],
+                    playerReferral: getPlayerReferralPDA(player1.publicKey)[0],
+                    referrer: null,
+                    playerProfile: getPlayerProfilePDA(player1.publicKey)[0],
                     systemProgram: SystemProgram.programId,
                 })
                 .signers([player1])
@@ -308,9 +479,13 @@ describe("alons-box", () => {
                     authority: authority.publicKey,
                     gameState: gameStatePDA,
                     round: roundPDA,
+                    settlement: getSettlementPDA(2)[0],
                     vault: vaultPDA,
                     treasury: treasuryKeypair.publicKey,
                     buybackWallet: buybackKeypair.publicKey,
+                    jackpotPool: jackpotPoolPDA,
+                    roles: rolesPDA,
+                    treasuryStream: treasuryStreamPDA,
                 })
                 .rpc();
 
@@ -351,12 +526,19 @@ describe("alons-box", () => {
                     .createRound(
                         new anchor.BN(3),
                         computeCommitHash("x", "y"),
-                        new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+                        new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+                        null,
+                        null,
+                        false,
+                        null
                     )
                     .accounts({
                         authority: fake.publicKey,
                         gameState: gameStatePDA,
                         round: roundPDA,
+                        vault: vaultPDA,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
                         systemProgram: SystemProgram.programId,
                     })
                     .signers([fake])
@@ -376,28 +558,43 @@ describe("alons-box", () => {
                 .createRound(
                     new anchor.BN(3),
                     commitHash,
-                    new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+                    new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+                    null,
+                    null,
+                    false,
+                    null
                 )
                 .accounts({
                     authority: authority.publicKey,
                     gameState: gameStatePDA,
                     round: roundPDA,
+                    vault: vaultPDA,
+                    jackpotPool: jackpotPoolPDA,
+                    roles: rolesPDA,
                     systemProgram: SystemProgram.programId,
                 })
                 .rpc();
 
             try {
                 await program.methods
-                    .settle("wrong", "wrong", [])
+                    .settle("wrong", "wrong", 1, [], { equalSplit: {} })
                     .accounts({
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: roundPDA,
+                        settlement: getSettlementPDA(3)[0],
                         vault: vaultPDA,
-                        winner: player1.publicKey,
                         treasury: treasuryKeypair.publicKey,
+                        buybackWallet: buybackKeypair.publicKey,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
+                        season: null,
                         systemProgram: SystemProgram.programId,
                     })
+                    .remainingAccounts([
+                        { pubkey: player1.publicKey, isSigner: false, isWritable: true },
+                    ])
                     .rpc();
                 assert.fail("Should have thrown");
             } catch (err) {
@@ -411,12 +608,17 @@ describe("alons-box", () => {
 
             try {
                 await program.methods
-                    .deposit(new anchor.BN(0.1 * LAMPORTS_PER_SOL))
+                    .deposit(new anchor.BN(0.1 * LAMPORTS_PER_SOL), null)
                     .accounts({
                         player: player1.publicKey,
+                        gameState: gameStatePDA,
                         round: roundPDA,
                         deposit: depositPDA,
                         vault: vaultPDA,
+                        playerLimits: getPlayerLimitsPDA(player1.publicKey)[0],
+                        playerReferral: getPlayerReferralPDA(player1.publicKey)[0],
+                        referrer: null,
+                        playerProfile: getPlayerProfilePDA(player1.publicKey)[0],
                         systemProgram: SystemProgram.programId,
                     })
                     .signers([player1])
@@ -443,17 +645,25 @@ describe("alons-box", () => {
 
             try {
                 await program.methods
-                    .settle("real answer", "real salt", [])
+                    .settle("real answer", "real salt", 1, [], { equalSplit: {} })
                     .accounts({
                         authority: attacker.publicKey,
                         gameState: gameStatePDA,
                         round: roundPDA,
+                        settlement: getSettlementPDA(3)[0],
                         vault: vaultPDA,
-                        winner: attacker.publicKey,
                         treasury: treasuryKeypair.publicKey,
+                        buybackWallet: buybackKeypair.publicKey,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
+                        season: null,
                         systemProgram: SystemProgram.programId,
                     })
                     .signers([attacker])
+                    .remainingAccounts([
+                        { pubkey: attacker.publicKey, isSigner: false, isWritable: true },
+                    ])
                     .rpc();
                 assert.fail("Should have thrown");
             } catch (err) {
@@ -478,9 +688,13 @@ describe("alons-box", () => {
                         authority: attacker.publicKey,
                         gameState: gameStatePDA,
                         round: roundPDA,
+                        settlement: getSettlementPDA(3)[0],
                         vault: vaultPDA,
                         treasury: treasuryKeypair.publicKey,
                         buybackWallet: buybackKeypair.publicKey,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
                     })
                     .signers([attacker])
                     .rpc();
@@ -496,16 +710,24 @@ describe("alons-box", () => {
 
             try {
                 await program.methods
-                    .settle("real answer", "real salt", [])
+                    .settle("real answer", "real salt", 1, [], { equalSplit: {} })
                     .accounts({
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: roundPDA,
+                        settlement: getSettlementPDA(3)[0],
                         vault: vaultPDA,
-                        winner: player1.publicKey,
                         treasury: fakeTreasury.publicKey,
+                        buybackWallet: buybackKeypair.publicKey,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
+                        season: null,
                         systemProgram: SystemProgram.programId,
                     })
+                    .remainingAccounts([
+                        { pubkey: player1.publicKey, isSigner: false, isWritable: true },
+                    ])
                     .rpc();
                 assert.fail("Should have thrown");
             } catch (err) {
@@ -521,16 +743,24 @@ describe("alons-box", () => {
 
             try {
                 await program.methods
-                    .settle("red apple", "a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6", [])
+                    .settle("red apple", "a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6", 1, [], { equalSplit: {} })
                     .accounts({
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: roundPDA,
+                        settlement: getSettlementPDA(1)[0],
                         vault: vaultPDA,
-                        winner: player1.publicKey,
                         treasury: treasuryKeypair.publicKey,
+                        buybackWallet: buybackKeypair.publicKey,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
+                        season: null,
                         systemProgram: SystemProgram.programId,
                     })
+                    .remainingAccounts([
+                        { pubkey: player1.publicKey, isSigner: false, isWritable: true },
+                    ])
                     .rpc();
                 assert.fail("Should have thrown");
             } catch (err) {
@@ -549,9 +779,13 @@ describe("alons-box", () => {
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: roundPDA,
+                        settlement: getSettlementPDA(2)[0],
                         vault: vaultPDA,
                         treasury: treasuryKeypair.publicKey,
                         buybackWallet: buybackKeypair.publicKey,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
                     })
                     .rpc();
                 assert.fail("Should have thrown");
@@ -570,9 +804,13 @@ describe("alons-box", () => {
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: roundPDA,
+                        settlement: getSettlementPDA(1)[0],
                         vault: vaultPDA,
                         treasury: treasuryKeypair.publicKey,
                         buybackWallet: buybackKeypair.publicKey,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
                     })
                     .rpc();
                 assert.fail("Should have thrown");
@@ -587,12 +825,17 @@ describe("alons-box", () => {
 
             try {
                 await program.methods
-                    .deposit(new anchor.BN(0.1 * LAMPORTS_PER_SOL))
+                    .deposit(new anchor.BN(0.1 * LAMPORTS_PER_SOL), null)
                     .accounts({
                         player: player2.publicKey,
+                        gameState: gameStatePDA,
                         round: roundPDA,
                         deposit: depositPDA,
                         vault: vaultPDA,
+                        playerLimits: getPlayerLimitsPDA(player2.publicKey)[0],
+                        playerReferral: getPlayerReferralPDA(player2.publicKey)[0],
+                        referrer: null,
+                        playerProfile: getPlayerProfilePDA(player2.publicKey)[0],
                         systemProgram: SystemProgram.programId,
                     })
                     .signers([player2])
@@ -616,53 +859,78 @@ describe("alons-box", () => {
                 .createRound(
                     new anchor.BN(4),
                     commitHash,
-                    new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+                    new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+                    null,
+                    null,
+                    false,
+                    null
                 )
                 .accounts({
                     authority: authority.publicKey,
                     gameState: gameStatePDA,
                     round: roundPDA,
+                    vault: vaultPDA,
+                    jackpotPool: jackpotPoolPDA,
+                    roles: rolesPDA,
                     systemProgram: SystemProgram.programId,
                 })
                 .rpc();
 
             const [depositPDA] = getDepositPDA(4, player1.publicKey);
             await program.methods
-                .deposit(new anchor.BN(1 * LAMPORTS_PER_SOL))
+                .deposit(new anchor.BN(1 * LAMPORTS_PER_SOL), null)
                 .accounts({
                     player: player1.publicKey,
+                    gameState: gameStatePDA,
                     round: roundPDA,
                     deposit: depositPDA,
                     vault: vaultPDA,
+                    playerLimits: getPlayerLimitsPDA(player1.publicKey)[0],
+                    playerReferral: getPlayerReferralPDA(player1.publicKey)[0],
+                    referrer: null,
+                    playerProfile: getPlayerProfilePDA(player1.publicKey)[0],
                     systemProgram: SystemProgram.programId,
                 })
                 .signers([player1])
                 .rpc();
 
-            const round = await program.account.round.fetch(roundPDA);
-            const pool =
-                round.totalDeposits.toNumber() + round.rolloverIn.toNumber();
-            const evidenceMax = Math.floor((pool * 3000) / 10000);
+            const questionPDA = await recordQuestion(
+                4,
+                player2.publicKey,
+                "Does it have wheels?",
+                true
+            );
 
-            // Try to claim MORE than 30% as evidence
+            // Try to weight evidence above 10000 BPS (more than the 30% pool)
             try {
                 await program.methods
-                    .settle(answer, salt, [new anchor.BN(evidenceMax + 1)])
+                    .settle(answer, salt, 1, [10001], { equalSplit: {} })
                     .accounts({
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: roundPDA,
+                        settlement: getSettlementPDA(4)[0],
                         vault: vaultPDA,
-                        winner: player1.publicKey,
                         treasury: treasuryKeypair.publicKey,
+                        buybackWallet: buybackKeypair.publicKey,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
+                        season: null,
                         systemProgram: SystemProgram.programId,
                     })
                     .remainingAccounts([
+                        { pubkey: player1.publicKey, isSigner: false, isWritable: true },
                         {
                             pubkey: player2.publicKey,
                             isSigner: false,
                             isWritable: true,
                         },
+                        {
+                            pubkey: questionPDA,
+                            isSigner: false,
+                            isWritable: false,
+                        },
                     ])
                     .rpc();
                 assert.fail("Should have thrown");
@@ -671,26 +939,137 @@ describe("alons-box", () => {
             }
         });
 
-        it("Rejects evidence wallet/amount count mismatch", async () => {
+        it("Rejects evidence to a wallet whose question was answered NO", async () => {
             const [roundPDA] = getRoundPDA(4); // Still active
+            const questionPDA = await recordQuestion(
+                4,
+                player3.publicKey,
+                "Is it edible?",
+                false
+            );
 
             try {
-                // 2 amounts but only 1 remaining account
                 await program.methods
-                    .settle("green car", "aabbccdd11223344", [
-                        new anchor.BN(1000),
-                        new anchor.BN(2000),
+                    .settle("green car", "aabbccdd11223344", 1, [1000], { equalSplit: {} })
+                    .accounts({
+                        authority: authority.publicKey,
+                        gameState: gameStatePDA,
+                        round: roundPDA,
+                        settlement: getSettlementPDA(4)[0],
+                        vault: vaultPDA,
+                        treasury: treasuryKeypair.publicKey,
+                        buybackWallet: buybackKeypair.publicKey,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
+                        season: null,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .remainingAccounts([
+                        { pubkey: player1.publicKey, isSigner: false, isWritable: true },
+                        { pubkey: player3.publicKey, isSigner: false, isWritable: true },
+                        { pubkey: questionPDA, isSigner: false, isWritable: false },
                     ])
+                    .rpc();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "IneligibleEvidence");
+            }
+        });
+
+        it("Rejects evidence paid against another player's question", async () => {
+            const [roundPDA] = getRoundPDA(4); // Still active
+            const [questionPDA] = getQuestionPDA(
+                4,
+                player2.publicKey,
+                sha256("Does it have wheels?")
+            );
+
+            try {
+                // player3 tries to claim using player2's YES question
+                await program.methods
+                    .settle("green car", "aabbccdd11223344", 1, [1000], { equalSplit: {} })
                     .accounts({
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: roundPDA,
+                        settlement: getSettlementPDA(4)[0],
                         vault: vaultPDA,
-                        winner: player1.publicKey,
                         treasury: treasuryKeypair.publicKey,
+                        buybackWallet: buybackKeypair.publicKey,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
+                        season: null,
                         systemProgram: SystemProgram.programId,
                     })
                     .remainingAccounts([
+                        { pubkey: player1.publicKey, isSigner: false, isWritable: true },
+                        { pubkey: player3.publicKey, isSigner: false, isWritable: true },
+                        { pubkey: questionPDA, isSigner: false, isWritable: false },
+                    ])
+                    .rpc();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "IneligibleEvidence");
+            }
+        });
+
+        it("Rejects record_question from non-authority", async () => {
+            const fake = anchor.web3.Keypair.generate();
+            const sig = await provider.connection.requestAirdrop(
+                fake.publicKey,
+                LAMPORTS_PER_SOL
+            );
+            await provider.connection.confirmTransaction(sig);
+
+            const questionHash = sha256("Is it blue?");
+            const [questionPDA] = getQuestionPDA(4, fake.publicKey, questionHash);
+            const [roundPDA] = getRoundPDA(4);
+
+            try {
+                await program.methods
+                    .recordQuestion(questionHash, true, sha256("yes"), true)
+                    .accounts({
+                        authority: fake.publicKey,
+                        gameState: gameStatePDA,
+                        round: roundPDA,
+                        asker: fake.publicKey,
+                        question: questionPDA,
+                        roles: rolesPDA,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .signers([fake])
+                    .rpc();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "Unauthorized");
+            }
+        });
+
+        it("Rejects evidence wallet/weight count mismatch", async () => {
+            const [roundPDA] = getRoundPDA(4); // Still active
+
+            try {
+                // 2 weights but only 1 remaining account
+                await program.methods
+                    .settle("green car", "aabbccdd11223344", 1, [1000, 2000], { equalSplit: {} })
+                    .accounts({
+                        authority: authority.publicKey,
+                        gameState: gameStatePDA,
+                        round: roundPDA,
+                        settlement: getSettlementPDA(4)[0],
+                        vault: vaultPDA,
+                        treasury: treasuryKeypair.publicKey,
+                        buybackWallet: buybackKeypair.publicKey,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
+                        season: null,
+                        systemProgram: SystemProgram.programId,
+                    })
+                    .remainingAccounts([
+                        { pubkey: player1.publicKey, isSigner: false, isWritable: true },
                         {
                             pubkey: player2.publicKey,
                             isSigner: false,
@@ -715,12 +1094,19 @@ describe("alons-box", () => {
                     .createRound(
                         new anchor.BN(99),
                         computeCommitHash("x", "y"),
-                        new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+                        new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+                        null,
+                        null,
+                        false,
+                        null
                     )
                     .accounts({
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: roundPDA,
+                        vault: vaultPDA,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
                         systemProgram: SystemProgram.programId,
                     })
                     .rpc();
@@ -740,12 +1126,19 @@ describe("alons-box", () => {
                     .createRound(
                         new anchor.BN(1),
                         computeCommitHash("x", "y"),
-                        new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+                        new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+                        null,
+                        null,
+                        false,
+                        null
                     )
                     .accounts({
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: roundPDA,
+                        vault: vaultPDA,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
                         systemProgram: SystemProgram.programId,
                     })
                     .rpc();
@@ -1,6 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { AlonsBox } from "../target/types/alons_box";
+import { MockAmm } from "../target/types/mock_amm";
 import { assert } from "chai";
 import { createHash } from "crypto";
 import { SystemProgram, LAMPORTS_PER_SOL, Keypair, PublicKey } from "@solana/web3.js";
@@ -31,6 +32,7 @@ describe("rollover-accounting", () => {
     // Wallets — treasury/buyback are PublicKeys read from on-chain state
     const treasuryKeypair = Keypair.generate();
     const buybackKeypair = Keypair.generate();
+    const guardianKeypair = Keypair.generate();
     let treasuryPk: PublicKey;
     let buybackPk: PublicKey;
     const player1 = Keypair.generate();
@@ -40,6 +42,10 @@ describe("rollover-accounting", () => {
     // PDAs
     let gameStatePDA: PublicKey;
     let vaultPDA: PublicKey;
+    let archivePDA: PublicKey;
+    let jackpotPoolPDA: PublicKey;
+    let rolesPDA: PublicKey;
+    let treasuryStreamPDA: PublicKey;
     let nextRoundId = 0; // tracks sequential round IDs across all tests
     // Constant surplus in vault from other test files' active rounds (deposits not yet settled/expired)
     let vaultSurplus = 0;
@@ -73,6 +79,49 @@ describe("rollover-accounting", () => {
         );
     }
 
+    function playerLimitsPDA(player: PublicKey): [PublicKey, number] {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("player_limits"), player.toBuffer()],
+            program.programId
+        );
+    }
+
+    function playerProfilePDA(player: PublicKey): [PublicKey, number] {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("player_profile"), player.toBuffer()],
+            program.programId
+        );
+    }
+
+    function playerReferralPDA(player: PublicKey): [PublicKey, number] {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("referral"), player.toBuffer()],
+            program.programId
+        );
+    }
+
+    function referrerPDA(owner: PublicKey): [PublicKey, number] {
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("referrer"), owner.toBuffer()],
+            program.programId
+        );
+    }
+
+    /** Season PDA a round is counted in, or null when its season_id is 0 */
+    function roundSeason(seasonId: number): PublicKey | null {
+        if (seasonId === 0) return null;
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(seasonId));
+        return PublicKey.findProgramAddressSync([Buffer.from("season"), buf], program.programId)[0];
+    }
+
+    /** Mirror of GameState::season_for: the Season a new round `id` is counted in */
+    async function newRoundSeason(id: number): Promise<PublicKey | null> {
+        const gs = await program.account.gameState.fetch(gameStatePDA);
+        const inRange = id >= gs.seasonStartRoundId.toNumber() && id <= gs.seasonEndRoundId.toNumber();
+        return roundSeason(inRange ? gs.seasonId.toNumber() : 0);
+    }
+
     async function airdrop(to: PublicKey, sol: number) {
         const sig = await provider.connection.requestAirdrop(
             to,
@@ -89,7 +138,11 @@ describe("rollover-accounting", () => {
     async function createNextRound(
         answer: string,
         salt: string,
-        endsAtOffset = 3600
+        endsAtOffset = 3600,
+        softClose: any = null,
+        minPool: number | null = null,
+        jackpot = false,
+        depositPolicy: any = null
     ): Promise<[number, PublicKey]> {
         nextRoundId++;
         const id = nextRoundId;
@@ -100,12 +153,20 @@ describe("rollover-accounting", () => {
             .createRound(
                 new anchor.BN(id),
                 commitHash(answer, salt),
-                new anchor.BN(endsAt)
+                new anchor.BN(endsAt),
+                softClose,
+                minPool === null ? null : new anchor.BN(minPool),
+                jackpot,
+                depositPolicy
             )
             .accounts({
                 authority: authority.publicKey,
                 gameState: gameStatePDA,
                 round: rPDA,
+                vault: vaultPDA,
+                jackpotPool: jackpotPoolPDA,
+                roles: rolesPDA,
+                season: await newRoundSeason(id),
                 systemProgram: SystemProgram.programId,
             })
             .rpc();
@@ -135,12 +196,20 @@ describe("rollover-accounting", () => {
             .createRound(
                 new anchor.BN(id),
                 commitHash(answer, salt),
-                new anchor.BN(endsAt)
+                new anchor.BN(endsAt),
+                null,
+                null,
+                false,
+                null
             )
             .accounts({
                 authority: authority.publicKey,
                 gameState: gameStatePDA,
                 round: rPDA,
+                vault: vaultPDA,
+                jackpotPool: jackpotPoolPDA,
+                roles: rolesPDA,
+                season: await newRoundSeason(id),
                 systemProgram: SystemProgram.programId,
             })
             .rpc();
@@ -152,79 +221,309 @@ describe("rollover-accounting", () => {
         roundId: number,
         rPDA: PublicKey,
         player: Keypair,
-        lamports: number
+        lamports: number,
+        referrer: PublicKey | null = null
     ) {
         const [dPDA] = depositPDA(roundId, player.publicKey);
         await program.methods
-            .deposit(new anchor.BN(lamports))
+            .deposit(new anchor.BN(lamports), referrer)
             .accounts({
                 player: player.publicKey,
+                gameState: gameStatePDA,
                 round: rPDA,
                 deposit: dPDA,
                 vault: vaultPDA,
+                playerLimits: playerLimitsPDA(player.publicKey)[0],
+                playerReferral: playerReferralPDA(player.publicKey)[0],
+                referrer: referrer ? referrerPDA(referrer)[0] : null,
+                playerProfile: playerProfilePDA(player.publicKey)[0],
                 systemProgram: SystemProgram.programId,
             })
             .signers([player])
             .rpc();
     }
 
-    async function settle(
+    function questionPDA(
+        id: number,
+        asker: PublicKey,
+        questionHash: number[]
+    ): [PublicKey, number] {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(id));
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("question"), buf, asker.toBuffer(), Buffer.from(questionHash)],
+            program.programId
+        );
+    }
+
+    function guessPDA(
+        id: number,
+        player: PublicKey
+    ): [PublicKey, number] {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(id));
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("guess"), buf, player.toBuffer()],
+            program.programId
+        );
+    }
+
+    function settlementPDA(id: number): [PublicKey, number] {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(id));
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("settlement"), buf],
+            program.programId
+        );
+    }
+
+    /** Archive leaf: SHA-256(0x00 || borsh(RoundSummary)) built from a Settlement */
+    function archiveLeaf(rec: any): Buffer {
+        const u64 = (n: any) => {
+            const b = Buffer.alloc(8);
+            b.writeBigUInt64LE(BigInt(n.toString()));
+            return b;
+        };
+        const i64 = (n: any) => {
+            const b = Buffer.alloc(8);
+            b.writeBigInt64LE(BigInt(n.toString()));
+            return b;
+        };
+        const u32 = (n: number) => {
+            const b = Buffer.alloc(4);
+            b.writeUInt32LE(n);
+            return b;
+        };
+        const outcome = ["active", "settled", "expired", "refunding"].indexOf(
+            Object.keys(rec.outcome)[0]
+        );
+        const data = Buffer.concat([
+            u64(rec.roundId),
+            Buffer.from([outcome]),
+            Buffer.from(rec.commitHash),
+            Buffer.from(rec.answerHash),
+            u64(rec.pool),
+            u64(rec.rolloverIn),
+            u64(rec.rolloverOut),
+            u32(rec.winners.length),
+            ...rec.winners.map((w: PublicKey) => w.toBuffer()),
+            u32(rec.winnerAmounts.length),
+            ...rec.winnerAmounts.map(u64),
+            u64(rec.evidenceTotal),
+            u64(rec.treasuryAmount),
+            u64(rec.buybackAmount),
+            u64(rec.jackpotAmount),
+            u64(rec.seasonAmount),
+            i64(rec.finalizedAt),
+        ]);
+        return createHash("sha256").update(Buffer.from([0])).update(data).digest();
+    }
+
+    function archiveNode(left: Buffer, right: Buffer): Buffer {
+        return createHash("sha256")
+            .update(Buffer.from([1]))
+            .update(left)
+            .update(right)
+            .digest();
+    }
+
+    /** Root after appending `leaf` to an archive with the given frontier */
+    function archiveRootAfter(frontier: number[][], leafCount: number, leaf: Buffer): Buffer {
+        let node = leaf;
+        let zero = Buffer.alloc(32);
+        let position = leafCount;
+        for (let level = 0; level < frontier.length; level++) {
+            node = position % 2 === 0
+                ? archiveNode(node, zero)
+                : archiveNode(Buffer.from(frontier[level]), node);
+            zero = archiveNode(zero, zero);
+            position = Math.floor(position / 2);
+        }
+        return node;
+    }
+
+    /** Record a YES question for `asker` so it is eligible for evidence payouts. */
+    async function recordYesQuestion(
+        roundId: number,
+        rPDA: PublicKey,
+        asker: PublicKey,
+        question: string
+    ): Promise<PublicKey> {
+        const qHash = Array.from(createHash("sha256").update(question).digest());
+        const aHash = Array.from(createHash("sha256").update("yes").digest());
+        const [qPDA] = questionPDA(roundId, asker, qHash);
+        await program.methods
+            .recordQuestion(qHash, true, aHash, true)
+            .accounts({
+                authority: authority.publicKey,
+                gameState: gameStatePDA,
+                round: rPDA,
+                asker,
+                question: qPDA,
+                roles: rolesPDA,
+                systemProgram: SystemProgram.programId,
+            })
+            .rpc();
+        return qPDA;
+    }
+
+    /** Record `player`'s guess in the round; returns the Guess PDA. */
+    async function recordGuess(
+        roundId: number,
+        rPDA: PublicKey,
+        player: PublicKey,
+        guess: string
+    ): Promise<PublicKey> {
+        const guessHash = Array.from(createHash("sha256").update(guess).digest());
+        const [gPDA] = guessPDA(roundId, player);
+        await program.methods
+            .recordGuess(guessHash)
+            .accounts({
+                authority: authority.publicKey,
+                gameState: gameStatePDA,
+                round: rPDA,
+                player,
+                guess: gPDA,
+                roles: rolesPDA,
+                systemProgram: SystemProgram.programId,
+            })
+            .rpc();
+        return gPDA;
+    }
+
+    async function settleWinners(
         rPDA: PublicKey,
         answer: string,
         salt: string,
-        winner: PublicKey,
-        evidenceAmounts: number[],
-        evidenceWallets: PublicKey[]
+        winners: PublicKey[],
+        tieBreak: object,
+        evidenceWeights: number[] = [],
+        evidenceWallets: PublicKey[] = [],
+        withProfiles = false,
+        season: PublicKey | null = null
     ) {
+        // Every evidence wallet needs a YES Question PDA in the round
+        const round = await program.account.round.fetch(rPDA);
+        const roundId = round.roundId.toNumber();
+        const evidenceAccounts = [];
+        for (const [i, pk] of evidenceWallets.entries()) {
+            const qPDA = await recordYesQuestion(
+                roundId,
+                rPDA,
+                pk,
+                `evidence question ${i}`
+            );
+            evidenceAccounts.push(
+                { pubkey: pk, isSigner: false, isWritable: true },
+                { pubkey: qPDA, isSigner: false, isWritable: false }
+            );
+        }
+
         await program.methods
-            .settle(
-                answer,
-                salt,
-                evidenceAmounts.map((a) => new anchor.BN(a))
-            )
+            .settle(answer, salt, winners.length, evidenceWeights, tieBreak as any)
             .accounts({
                 authority: authority.publicKey,
                 gameState: gameStatePDA,
                 round: rPDA,
+                settlement: settlementPDA(roundId)[0],
                 vault: vaultPDA,
-                winner,
                 treasury: treasuryPk,
+                buybackWallet: buybackPk,
+                jackpotPool: jackpotPoolPDA,
+                roles: rolesPDA,
+                treasuryStream: treasuryStreamPDA,
+                season,
                 systemProgram: SystemProgram.programId,
             })
-            .remainingAccounts(
-                evidenceWallets.map((pk) => ({
+            .remainingAccounts([
+                ...winners.map((pk) => ({
                     pubkey: pk,
                     isSigner: false,
                     isWritable: true,
-                }))
-            )
+                })),
+                // LargestDeposit splits by each winner's Deposit in the round,
+                // EarliestGuess by each winner's Guess
+                ...("largestDeposit" in tieBreak ? winners : []).map((pk) => ({
+                    pubkey: depositPDA(roundId, pk)[0],
+                    isSigner: false,
+                    isWritable: false,
+                })),
+                ...("earliestGuess" in tieBreak ? winners : []).map((pk) => ({
+                    pubkey: guessPDA(roundId, pk)[0],
+                    isSigner: false,
+                    isWritable: false,
+                })),
+                ...evidenceAccounts,
+                ...(withProfiles ? [...winners, ...evidenceWallets] : []).map((pk) => ({
+                    pubkey: playerProfilePDA(pk)[0],
+                    isSigner: false,
+                    isWritable: true,
+                })),
+            ])
             .rpc();
     }
 
-    async function expire(rPDA: PublicKey, answer: string, salt: string) {
+    /** Single-winner settle: the winner takes the full 50% share. */
+    async function settle(
+        rPDA: PublicKey,
+        answer: string,
+        salt: string,
+        winner: PublicKey,
+        evidenceWeights: number[],
+        evidenceWallets: PublicKey[]
+    ) {
+        await settleWinners(
+            rPDA,
+            answer,
+            salt,
+            [winner],
+            { equalSplit: {} },
+            evidenceWeights,
+            evidenceWallets
+        );
+    }
+
+    async function expire(
+        rPDA: PublicKey,
+        answer: string,
+        salt: string,
+        signer: Keypair = authority
+    ) {
+        const round = await program.account.round.fetch(rPDA);
         await program.methods
             .expire(answer, salt)
             .accounts({
-                authority: authority.publicKey,
+                authority: signer.publicKey,
                 gameState: gameStatePDA,
                 round: rPDA,
+                settlement: settlementPDA(roundId)[0],
                 vault: vaultPDA,
                 treasury: treasuryPk,
                 buybackWallet: buybackPk,
+                jackpotPool: jackpotPoolPDA,
+                roles: rolesPDA,
+                treasuryStream: treasuryStreamPDA,
+                season: roundSeason(round.seasonId.toNumber()),
             })
+            .signers([signer])
             .rpc();
     }
 
     async function emergencyExpire(rPDA: PublicKey, caller: Keypair) {
+        const round = await program.account.round.fetch(rPDA);
         await program.methods
             .emergencyExpire()
             .accounts({
                 caller: caller.publicKey,
                 gameState: gameStatePDA,
                 round: rPDA,
+                settlement: settlementPDA(roundId)[0],
                 vault: vaultPDA,
                 treasury: treasuryPk,
                 buybackWallet: buybackPk,
+                jackpotPool: jackpotPoolPDA,
+                treasuryStream: treasuryStreamPDA,
+                season: roundSeason(round.seasonId.toNumber()),
             })
             .signers([caller])
             .rpc();
@@ -240,17 +539,160 @@ describe("rollover-accounting", () => {
 
     /** Assert vault balance == game_state.rollover_balance + rent + vaultSurplus
      *  vaultSurplus accounts for deposits from other test files' active rounds. */
+    /** Unvested + vested treasury lamports the vault holds for the stream (0 before it exists). */
+    async function streamLiability(): Promise<number> {
+        const stream = await program.account.treasuryStream.fetchNullable(treasuryStreamPDA);
+        return stream ? stream.unvested.toNumber() + stream.vested.toNumber() : 0;
+    }
+
     async function assertVaultConsistency(label: string) {
         const gs = await program.account.gameState.fetch(gameStatePDA);
         const vaultBal = await getBalance(vaultPDA);
         const rent = await vaultRent();
         assert.equal(
             vaultBal,
-            gs.rolloverBalance.toNumber() + rent + vaultSurplus,
+            gs.rolloverBalance.toNumber() +
+                rent +
+                vaultSurplus +
+                (await streamLiability()) +
+                gs.referralLiability.toNumber(),
             `Vault consistency failed at: ${label}`
         );
     }
 
+    function pendingChangePDA(changeId: number): [PublicKey, number] {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(changeId));
+        return PublicKey.findProgramAddressSync(
+            [Buffer.from("pending_change"), buf],
+            program.programId
+        );
+    }
+
+    /** Queue a config change. Returns its change id. */
+    async function queueChange(change: any, admin: Keypair = authority): Promise<number> {
+        const gs = await program.account.gameState.fetch(gameStatePDA);
+        const changeId = gs.changeCount.toNumber() + 1;
+        await program.methods
+            .queueChange(change)
+            .accounts({
+                admin: admin.publicKey,
+                gameState: gameStatePDA,
+                roles: rolesPDA,
+                governance: PublicKey.findProgramAddressSync(
+                    [Buffer.from("governance")],
+                    program.programId
+                )[0],
+                pendingChange: pendingChangePDA(changeId)[0],
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([admin])
+            .rpc();
+        return changeId;
+    }
+
+    async function executeChange(changeId: number, executor: Keypair = authority) {
+        const pending = await program.account.pendingChange.fetch(pendingChangePDA(changeId)[0]);
+        await program.methods
+            .executeChange()
+            .accounts({
+                executor: executor.publicKey,
+                gameState: gameStatePDA,
+                roles: rolesPDA,
+                pendingChange: pendingChangePDA(changeId)[0],
+                queuedBy: pending.queuedBy,
+            })
+            .signers([executor])
+            .rpc();
+    }
+
+    /** Queue and execute at once — only valid while timelock_delay is 0 */
+    async function applyChange(change: any, admin: Keypair = authority) {
+        const changeId = await queueChange(change, admin);
+        await executeChange(changeId);
+    }
+
+    // ── SPL token helpers ──
+    // Built by hand to avoid pulling in @solana/spl-token for three instructions
+
+    const TOKEN_PROGRAM_ID = new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+    function u64(n: number): Buffer {
+        const buf = Buffer.alloc(8);
+        buf.writeBigUInt64LE(BigInt(n));
+        return buf;
+    }
+
+    async function sendIxs(ixs: anchor.web3.TransactionInstruction[], signers: Keypair[]) {
+        const tx = new anchor.web3.Transaction().add(...ixs);
+        await provider.sendAndConfirm(tx, signers);
+    }
+
+    /** Create a 0-decimal mint with the provider wallet as mint authority. */
+    async function createMint(mint: Keypair) {
+        const space = 82;
+        await sendIxs(
+            [
+                SystemProgram.createAccount({
+                    fromPubkey: authority.publicKey,
+                    newAccountPubkey: mint.publicKey,
+                    lamports: await provider.connection.getMinimumBalanceForRentExemption(space),
+                    space,
+                    programId: TOKEN_PROGRAM_ID,
+                }),
+                // InitializeMint2 { decimals: 0, mint_authority, freeze_authority: None }
+                new anchor.web3.TransactionInstruction({
+                    programId: TOKEN_PROGRAM_ID,
+                    keys: [{ pubkey: mint.publicKey, isSigner: false, isWritable: true }],
+                    data: Buffer.concat([Buffer.from([20, 0]), authority.publicKey.toBuffer(), Buffer.from([0])]),
+                }),
+            ],
+            [mint]
+        );
+    }
+
+    /** Create a token account for `owner` holding `amount` freshly minted tokens. */
+    async function createTokenAccount(mint: PublicKey, owner: PublicKey, amount: number): Promise<PublicKey> {
+        const account = Keypair.generate();
+        const space = 165;
+        await sendIxs(
+            [
+                SystemProgram.createAccount({
+                    fromPubkey: authority.publicKey,
+                    newAccountPubkey: account.publicKey,
+                    lamports: await provider.connection.getMinimumBalanceForRentExemption(space),
+                    space,
+                    programId: TOKEN_PROGRAM_ID,
+                }),
+                // InitializeAccount3 { owner }
+                new anchor.web3.TransactionInstruction({
+                    programId: TOKEN_PROGRAM_ID,
+                    keys: [
+                        { pubkey: account.publicKey, isSigner: false, isWritable: true },
+                        { pubkey: mint, isSigner: false, isWritable: false },
+                    ],
+                    data: Buffer.concat([Buffer.from([18]), owner.toBuffer()]),
+                }),
+                // MintTo { amount }
+                new anchor.web3.TransactionInstruction({
+                    programId: TOKEN_PROGRAM_ID,
+                    keys: [
+                        { pubkey: mint, isSigner: false, isWritable: true },
+                        { pubkey: account.publicKey, isSigner: false, isWritable: true },
+                        { pubkey: authority.publicKey, isSigner: true, isWritable: false },
+                    ],
+                    data: Buffer.concat([Buffer.from([7]), u64(amount)]),
+                }),
+            ],
+            [account]
+        );
+        return account.publicKey;
+    }
+
+    async function tokenBalance(account: PublicKey): Promise<number> {
+        return Number((await provider.connection.getTokenAccountBalance(account)).value.amount);
+    }
+
     // ── Setup ──
 
     before(async () => {
@@ -262,6 +704,22 @@ describe("rollover-accounting", () => {
             [Buffer.from("vault")],
             program.programId
         );
+        [archivePDA] = PublicKey.findProgramAddressSync(
+            [Buffer.from("round_archive")],
+            program.programId
+        );
+        [jackpotPoolPDA] = PublicKey.findProgramAddressSync(
+            [Buffer.from("jackpot_pool")],
+            program.programId
+        );
+        [rolesPDA] = PublicKey.findProgramAddressSync(
+            [Buffer.from("roles")],
+            program.programId
+        );
+        [treasuryStreamPDA] = PublicKey.findProgramAddressSync(
+            [Buffer.from("treasury_stream")],
+            program.programId
+        );
 
         // Airdrop to all test wallets
         for (const kp of [player1, player2, player3]) {
@@ -279,11 +737,17 @@ describe("rollover-accounting", () => {
 
         if (!alreadyInitialized) {
             await program.methods
-                .initialize(treasuryKeypair.publicKey, buybackKeypair.publicKey)
+                .initialize(
+                    treasuryKeypair.publicKey,
+                    buybackKeypair.publicKey,
+                    guardianKeypair.publicKey
+                )
                 .accounts({
                     authority: authority.publicKey,
                     gameState: gameStatePDA,
                     vault: vaultPDA,
+                    jackpotPool: jackpotPoolPDA,
+                    roles: rolesPDA,
                     systemProgram: SystemProgram.programId,
                 })
                 .rpc();
@@ -396,7 +860,7 @@ describe("rollover-accounting", () => {
                 a3,
                 s3,
                 player2.publicKey,
-                [evidencePool],
+                [10000],
                 [player3.publicKey]
             );
             const after = await getBalance(player3.publicKey);
@@ -423,7 +887,7 @@ describe("rollover-accounting", () => {
                 a4,
                 s4,
                 player1.publicKey,
-                [evidence],
+                [10000],
                 [player2.publicKey]
             );
 
@@ -466,7 +930,8 @@ describe("rollover-accounting", () => {
                 round.totalDeposits.toNumber() + round.rolloverIn.toNumber();
             const winner = Math.floor((pool * 5000) / 10000);
             const treas = Math.floor((pool * 500) / 10000);
-            const partialEvidence = 1000; // tiny amount
+            const evidencePool = Math.floor((pool * 3000) / 10000);
+            const partialEvidence = Math.floor((evidencePool * 1) / 10000); // 1 BPS
             const expectedRollover = pool - winner - partialEvidence - treas;
 
             await settle(
@@ -474,7 +939,7 @@ describe("rollover-accounting", () => {
                 a6,
                 s6,
                 player1.publicKey,
-                [partialEvidence],
+                [1],
                 [player3.publicKey]
             );
 
@@ -493,8 +958,9 @@ describe("rollover-accounting", () => {
             const pool =
                 round.totalDeposits.toNumber() + round.rolloverIn.toNumber();
             const evidencePool = Math.floor((pool * 3000) / 10000);
-            const ev1 = Math.floor(evidencePool / 2);
-            const ev2 = evidencePool - ev1;
+            // 70/30 weighting — each share rounds down, dust stays in rollover
+            const ev1 = Math.floor((evidencePool * 7000) / 10000);
+            const ev2 = Math.floor((evidencePool * 3000) / 10000);
 
             const before2 = await getBalance(player2.publicKey);
             const before3 = await getBalance(player3.publicKey);
@@ -504,7 +970,7 @@ describe("rollover-accounting", () => {
                 a7,
                 s7,
                 player1.publicKey,
-                [ev1, ev2],
+                [7000, 3000],
                 [player2.publicKey, player3.publicKey]
             );
 
@@ -814,7 +1280,7 @@ describe("rollover-accounting", () => {
                 a,
                 s,
                 player1.publicKey,
-                [evidencePool],
+                [10000],
                 [player3.publicKey]
             );
 
@@ -1136,12 +1602,20 @@ describe("rollover-accounting", () => {
                     .createRound(
                         new anchor.BN(id),
                         commitHash("x", "y"),
-                        new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+                        new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+                        null,
+                        null,
+                        false,
+                        null
                     )
                     .accounts({
                         authority: attacker.publicKey,
                         gameState: gameStatePDA,
                         round: rPDA,
+                        vault: vaultPDA,
+                        jackpotPool: jackpotPoolPDA,
+                        season: null,
+                        roles: rolesPDA,
                         systemProgram: SystemProgram.programId,
                     })
                     .signers([attacker])
@@ -1163,17 +1637,25 @@ describe("rollover-accounting", () => {
 
             try {
                 await program.methods
-                    .settle(a, s, [])
+                    .settle(a, s, 1, [], { equalSplit: {} })
                     .accounts({
                         authority: attacker.publicKey,
                         gameState: gameStatePDA,
                         round: pda,
+                        settlement: settlementPDA(id)[0],
                         vault: vaultPDA,
-                        winner: attacker.publicKey,
                         treasury: treasuryPk,
+                        buybackWallet: buybackPk,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
+                        season: null,
                         systemProgram: SystemProgram.programId,
                     })
                     .signers([attacker])
+                    .remainingAccounts([
+                        { pubkey: attacker.publicKey, isSigner: false, isWritable: true },
+                    ])
                     .rpc();
                 assert.fail("Should have thrown");
             } catch (err) {
@@ -1199,9 +1681,14 @@ describe("rollover-accounting", () => {
                         authority: attacker.publicKey,
                         gameState: gameStatePDA,
                         round: pda,
+                        settlement: settlementPDA(id)[0],
                         vault: vaultPDA,
                         treasury: treasuryPk,
                         buybackWallet: buybackPk,
+                        jackpotPool: jackpotPoolPDA,
+                        season: null,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
                     })
                     .signers([attacker])
                     .rpc();
@@ -1224,16 +1711,24 @@ describe("rollover-accounting", () => {
 
             try {
                 await program.methods
-                    .settle(a, s, [])
+                    .settle(a, s, 1, [], { equalSplit: {} })
                     .accounts({
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: pda,
+                        settlement: settlementPDA(id)[0],
                         vault: vaultPDA,
-                        winner: player1.publicKey,
                         treasury: fakeTreasury.publicKey,
+                        buybackWallet: buybackPk,
+                        jackpotPool: jackpotPoolPDA,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
+                        season: null,
                         systemProgram: SystemProgram.programId,
                     })
+                    .remainingAccounts([
+                        { pubkey: player1.publicKey, isSigner: false, isWritable: true },
+                    ])
                     .rpc();
                 assert.fail("Should have thrown");
             } catch (err) {
@@ -1258,9 +1753,14 @@ describe("rollover-accounting", () => {
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: pda,
+                        settlement: settlementPDA(id)[0],
                         vault: vaultPDA,
                         treasury: fakeTreasury.publicKey,
                         buybackWallet: buybackPk,
+                        jackpotPool: jackpotPoolPDA,
+                        season: null,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
                     })
                     .rpc();
                 assert.fail("Should have thrown");
@@ -1286,9 +1786,14 @@ describe("rollover-accounting", () => {
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: pda,
+                        settlement: settlementPDA(id)[0],
                         vault: vaultPDA,
                         treasury: treasuryPk,
                         buybackWallet: fakeBuyback.publicKey,
+                        jackpotPool: jackpotPoolPDA,
+                        season: null,
+                        roles: rolesPDA,
+                        treasuryStream: treasuryStreamPDA,
                     })
                     .rpc();
                 assert.fail("Should have thrown");
@@ -1316,6 +1821,11 @@ describe("rollover-accounting", () => {
                         authority: attacker.publicKey,
                         gameState: gameStatePDA,
                         round: pda,
+                        settlement: settlementPDA(id)[0],
+                        rentPayer: authority.publicKey,
+                        archive: archivePDA,
+                        roles: rolesPDA,
+                        systemProgram: SystemProgram.programId,
                     })
                     .signers([attacker])
                     .rpc();
@@ -1344,6 +1854,7 @@ describe("rollover-accounting", () => {
                         gameState: gameStatePDA,
                         round: pda,
                         deposit: dPDA,
+                        roles: rolesPDA,
                     })
                     .signers([attacker])
                     .rpc();
@@ -1462,24 +1973,19 @@ describe("rollover-accounting", () => {
     // ═══════════════════════════════════════════════════
 
     describe("9. Adversarial — payout manipulation", () => {
-        it("T054: Evidence overpay (exceed 30% cap) is rejected", async () => {
+        it("T054: Evidence overpay (weights exceed 10000 BPS) is rejected", async () => {
             const a = "payout-overpay";
             const s = "payout-soverpay";
             const [id, pda] = await createNextRound(a, s);
             await deposit(id, pda, player1, LAMPORTS_PER_SOL);
 
-            const round = await program.account.round.fetch(pda);
-            const pool =
-                round.totalDeposits.toNumber() + round.rolloverIn.toNumber();
-            const evidenceMax = Math.floor((pool * 3000) / 10000);
-
             try {
                 await settle(
                     pda,
                     a,
                     s,
                     player1.publicKey,
-                    [evidenceMax + 1],
+                    [10001],
                     [player2.publicKey]
                 );
                 assert.fail("Should have thrown");
@@ -1491,14 +1997,14 @@ describe("rollover-accounting", () => {
             await expire(pda, a, s);
         });
 
-        it("T055: Evidence amounts/wallets count mismatch rejected", async () => {
+        it("T055: Evidence weights/wallets count mismatch rejected", async () => {
             const a = "payout-mismatch";
             const s = "payout-smismatch";
             const [id, pda] = await createNextRound(a, s);
             await deposit(id, pda, player1, LAMPORTS_PER_SOL);
 
             try {
-                // 2 amounts but 1 wallet
+                // 2 weights but 1 wallet
                 await settle(
                     pda,
                     a,
@@ -1533,14 +2039,14 @@ describe("rollover-accounting", () => {
                 a,
                 s,
                 player1.publicKey,
-                [evidenceMax],
+                [10000],
                 [player3.publicKey]
             );
             const after = await getBalance(player3.publicKey);
             assert.equal(after - before, evidenceMax);
         });
 
-        it("T057: Zero evidence amount with wallet provided accepted", async () => {
+        it("T057: Zero evidence weight with wallet provided accepted", async () => {
             const a = "payout-zero-ev";
             const s = "payout-szero-ev";
             const [id, pda] = await createNextRound(a, s);
@@ -1710,12 +2216,20 @@ describe("rollover-accounting", () => {
                     .createRound(
                         new anchor.BN(skipped),
                         commitHash("x", "y"),
-                        new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+                        new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+                        null,
+                        null,
+                        false,
+                        null
                     )
                     .accounts({
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: rPDA,
+                        vault: vaultPDA,
+                        jackpotPool: jackpotPoolPDA,
+                        season: null,
+                        roles: rolesPDA,
                         systemProgram: SystemProgram.programId,
                     })
                     .rpc();
@@ -1733,12 +2247,20 @@ describe("rollover-accounting", () => {
                     .createRound(
                         new anchor.BN(0),
                         commitHash("x", "y"),
-                        new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+                        new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+                        null,
+                        null,
+                        false,
+                        null
                     )
                     .accounts({
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: rPDA,
+                        vault: vaultPDA,
+                        jackpotPool: jackpotPoolPDA,
+                        season: null,
+                        roles: rolesPDA,
                         systemProgram: SystemProgram.programId,
                     })
                     .rpc();
@@ -1757,12 +2279,20 @@ describe("rollover-accounting", () => {
                     .createRound(
                         new anchor.BN(id),
                         commitHash("x", "y"),
-                        new anchor.BN(Math.floor(Date.now() / 1000) - 3600)
+                        new anchor.BN(Math.floor(Date.now() / 1000) - 3600),
+                        null,
+                        null,
+                        false,
+                        null
                     )
                     .accounts({
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: rPDA,
+                        vault: vaultPDA,
+                        jackpotPool: jackpotPoolPDA,
+                        season: null,
+                        roles: rolesPDA,
                         systemProgram: SystemProgram.programId,
                     })
                     .rpc();
@@ -1801,6 +2331,7 @@ describe("rollover-accounting", () => {
                     gameState: gameStatePDA,
                     round: closeRoundPDA,
                     deposit: dPDA,
+                    roles: rolesPDA,
                 })
                 .rpc();
 
@@ -1819,6 +2350,7 @@ describe("rollover-accounting", () => {
                     gameState: gameStatePDA,
                     round: closeRoundPDA,
                     deposit: dPDA,
+                    roles: rolesPDA,
                 })
                 .rpc();
 
@@ -1838,6 +2370,11 @@ describe("rollover-accounting", () => {
                     authority: authority.publicKey,
                     gameState: gameStatePDA,
                     round: closeRoundPDA,
+                    settlement: settlementPDA(closeRoundId)[0],
+                    rentPayer: authority.publicKey,
+                    archive: archivePDA,
+                    roles: rolesPDA,
+                    systemProgram: SystemProgram.programId,
                 })
                 .rpc();
 
@@ -1862,6 +2399,11 @@ describe("rollover-accounting", () => {
                         authority: authority.publicKey,
                         gameState: gameStatePDA,
                         round: pda,
+                        settlement: settlementPDA(id)[0],
+                        rentPayer: authority.publicKey,
+                        archive: archivePDA,
+                        roles: rolesPDA,
+                        systemProgram: SystemProgram.programId,
                     })
                     .rpc();
                 assert.fail("Should have thrown");
@@ -1889,6 +2431,7 @@ describe("rollover-accounting", () => {
                         gameState: gameStatePDA,
                         round: pda,
                         deposit: dPDA,
+                        roles: rolesPDA,
                     })
                     .rpc();
                 assert.fail("Should have thrown");
@@ -1919,6 +2462,7 @@ describe("rollover-accounting", () => {
                     gameState: gameStatePDA,
                     round: pda,
                     deposit: dPDA,
+                    roles: rolesPDA,
                 })
                 .rpc();
 
@@ -1959,7 +2503,7 @@ describe("rollover-accounting", () => {
                 a,
                 s,
                 player1.publicKey,
-                [evidencePool],
+                [10000],
                 [player2.publicKey]
             );
             await assertVaultConsistency("T077 full evidence settle");
@@ -2101,7 +2645,7 @@ describe("rollover-accounting", () => {
                 a,
                 s,
                 player2.publicKey,
-                [evidencePool],
+                [10000],
                 [player3.publicKey]
             );
 
@@ -2518,4 +3062,2790 @@ describe("rollover-accounting", () => {
             await assertVaultConsistency("final check T106");
         });
     });
+
+    // ═══════════════════════════════════════════════════
+    // 18. MULTIPLE WINNERS & TIE-SPLITTING
+    // ═══════════════════════════════════════════════════
+
+    describe("18. Multiple winners & tie-splitting", () => {
+        it("T107: Equal split between three winners, remainder to the first", async () => {
+            const a = "multi-equal";
+            const s = "multi-sequal";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL + 3);
+
+            const round = await program.account.round.fetch(pda);
+            const pool =
+                round.totalDeposits.toNumber() + round.rolloverIn.toNumber();
+            const winnerPool = Math.floor((pool * 5000) / 10000);
+            const share = Math.floor(winnerPool / 3);
+            const treas = Math.floor((pool * 500) / 10000);
+
+            const winners = [player1.publicKey, player2.publicKey, player3.publicKey];
+            const before = await Promise.all(winners.map((pk) => getBalance(pk)));
+
+            await settleWinners(pda, a, s, winners, { equalSplit: {} });
+
+            const paid = await Promise.all(
+                winners.map(async (pk, i) => (await getBalance(pk)) - before[i])
+            );
+            assert.deepEqual(paid, [share + (winnerPool % 3), share, share]);
+
+            // The whole winner pool is paid out; none of it rolls over
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            assert.equal(gs.rolloverBalance.toNumber(), pool - winnerPool - treas);
+            await assertVaultConsistency("after equal-split settle T107");
+        });
+
+        it("T108: LargestDeposit splits pro rata to the winners' deposits", async () => {
+            const a = "multi-weighted";
+            const s = "multi-sweighted";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player2, 3 * LAMPORTS_PER_SOL);
+            await deposit(id, pda, player3, LAMPORTS_PER_SOL);
+
+            const round = await program.account.round.fetch(pda);
+            const pool =
+                round.totalDeposits.toNumber() + round.rolloverIn.toNumber();
+            const winnerPool = Math.floor((pool * 5000) / 10000);
+
+            const before2 = await getBalance(player2.publicKey);
+            const before3 = await getBalance(player3.publicKey);
+
+            await settleWinners(
+                pda,
+                a,
+                s,
+                [player2.publicKey, player3.publicKey],
+                { largestDeposit: {} }
+            );
+
+            assert.equal(
+                (await getBalance(player2.publicKey)) - before2,
+                Math.floor((winnerPool * 3) / 4)
+            );
+            assert.equal(
+                (await getBalance(player3.publicKey)) - before3,
+                Math.floor(winnerPool / 4)
+            );
+            await assertVaultConsistency("after weighted settle T108");
+        });
+
+        it("T109: LargestDeposit rejects a winner without a deposit", async () => {
+            const a = "multi-nodeposit";
+            const s = "multi-snodeposit";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            try {
+                await settleWinners(
+                    pda,
+                    a,
+                    s,
+                    [player1.publicKey, player2.publicKey],
+                    { largestDeposit: {} }
+                );
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "WinnerDepositMismatch");
+            }
+
+            // Clean up
+            await expire(pda, a, s);
+        });
+
+        it("T110: EarliestGuess pays the winners whose Guess landed first", async () => {
+            const a = "multi-earliest";
+            const s = "multi-searliest";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+            const g2 = await recordGuess(id, pda, player2.publicKey, a);
+            const g3 = await recordGuess(id, pda, player3.publicKey, a);
+
+            const guess2 = await program.account.guess.fetch(g2);
+            const guess3 = await program.account.guess.fetch(g3);
+            assert.equal(guess2.player.toBase58(), player2.publicKey.toBase58());
+            assert.isAtMost(guess2.slot.toNumber(), guess3.slot.toNumber());
+
+            const round = await program.account.round.fetch(pda);
+            const pool =
+                round.totalDeposits.toNumber() + round.rolloverIn.toNumber();
+            const winnerPool = Math.floor((pool * 5000) / 10000);
+            // Both guesses can land in one slot, which splits the pool evenly
+            // with the remainder to the first winner passed (player3)
+            const half = Math.floor(winnerPool / 2);
+            const expected = guess2.slot.eq(guess3.slot)
+                ? [half, winnerPool - half]
+                : [winnerPool, 0];
+
+            const before2 = await getBalance(player2.publicKey);
+            const before3 = await getBalance(player3.publicKey);
+
+            await settleWinners(
+                pda,
+                a,
+                s,
+                [player3.publicKey, player2.publicKey],
+                { earliestGuess: {} }
+            );
+
+            assert.equal((await getBalance(player2.publicKey)) - before2, expected[0]);
+            assert.equal((await getBalance(player3.publicKey)) - before3, expected[1]);
+            await assertVaultConsistency("after earliest-guess settle T110");
+        });
+
+        it("T177: EarliestGuess rejects a Guess that is not the answer", async () => {
+            const a = "multi-wrongguess";
+            const s = "multi-swrongguess";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+            const gPDA = await recordGuess(id, pda, player2.publicKey, "something else");
+
+            try {
+                await settleWinners(pda, a, s, [player2.publicKey], { earliestGuess: {} });
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "WinnerGuessMismatch");
+            }
+
+            // Once the round has a Settlement, close_guess returns the rent
+            await expire(pda, a, s);
+            const rent = await getBalance(gPDA);
+            const authBefore = await getBalance(authority.publicKey);
+            await program.methods
+                .closeGuess()
+                .accounts({
+                    authority: authority.publicKey,
+                    gameState: gameStatePDA,
+                    roles: rolesPDA,
+                    round: pda,
+                    guess: gPDA,
+                })
+                .rpc();
+            assert.isNull(await program.account.guess.fetchNullable(gPDA));
+            assert.isAbove((await getBalance(authority.publicKey)) - authBefore, rent - 10000);
+        });
+
+        it("T111: Duplicate winner wallet rejected", async () => {
+            const a = "multi-dup";
+            const s = "multi-sdup";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            try {
+                await settleWinners(
+                    pda,
+                    a,
+                    s,
+                    [player2.publicKey, player2.publicKey],
+                    { equalSplit: {} }
+                );
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "DuplicateWinner");
+            }
+
+            // Clean up
+            await expire(pda, a, s);
+        });
+
+        it("T112: Settle with no winners rejected", async () => {
+            const a = "multi-none";
+            const s = "multi-snone";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            try {
+                await settleWinners(pda, a, s, [], { equalSplit: {} });
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "InvalidWinnerWeights");
+            }
+
+            // Clean up
+            await expire(pda, a, s);
+            await assertVaultConsistency("after multi-winner tests");
+        });
+    });
+
+    describe("19. Settlement records", () => {
+        it("T113: Settle writes a Settlement with the realised split", async () => {
+            const a = "record-settle";
+            const s = "record-ssettle";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            const round = await program.account.round.fetch(pda);
+            const pool =
+                round.totalDeposits.toNumber() + round.rolloverIn.toNumber();
+            const winnerPool = Math.floor((pool * 5000) / 10000);
+            const share = Math.floor(winnerPool / 2);
+            const treas = Math.floor((pool * 500) / 10000);
+
+            await settleWinners(
+                pda,
+                a,
+                s,
+                [player2.publicKey, player3.publicKey],
+                { equalSplit: {} }
+            );
+
+            const rec = await program.account.settlement.fetch(settlementPDA(id)[0]);
+            assert.equal(rec.roundId.toNumber(), id);
+            assert.deepEqual(rec.outcome, { settled: {} });
+            assert.deepEqual(rec.commitHash, round.commitHash);
+            assert.deepEqual(
+                Buffer.from(rec.answerHash),
+                createHash("sha256").update(a).digest()
+            );
+            assert.equal(rec.pool.toNumber(), pool);
+            assert.equal(rec.rolloverIn.toNumber(), round.rolloverIn.toNumber());
+            assert.equal(rec.rolloverOut.toNumber(), pool - winnerPool - treas);
+            assert.deepEqual(
+                rec.winners.map((w) => w.toBase58()),
+                [player2.publicKey.toBase58(), player3.publicKey.toBase58()]
+            );
+            assert.deepEqual(
+                rec.winnerAmounts.map((x) => x.toNumber()),
+                [winnerPool - share, share]
+            );
+            assert.equal(rec.evidenceTotal.toNumber(), 0);
+            assert.equal(rec.treasuryAmount.toNumber(), treas);
+            assert.equal(rec.buybackAmount.toNumber(), 0);
+            assert.isAbove(rec.finalizedAt.toNumber(), 0);
+        });
+
+        it("T114: Expire writes a Settlement with no winners", async () => {
+            const a = "record-expire";
+            const s = "record-sexpire";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            const round = await program.account.round.fetch(pda);
+            const deposits = round.totalDeposits.toNumber();
+            const rolloverIn = round.rolloverIn.toNumber();
+            const pool = deposits + rolloverIn;
+            const treas = Math.floor((deposits * 500) / 10000);
+            const buyback = Math.floor((deposits * 4750) / 10000);
+
+            await expire(pda, a, s);
+
+            const rec = await program.account.settlement.fetch(settlementPDA(id)[0]);
+            assert.deepEqual(rec.outcome, { expired: {} });
+            assert.equal(rec.pool.toNumber(), pool);
+            assert.lengthOf(rec.winners, 0);
+            assert.lengthOf(rec.winnerAmounts, 0);
+            assert.equal(rec.treasuryAmount.toNumber(), treas);
+            assert.equal(rec.buybackAmount.toNumber(), buyback);
+            assert.equal(
+                rec.rolloverOut.toNumber(),
+                rolloverIn + deposits - treas - buyback
+            );
+        });
+
+        it("T115: close_round archives the Settlement and closes it with the Round", async () => {
+            const a = "record-close";
+            const s = "record-sclose";
+            const [id, pda] = await createNextRound(a, s);
+            await settle(pda, a, s, player1.publicKey, [], []);
+
+            const [sPDA] = settlementPDA(id);
+            const rec = await program.account.settlement.fetch(sPDA);
+            const archiveBefore = await program.account.roundArchive.fetchNullable(archivePDA);
+            const countBefore = archiveBefore ? archiveBefore.leafCount.toNumber() : 0;
+            const rent = (await getBalance(pda)) + (await getBalance(sPDA));
+            const authBefore = await getBalance(authority.publicKey);
+
+            await program.methods
+                .closeRound()
+                .accounts({
+                    authority: authority.publicKey,
+                    gameState: gameStatePDA,
+                    round: pda,
+                    settlement: sPDA,
+                    rentPayer: authority.publicKey,
+                    archive: archivePDA,
+                    roles: rolesPDA,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
+            const archive = await program.account.roundArchive.fetch(archivePDA);
+            assert.equal(archive.leafCount.toNumber(), countBefore + 1);
+            assert.notDeepEqual(archive.root, Array(32).fill(0));
+
+            // The archive leaf keeps the record; both accounts' rent comes back
+            assert.isNull(await program.account.round.fetchNullable(pda));
+            assert.isNull(await program.account.settlement.fetchNullable(sPDA));
+            assert.isAbove((await getBalance(authority.publicKey)) - authBefore, rent - 10000);
+            assert.deepEqual(rec.outcome, { settled: {} });
+            await assertVaultConsistency("after settlement record tests");
+        });
+
+        it("T116: Archive root commits to the closed round's summary", async () => {
+            const a = "record-archive";
+            const s = "record-sarchive";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player2, LAMPORTS_PER_SOL);
+            await expire(pda, a, s);
+
+            const [sPDA] = settlementPDA(id);
+            const rec = await program.account.settlement.fetch(sPDA);
+            const before = await program.account.roundArchive.fetch(archivePDA);
+            const expectedRoot = archiveRootAfter(
+                before.frontier,
+                before.leafCount.toNumber(),
+                archiveLeaf(rec)
+            );
+
+            await program.methods
+                .closeRound()
+                .accounts({
+                    authority: authority.publicKey,
+                    gameState: gameStatePDA,
+                    round: pda,
+                    settlement: sPDA,
+                    rentPayer: authority.publicKey,
+                    archive: archivePDA,
+                    roles: rolesPDA,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+
+            const after = await program.account.roundArchive.fetch(archivePDA);
+            assert.equal(after.leafCount.toNumber(), before.leafCount.toNumber() + 1);
+            assert.deepEqual(Buffer.from(after.root), expectedRoot);
+        });
+    });
+
+    describe("20. Soft close", () => {
+        function softClose(windowSecs: number, extensionSecs: number, maxEndsAt: number) {
+            return {
+                windowSecs,
+                extensionSecs,
+                maxEndsAt: new anchor.BN(maxEndsAt),
+            };
+        }
+
+        it("T117: Round without a soft-close rule keeps a fixed end", async () => {
+            const a = "soft-none";
+            const s = "soft-snone";
+            const [id, pda] = await createNextRound(a, s, 30);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            const round = await program.account.round.fetch(pda);
+            assert.equal(round.endsAt.toNumber(), round.originalEndsAt.toNumber());
+            assert.equal(round.softClose.windowSecs, 0);
+
+            await expire(pda, a, s);
+        });
+
+        it("T118: Deposit inside the window extends ends_at", async () => {
+            const a = "soft-extend";
+            const s = "soft-sextend";
+            const endsAt = Math.floor(Date.now() / 1000) + 30;
+            const [id, pda] = await createNextRound(
+                a,
+                s,
+                30,
+                softClose(120, 20, endsAt + 600)
+            );
+            const before = await program.account.round.fetch(pda);
+
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            const round = await program.account.round.fetch(pda);
+            assert.equal(round.originalEndsAt.toNumber(), before.endsAt.toNumber());
+            assert.equal(round.endsAt.toNumber(), before.endsAt.toNumber() + 20);
+
+            await expire(pda, a, s);
+        });
+
+        it("T119: Extensions stop at the hard cap", async () => {
+            const a = "soft-cap";
+            const s = "soft-scap";
+            const endsAt = Math.floor(Date.now() / 1000) + 30;
+            const [id, pda] = await createNextRound(
+                a,
+                s,
+                30,
+                softClose(120, 50, endsAt + 30)
+            );
+            const before = await program.account.round.fetch(pda);
+            const cap = before.softClose.maxEndsAt.toNumber();
+
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+            assert.equal((await program.account.round.fetch(pda)).endsAt.toNumber(), cap);
+
+            await deposit(id, pda, player2, LAMPORTS_PER_SOL);
+            assert.equal((await program.account.round.fetch(pda)).endsAt.toNumber(), cap);
+
+            await expire(pda, a, s);
+        });
+
+        it("T120: Deposit outside the window does not extend", async () => {
+            const a = "soft-early";
+            const s = "soft-searly";
+            const endsAt = Math.floor(Date.now() / 1000) + 3600;
+            const [id, pda] = await createNextRound(
+                a,
+                s,
+                3600,
+                softClose(60, 20, endsAt + 600)
+            );
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            const round = await program.account.round.fetch(pda);
+            assert.equal(round.endsAt.toNumber(), round.originalEndsAt.toNumber());
+
+            await expire(pda, a, s);
+        });
+
+        it("T121: Cap before ends_at rejected", async () => {
+            const endsAt = Math.floor(Date.now() / 1000) + 3600;
+            try {
+                await createNextRound("soft-bad", "soft-sbad", 3600, softClose(60, 20, endsAt - 1));
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "InvalidSoftClose");
+            }
+            // The failed create did not consume the id
+            nextRoundId--;
+            await assertVaultConsistency("after soft-close tests");
+        });
+    });
+
+    describe("21. Minimum pool & refunds", () => {
+        async function expectError(fn: () => Promise<any>, code: string) {
+            try {
+                await fn();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), code);
+            }
+        }
+
+        const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+
+        async function failRound(id: number, pda: PublicKey, caller: Keypair) {
+            await program.methods
+                .failRound()
+                .accounts({
+                    caller: caller.publicKey,
+                    gameState: gameStatePDA,
+                    round: pda,
+                    settlement: settlementPDA(id)[0],
+                    season: null,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([caller])
+                .rpc();
+        }
+
+        async function claimRefund(id: number, pda: PublicKey, player: Keypair) {
+            await program.methods
+                .claimRefund()
+                .accounts({
+                    player: player.publicKey,
+                    round: pda,
+                    deposit: depositPDA(id, player.publicKey)[0],
+                    vault: vaultPDA,
+                    playerProfile: playerProfilePDA(player.publicKey)[0],
+                })
+                .signers([player])
+                .rpc();
+        }
+
+        async function closeRound(id: number, pda: PublicKey, rentPayer: PublicKey) {
+            await program.methods
+                .closeRound()
+                .accounts({
+                    authority: authority.publicKey,
+                    gameState: gameStatePDA,
+                    round: pda,
+                    settlement: settlementPDA(id)[0],
+                    rentPayer,
+                    archive: archivePDA,
+                    roles: rolesPDA,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+        }
+
+        it("T122: fail_round before ends_at rejected", async () => {
+            const a = "minpool-early";
+            const s = "minpool-searly";
+            const [id, pda] = await createNextRound(a, s, 3600, null, 5 * LAMPORTS_PER_SOL);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            try {
+                await failRound(id, pda, player3);
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "RoundNotEnded");
+            }
+
+            // Topped up to its min_pool, the round expires as usual
+            await deposit(id, pda, player2, 4 * LAMPORTS_PER_SOL);
+            await expire(pda, a, s);
+        });
+
+        it("T123: fail_round rejected once min_pool is reached", async () => {
+            const a = "minpool-met";
+            const s = "minpool-smet";
+            const [id, pda] = await createNextRound(a, s, 2, null, LAMPORTS_PER_SOL);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+            await sleep(3000);
+
+            try {
+                await failRound(id, pda, player3);
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "MinPoolReached");
+            }
+
+            await expire(pda, a, s);
+        });
+
+        it("T124: fail_round rejected without a min_pool", async () => {
+            const a = "minpool-none";
+            const s = "minpool-snone";
+            const [id, pda] = await createNextRound(a, s, 2);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+            await sleep(3000);
+
+            try {
+                await failRound(id, pda, player3);
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "MinPoolReached");
+            }
+
+            await expire(pda, a, s);
+        });
+
+        it("T125: Failed round refunds deposits in full and preserves rollover", async () => {
+            const a = "minpool-fail";
+            const s = "minpool-sfail";
+            const [id, pda] = await createNextRound(a, s, 2, null, 5 * LAMPORTS_PER_SOL);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+            await deposit(id, pda, player2, 0.5 * LAMPORTS_PER_SOL);
+            await sleep(3000);
+
+            const rolloverBefore = (
+                await program.account.gameState.fetch(gameStatePDA)
+            ).rolloverBalance.toNumber();
+
+            // Anyone can fail the round
+            const caller = Keypair.generate();
+            await airdrop(caller.publicKey, 1);
+            await failRound(id, pda, caller);
+
+            const round = await program.account.round.fetch(pda);
+            assert.deepEqual(round.status, { refunding: {} });
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            assert.equal(gs.rolloverBalance.toNumber(), rolloverBefore);
+
+            const rec = await program.account.settlement.fetch(settlementPDA(id)[0]);
+            assert.deepEqual(rec.outcome, { refunding: {} });
+            assert.equal(rec.treasuryAmount.toNumber(), 0);
+            assert.equal(rec.buybackAmount.toNumber(), 0);
+
+            // Settle and expire no longer apply
+            try {
+                await expire(pda, a, s);
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "RoundNotActive");
+            }
+
+            // Deposit PDAs cannot be swept by the authority before refunds
+            try {
+                await program.methods
+                    .closeDeposit()
+                    .accounts({
+                        authority: authority.publicKey,
+                        gameState: gameStatePDA,
+                        round: pda,
+                        deposit: depositPDA(id, player1.publicKey)[0],
+                        roles: rolesPDA,
+                    })
+                    .rpc();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "RefundsOutstanding");
+            }
+
+            const [d1] = depositPDA(id, player1.publicKey);
+            const depositRent = await getBalance(d1);
+            const before1 = await getBalance(player1.publicKey);
+            await claimRefund(id, pda, player1);
+            assert.equal(
+                (await getBalance(player1.publicKey)) - before1,
+                LAMPORTS_PER_SOL + depositRent - 5000
+            );
+
+            // Round cannot be closed while player2 is still owed
+            try {
+                await closeRound(id, pda, caller.publicKey);
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "RefundsOutstanding");
+            }
+
+            await claimRefund(id, pda, player2);
+            assert.equal(
+                (await program.account.round.fetch(pda)).refunded.toNumber(),
+                1.5 * LAMPORTS_PER_SOL
+            );
+
+            // The Settlement rent goes back to the caller who paid it, and the
+            // closer keeps the whole Round rent
+            await expectError(() => closeRound(id, pda, authority.publicKey), "RentPayerMismatch");
+            const settlementRent = await getBalance(settlementPDA(id)[0]);
+            const roundRent = await getBalance(pda);
+            const callerBefore = await getBalance(caller.publicKey);
+            const closerBefore = await getBalance(authority.publicKey);
+            await closeRound(id, pda, caller.publicKey);
+            assert.equal((await getBalance(caller.publicKey)) - callerBefore, settlementRent);
+            assert.equal((await getBalance(authority.publicKey)) - closerBefore, roundRent - 5000);
+            assert.isNull(await program.account.settlement.fetchNullable(settlementPDA(id)[0]));
+            await assertVaultConsistency("after refunds T125");
+        });
+
+        it("T126: Refund cannot be claimed twice or from a live round", async () => {
+            const a = "minpool-twice";
+            const s = "minpool-stwice";
+            const [id, pda] = await createNextRound(a, s, 2, null, 5 * LAMPORTS_PER_SOL);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            try {
+                await claimRefund(id, pda, player1);
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "RoundNotRefunding");
+            }
+
+            await sleep(3000);
+            await failRound(id, pda, player3);
+            await claimRefund(id, pda, player1);
+
+            try {
+                await claimRefund(id, pda, player1);
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "AccountNotInitialized");
+            }
+            await assertVaultConsistency("after minimum pool tests");
+        });
+
+        it("T174: A round under its min_pool can only be failed and refunded", async () => {
+            const a = "minpool-only-fail";
+            const s = "minpool-sonly-fail";
+            const [id, pda] = await createNextRound(a, s, 2, null, 5 * LAMPORTS_PER_SOL);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            // Before ends_at: neither payout path applies
+            await expectError(() => expire(pda, a, s), "BelowMinPool");
+            await expectError(
+                () => settleWinners(pda, a, s, [player1.publicKey], { equalSplit: {} }),
+                "BelowMinPool"
+            );
+
+            await sleep(3000);
+
+            // After ends_at: the pool is fixed, and the round still cannot pay out
+            await expectError(
+                () => deposit(id, pda, player2, 4 * LAMPORTS_PER_SOL),
+                "RoundEnded"
+            );
+            await expectError(() => expire(pda, a, s), "BelowMinPool");
+            await expectError(
+                () => settleWinners(pda, a, s, [player1.publicKey], { equalSplit: {} }),
+                "BelowMinPool"
+            );
+            await expectError(() => emergencyExpire(pda, player3), "BelowMinPool");
+
+            const rolloverBefore = (
+                await program.account.gameState.fetch(gameStatePDA)
+            ).rolloverBalance.toNumber();
+            await failRound(id, pda, player3);
+            assert.deepEqual((await program.account.round.fetch(pda)).status, { refunding: {} });
+            assert.equal(
+                (await program.account.gameState.fetch(gameStatePDA)).rolloverBalance.toNumber(),
+                rolloverBefore
+            );
+
+            const before = await getBalance(player1.publicKey);
+            const depositRent = await getBalance(depositPDA(id, player1.publicKey)[0]);
+            await claimRefund(id, pda, player1);
+            assert.equal(
+                (await getBalance(player1.publicKey)) - before,
+                LAMPORTS_PER_SOL + depositRent - 5000
+            );
+            await assertVaultConsistency("after min_pool-only refund T174");
+        });
+    });
+
+    describe("22. Rollover cap & jackpot pool", () => {
+        async function setCap(cap: number, policy: any, signer: Keypair = authority) {
+            await applyChange(
+                { rolloverCap: { rolloverCap: new anchor.BN(cap), overflowPolicy: policy } },
+                signer
+            );
+        }
+
+        after(async () => {
+            await setCap(0, { jackpot: {} });
+        });
+
+        it("T127: Non-authority cannot set the rollover cap", async () => {
+            const attacker = Keypair.generate();
+            await airdrop(attacker.publicKey, 1);
+            try {
+                await setCap(1, { jackpot: {} }, attacker);
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "Unauthorized");
+            }
+        });
+
+        it("T128: Rollover above the cap moves to the jackpot pool", async () => {
+            const rolloverBefore = (
+                await program.account.gameState.fetch(gameStatePDA)
+            ).rolloverBalance.toNumber();
+            await setCap(rolloverBefore, { jackpot: {} });
+
+            const a = "cap-jackpot";
+            const s = "cap-sjackpot";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, 2 * LAMPORTS_PER_SOL);
+
+            const round = await program.account.round.fetch(pda);
+            const deposits = round.totalDeposits.toNumber();
+            const added =
+                deposits -
+                Math.floor((deposits * 4750) / 10000) -
+                Math.floor((deposits * 500) / 10000);
+            const poolBefore = await program.account.jackpotPool.fetch(jackpotPoolPDA);
+            const poolLamportsBefore = await getBalance(jackpotPoolPDA);
+
+            await expire(pda, a, s);
+
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            assert.equal(gs.rolloverBalance.toNumber(), rolloverBefore);
+
+            const pool = await program.account.jackpotPool.fetch(jackpotPoolPDA);
+            assert.equal(pool.balance.toNumber() - poolBefore.balance.toNumber(), added);
+            assert.equal((await getBalance(jackpotPoolPDA)) - poolLamportsBefore, added);
+
+            const rec = await program.account.settlement.fetch(settlementPDA(id)[0]);
+            assert.equal(rec.jackpotAmount.toNumber(), added);
+            assert.equal(rec.rolloverOut.toNumber(), rolloverBefore);
+            await assertVaultConsistency("after jackpot overflow T128");
+        });
+
+        it("T129: Buyback policy sends the overflow to the buyback wallet", async () => {
+            const rolloverBefore = (
+                await program.account.gameState.fetch(gameStatePDA)
+            ).rolloverBalance.toNumber();
+            await setCap(rolloverBefore, { buyback: {} });
+
+            const a = "cap-buyback";
+            const s = "cap-sbuyback";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, 2 * LAMPORTS_PER_SOL);
+
+            const round = await program.account.round.fetch(pda);
+            const deposits = round.totalDeposits.toNumber();
+            const buyback = Math.floor((deposits * 4750) / 10000);
+            const added = deposits - buyback - Math.floor((deposits * 500) / 10000);
+            const bbBefore = await getBalance(buybackPk);
+            const poolBefore = await program.account.jackpotPool.fetch(jackpotPoolPDA);
+
+            await expire(pda, a, s);
+
+            assert.equal((await getBalance(buybackPk)) - bbBefore, buyback + added);
+            const pool = await program.account.jackpotPool.fetch(jackpotPoolPDA);
+            assert.equal(pool.balance.toNumber(), poolBefore.balance.toNumber());
+
+            const rec = await program.account.settlement.fetch(settlementPDA(id)[0]);
+            assert.equal(rec.buybackAmount.toNumber(), buyback + added);
+            assert.equal(rec.jackpotAmount.toNumber(), 0);
+            await assertVaultConsistency("after buyback overflow T129");
+        });
+
+        it("T130: Jackpot round pulls the whole jackpot pool into rollover_in", async () => {
+            await setCap(0, { jackpot: {} });
+
+            const gsBefore = await program.account.gameState.fetch(gameStatePDA);
+            const jackpot = (
+                await program.account.jackpotPool.fetch(jackpotPoolPDA)
+            ).balance.toNumber();
+            assert.isAbove(jackpot, 0);
+
+            const a = "cap-release";
+            const s = "cap-srelease";
+            const [id, pda] = await createNextRound(a, s, 3600, null, null, true);
+
+            const round = await program.account.round.fetch(pda);
+            assert.equal(round.jackpotIn.toNumber(), jackpot);
+            assert.equal(
+                round.rolloverIn.toNumber(),
+                gsBefore.rolloverBalance.toNumber() + jackpot
+            );
+            const pool = await program.account.jackpotPool.fetch(jackpotPoolPDA);
+            assert.equal(pool.balance.toNumber(), 0);
+            await assertVaultConsistency("after jackpot release T130");
+
+            // The released jackpot pays out like any rollover
+            await settle(pda, a, s, player2.publicKey, [], []);
+            await assertVaultConsistency("after jackpot round settle T130");
+        });
+    });
+
+    describe("23. Player limits", () => {
+        const a = "limits-round";
+        const s = "limits-sround";
+        let id: number;
+        let pda: PublicKey;
+
+        async function setLimits(
+            player: Keypair,
+            daily: number,
+            weekly: number,
+            perRound: number,
+            excludedUntil = 0
+        ) {
+            await program.methods
+                .setPlayerLimits(
+                    new anchor.BN(daily),
+                    new anchor.BN(weekly),
+                    new anchor.BN(perRound),
+                    new anchor.BN(excludedUntil)
+                )
+                .accounts({
+                    player: player.publicKey,
+                    playerLimits: playerLimitsPDA(player.publicKey)[0],
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([player])
+                .rpc();
+        }
+
+        async function newPlayer(): Promise<Keypair> {
+            const player = Keypair.generate();
+            await airdrop(player.publicKey, 10);
+            return player;
+        }
+
+        async function expectDepositError(player: Keypair, lamports: number, code: string) {
+            try {
+                await deposit(id, pda, player, lamports);
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), code);
+            }
+        }
+
+        before(async () => {
+            [id, pda] = await createNextRound(a, s);
+        });
+
+        after(async () => {
+            await expire(pda, a, s);
+            await assertVaultConsistency("after player limits round");
+        });
+
+        it("T131: Daily cap counts every deposit in the rolling window", async () => {
+            const player = await newPlayer();
+            await setLimits(player, LAMPORTS_PER_SOL, 0, 0);
+
+            await deposit(id, pda, player, 0.6 * LAMPORTS_PER_SOL);
+            await expectDepositError(player, 0.5 * LAMPORTS_PER_SOL, "DailyLimitExceeded");
+            await deposit(id, pda, player, 0.4 * LAMPORTS_PER_SOL);
+            await expectDepositError(player, 1, "DailyLimitExceeded");
+
+            const limits = await program.account.playerLimits.fetch(
+                playerLimitsPDA(player.publicKey)[0]
+            );
+            const windowTotal = limits.hourBuckets.reduce((t, b) => t + b.toNumber(), 0);
+            assert.equal(windowTotal, LAMPORTS_PER_SOL);
+        });
+
+        it("T132: Weekly and per-round caps are enforced independently", async () => {
+            const weekly = await newPlayer();
+            await setLimits(weekly, 0, LAMPORTS_PER_SOL, 0);
+            await deposit(id, pda, weekly, LAMPORTS_PER_SOL);
+            await expectDepositError(weekly, 1, "WeeklyLimitExceeded");
+
+            const perRound = await newPlayer();
+            await setLimits(perRound, 0, 0, LAMPORTS_PER_SOL);
+            await deposit(id, pda, perRound, 0.7 * LAMPORTS_PER_SOL);
+            await expectDepositError(perRound, 0.4 * LAMPORTS_PER_SOL, "RoundLimitExceeded");
+            await deposit(id, pda, perRound, 0.3 * LAMPORTS_PER_SOL);
+        });
+
+        it("T133: Loosening waits out the cooldown, tightening applies at once", async () => {
+            const player = await newPlayer();
+            const limitsPDA = playerLimitsPDA(player.publicKey)[0];
+            await setLimits(player, LAMPORTS_PER_SOL, 0, 0);
+
+            const before = Math.floor(Date.now() / 1000);
+            await setLimits(player, 5 * LAMPORTS_PER_SOL, 0, 0);
+            let limits = await program.account.playerLimits.fetch(limitsPDA);
+            assert.equal(limits.dailyCap.toNumber(), LAMPORTS_PER_SOL);
+            assert.equal(limits.pending.dailyCap.toNumber(), 5 * LAMPORTS_PER_SOL);
+            assert.approximately(limits.pending.effectiveAt.toNumber(), before + 86400, 60);
+            await expectDepositError(player, 1.5 * LAMPORTS_PER_SOL, "DailyLimitExceeded");
+
+            // Lifting a cap entirely (0) is a loosening too
+            await setLimits(player, 0, 0, 0);
+            limits = await program.account.playerLimits.fetch(limitsPDA);
+            assert.equal(limits.dailyCap.toNumber(), LAMPORTS_PER_SOL);
+            assert.equal(limits.pending.dailyCap.toNumber(), 0);
+            assert.isAbove(limits.pending.effectiveAt.toNumber(), 0);
+
+            await setLimits(player, 0.5 * LAMPORTS_PER_SOL, 0, 0);
+            limits = await program.account.playerLimits.fetch(limitsPDA);
+            assert.equal(limits.dailyCap.toNumber(), 0.5 * LAMPORTS_PER_SOL);
+            assert.equal(limits.pending.effectiveAt.toNumber(), 0);
+            await expectDepositError(player, 0.6 * LAMPORTS_PER_SOL, "DailyLimitExceeded");
+        });
+
+        it("T134: Self-exclusion blocks deposits and cannot be shortened", async () => {
+            const player = await newPlayer();
+            const now = Math.floor(Date.now() / 1000);
+            await setLimits(player, 0, 0, 0, now + 3600);
+            await expectDepositError(player, LAMPORTS_PER_SOL, "SelfExcluded");
+
+            try {
+                await setLimits(player, 0, 0, 0, now + 60);
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), "SelfExclusionLocked");
+            }
+
+            await setLimits(player, 0, 0, 0, now + 7200);
+            const limits = await program.account.playerLimits.fetch(
+                playerLimitsPDA(player.publicKey)[0]
+            );
+            assert.equal(limits.excludedUntil.toNumber(), now + 7200);
+        });
+
+        it("T135: Wallets without limits deposit as before", async () => {
+            const player = await newPlayer();
+            await deposit(id, pda, player, 3 * LAMPORTS_PER_SOL);
+            const info = await provider.connection.getAccountInfo(
+                playerLimitsPDA(player.publicKey)[0]
+            );
+            assert.isNull(info);
+        });
+    });
+
+    describe("24. Deposit policy", () => {
+        function policy(minDeposit: number, maxDeposit: number, maxPerPlayer: number, maxPool: number) {
+            return {
+                minDeposit: new anchor.BN(minDeposit),
+                maxDeposit: new anchor.BN(maxDeposit),
+                maxPerPlayer: new anchor.BN(maxPerPlayer),
+                maxPool: new anchor.BN(maxPool),
+            };
+        }
+
+        async function expectDepositError(
+            id: number,
+            pda: PublicKey,
+            player: Keypair,
+            lamports: number,
+            code: string
+        ) {
+            try {
+                await deposit(id, pda, player, lamports);
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), code);
+            }
+        }
+
+        it("T136: Zero-lamport deposit rejected without a policy", async () => {
+            const a = "policy-zero";
+            const s = "policy-szero";
+            const [id, pda] = await createNextRound(a, s);
+            await expectDepositError(id, pda, player1, 0, "DepositTooSmall");
+
+            const info = await provider.connection.getAccountInfo(
+                depositPDA(id, player1.publicKey)[0]
+            );
+            assert.isNull(info);
+            await expire(pda, a, s);
+        });
+
+        it("T137: Single-deposit bounds map to distinct errors", async () => {
+            const a = "policy-single";
+            const s = "policy-ssingle";
+            const [id, pda] = await createNextRound(
+                a, s, 3600, null, null, false,
+                policy(0.1 * LAMPORTS_PER_SOL, LAMPORTS_PER_SOL, 0, 0)
+            );
+            const round = await program.account.round.fetch(pda);
+            assert.equal(round.depositPolicy.maxDeposit.toNumber(), LAMPORTS_PER_SOL);
+
+            await expectDepositError(id, pda, player1, 0.1 * LAMPORTS_PER_SOL - 1, "DepositTooSmall");
+            await expectDepositError(id, pda, player1, LAMPORTS_PER_SOL + 1, "DepositTooLarge");
+            await deposit(id, pda, player1, 0.1 * LAMPORTS_PER_SOL);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+            await expire(pda, a, s);
+        });
+
+        it("T138: Per-player and pool maximums count cumulative deposits", async () => {
+            const a = "policy-caps";
+            const s = "policy-scaps";
+            const [id, pda] = await createNextRound(
+                a, s, 3600, null, null, false,
+                policy(0, 0, 1.5 * LAMPORTS_PER_SOL, 2 * LAMPORTS_PER_SOL)
+            );
+
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+            await expectDepositError(id, pda, player1, 0.6 * LAMPORTS_PER_SOL, "PlayerCapExceeded");
+            await deposit(id, pda, player1, 0.5 * LAMPORTS_PER_SOL);
+            await expectDepositError(id, pda, player2, 0.6 * LAMPORTS_PER_SOL, "PoolCapExceeded");
+            await deposit(id, pda, player2, 0.5 * LAMPORTS_PER_SOL);
+
+            const round = await program.account.round.fetch(pda);
+            assert.equal(round.totalDeposits.toNumber(), 2 * LAMPORTS_PER_SOL);
+            await expire(pda, a, s);
+        });
+
+        it("T139: Inconsistent policy rejected at create_round", async () => {
+            const bad = [
+                [policy(LAMPORTS_PER_SOL, LAMPORTS_PER_SOL - 1, 0, 0), null],
+                [policy(LAMPORTS_PER_SOL, 0, LAMPORTS_PER_SOL - 1, 0), null],
+                [policy(0, 0, 0, LAMPORTS_PER_SOL), 2 * LAMPORTS_PER_SOL],
+            ];
+            for (const [p, minPool] of bad) {
+                try {
+                    await createNextRound("policy-bad", "policy-sbad", 3600, null, minPool, false, p);
+                    assert.fail("Should have thrown");
+                } catch (err) {
+                    assert.include(err.toString(), "InvalidDepositPolicy");
+                }
+                // The failed create did not consume the id
+                nextRoundId--;
+            }
+            await assertVaultConsistency("after deposit policy tests");
+        });
+    });
+
+    describe("25. Emergency pause", () => {
+        const PAUSE_DEPOSITS = 1;
+        const PAUSE_ROUND_CREATION = 2;
+        const PAUSE_SETTLEMENT = 4;
+        const PAUSE_ALL = PAUSE_DEPOSITS | PAUSE_ROUND_CREATION | PAUSE_SETTLEMENT;
+        const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+
+        async function setPaused(paused: number, guardian: Keypair = authority) {
+            await program.methods
+                .setPaused(paused)
+                .accounts({
+                    guardian: guardian.publicKey,
+                    gameState: gameStatePDA,
+                })
+                .signers([guardian])
+                .rpc();
+        }
+
+        async function setGuardian(guardian: PublicKey) {
+            await applyChange({ guardian: [guardian] });
+        }
+
+        async function expectError(fn: () => Promise<any>, code: string) {
+            try {
+                await fn();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), code);
+            }
+        }
+
+        // initialize requires a separate guardian; these tests pause as the authority
+        before(async () => {
+            await setGuardian(authority.publicKey);
+        });
+
+        after(async () => {
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            if (!gs.guardian.equals(authority.publicKey)) {
+                await setGuardian(authority.publicKey);
+            }
+            await setPaused(0);
+        });
+
+        it("T140: Only the guardian can pause, and only with known flags", async () => {
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            assert.ok(gs.guardian.equals(authority.publicKey));
+            assert.equal(gs.paused, 0);
+
+            await expectError(() => setPaused(PAUSE_DEPOSITS, player1), "Unauthorized");
+            await expectError(() => setPaused(8), "InvalidPauseFlags");
+        });
+
+        it("T141: Paused deposits and round creation are refused until resumed", async () => {
+            const a = "pause-deposits";
+            const s = "pause-sdeposits";
+            const [id, pda] = await createNextRound(a, s);
+
+            await setPaused(PAUSE_DEPOSITS | PAUSE_ROUND_CREATION);
+            await expectError(
+                () => deposit(id, pda, player1, LAMPORTS_PER_SOL),
+                "DepositsPaused"
+            );
+            await expectError(() => createNextRound("pause-new", "pause-snew"), "RoundCreationPaused");
+            // The failed create did not consume the id
+            nextRoundId--;
+
+            await setPaused(0);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+            await expire(pda, a, s);
+            await assertVaultConsistency("after deposit pause T141");
+        });
+
+        it("T142: Settlement pause blocks settle and expire but not refunds", async () => {
+            const a = "pause-settle";
+            const s = "pause-ssettle";
+            const [id, pda] = await createNextRound(a, s, 2, null, 5 * LAMPORTS_PER_SOL);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            await setPaused(PAUSE_ALL);
+            await expectError(
+                () => settle(pda, a, s, player1.publicKey, [], []),
+                "SettlementPaused"
+            );
+            await expectError(() => expire(pda, a, s), "SettlementPaused");
+
+            await sleep(3000);
+            await program.methods
+                .failRound()
+                .accounts({
+                    caller: player3.publicKey,
+                    gameState: gameStatePDA,
+                    round: pda,
+                    settlement: settlementPDA(id)[0],
+                    season: null,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([player3])
+                .rpc();
+
+            const before = await getBalance(player1.publicKey);
+            await program.methods
+                .claimRefund()
+                .accounts({
+                    player: player1.publicKey,
+                    round: pda,
+                    deposit: depositPDA(id, player1.publicKey)[0],
+                    vault: vaultPDA,
+                    playerProfile: playerProfilePDA(player1.publicKey)[0],
+                })
+                .signers([player1])
+                .rpc();
+            assert.isAbove((await getBalance(player1.publicKey)) - before, LAMPORTS_PER_SOL - 10_000);
+
+            await setPaused(0);
+            await assertVaultConsistency("after settlement pause T142");
+        });
+
+        it("T143: Handing over the guardian role revokes the authority's pause", async () => {
+            const guardian = Keypair.generate();
+            await airdrop(guardian.publicKey, 1);
+            await setGuardian(guardian.publicKey);
+
+            await expectError(() => setPaused(PAUSE_SETTLEMENT), "Unauthorized");
+            await setPaused(PAUSE_SETTLEMENT, guardian);
+            assert.equal((await program.account.gameState.fetch(gameStatePDA)).paused, PAUSE_SETTLEMENT);
+            await setPaused(0, guardian);
+
+            await expectError(() => queueChange({ guardian: [PublicKey.default] }), "InvalidGuardian");
+        });
+    });
+
+    describe("26. Role-based access", () => {
+        const ROLE_ROUND_CREATOR = 1;
+        const ROLE_SETTLER = 2;
+        const ROLE_CLOSER = 4;
+        const ROLE_CONFIG_ADMIN = 8;
+        const ROLE_BUYBACK = 16;
+        const ROLE_ALL = ROLE_ROUND_CREATOR | ROLE_SETTLER | ROLE_CLOSER | ROLE_CONFIG_ADMIN | ROLE_BUYBACK;
+        const settler = Keypair.generate();
+
+        async function setRole(member: PublicKey, capabilities: number, admin: Keypair = authority) {
+            await program.methods
+                .setRole(member, capabilities)
+                .accounts({
+                    admin: admin.publicKey,
+                    roles: rolesPDA,
+                })
+                .signers([admin])
+                .rpc();
+        }
+
+        /** Grants wait out the timelock, which is 0 outside section 27 */
+        async function grantRole(member: PublicKey, capabilities: number) {
+            await applyChange({ role: { member, capabilities } });
+        }
+
+        async function capabilitiesOf(member: PublicKey): Promise<number> {
+            const roles = await program.account.roles.fetch(rolesPDA);
+            const entry = roles.members.find((m) => m.key.equals(member));
+            return entry ? entry.capabilities : 0;
+        }
+
+        async function expectError(fn: () => Promise<any>, code: string) {
+            try {
+                await fn();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), code);
+            }
+        }
+
+        before(async () => {
+            await airdrop(settler.publicKey, 1);
+        });
+
+        after(async () => {
+            if ((await capabilitiesOf(settler.publicKey)) !== 0) {
+                await setRole(settler.publicKey, 0);
+            }
+        });
+
+        it("T144: The deployer holds every capability after initialize", async () => {
+            assert.equal(await capabilitiesOf(authority.publicKey), ROLE_ALL);
+        });
+
+        it("T145: A settler-only key can expire but not create rounds", async () => {
+            await grantRole(settler.publicKey, ROLE_SETTLER);
+            assert.equal(await capabilitiesOf(settler.publicKey), ROLE_SETTLER);
+
+            const a = "roles-settler";
+            const s = "roles-ssettler";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            await expectError(
+                () =>
+                    program.methods
+                        .createRound(
+                            new anchor.BN(id + 1),
+                            commitHash(a, s),
+                            new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+                            null,
+                            null,
+                            false,
+                            null
+                        )
+                        .accounts({
+                            authority: settler.publicKey,
+                            gameState: gameStatePDA,
+                            roles: rolesPDA,
+                            round: roundPDA(id + 1)[0],
+                            vault: vaultPDA,
+                            jackpotPool: jackpotPoolPDA,
+                            season: null,
+                            systemProgram: SystemProgram.programId,
+                        })
+                        .signers([settler])
+                        .rpc(),
+                "Unauthorized"
+            );
+
+            await expire(pda, a, s, settler);
+            const round = await program.account.round.fetch(pda);
+            assert.deepEqual(round.status, { expired: {} });
+            await assertVaultConsistency("after settler expire T145");
+        });
+
+        it("T146: Only config admins manage roles, with known bits only", async () => {
+            await expectError(() => setRole(player1.publicKey, ROLE_SETTLER, settler), "Unauthorized");
+            await expectError(() => setRole(player1.publicKey, 32), "InvalidCapabilities");
+            await expectError(
+                () => queueChange({ role: { member: player1.publicKey, capabilities: 32 } }),
+                "InvalidCapabilities"
+            );
+        });
+
+        it("T175: set_role only revokes; grants go through the timelock", async () => {
+            await expectError(() => setRole(player1.publicKey, ROLE_SETTLER), "RoleGrantTimelocked");
+
+            await grantRole(player1.publicKey, ROLE_SETTLER | ROLE_CLOSER);
+            assert.equal(await capabilitiesOf(player1.publicKey), ROLE_SETTLER | ROLE_CLOSER);
+            // Adding a bit to an existing member is a grant too
+            await expectError(
+                () => setRole(player1.publicKey, ROLE_SETTLER | ROLE_CLOSER | ROLE_ROUND_CREATOR),
+                "RoleGrantTimelocked"
+            );
+
+            // Dropping bits is instant
+            await setRole(player1.publicKey, ROLE_CLOSER);
+            assert.equal(await capabilitiesOf(player1.publicKey), ROLE_CLOSER);
+            await setRole(player1.publicKey, 0);
+            assert.equal(await capabilitiesOf(player1.publicKey), 0);
+        });
+
+        it("T147: Revoking removes the member, but never the last config admin", async () => {
+            await expectError(() => setRole(authority.publicKey, ROLE_SETTLER), "LastConfigAdmin");
+            assert.equal(await capabilitiesOf(authority.publicKey), ROLE_ALL);
+
+            await setRole(settler.publicKey, 0);
+            const roles = await program.account.roles.fetch(rolesPDA);
+            assert.isUndefined(roles.members.find((m) => m.key.equals(settler.publicKey)));
+            await expectError(() => expire(roundPDA(nextRoundId)[0], "x", "y", settler), "Unauthorized");
+        });
+    });
+
+    describe("27. Timelocked config changes", () => {
+        const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+        const capChange = (cap: number) => ({
+            rolloverCap: { rolloverCap: new anchor.BN(cap), overflowPolicy: { jackpot: {} } },
+        });
+
+        async function cancelChange(changeId: number, canceller: Keypair) {
+            const pending = await program.account.pendingChange.fetch(pendingChangePDA(changeId)[0]);
+            await program.methods
+                .cancelChange()
+                .accounts({
+                    canceller: canceller.publicKey,
+                    gameState: gameStatePDA,
+                    roles: rolesPDA,
+                    pendingChange: pendingChangePDA(changeId)[0],
+                    queuedBy: pending.queuedBy,
+                })
+                .signers([canceller])
+                .rpc();
+        }
+
+        async function expectError(fn: () => Promise<any>, code: string) {
+            try {
+                await fn();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), code);
+            }
+        }
+
+        after(async () => {
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            if (gs.timelockDelay.toNumber() !== 0) {
+                const id = await queueChange({ timelockDelay: [new anchor.BN(0)] });
+                await sleep((gs.timelockDelay.toNumber() + 1) * 1000);
+                await executeChange(id);
+            }
+        });
+
+        it("T148: Only config admins queue, and the delay is bounded", async () => {
+            await expectError(() => queueChange(capChange(1), player1), "Unauthorized");
+            await expectError(
+                () => queueChange({ timelockDelay: [new anchor.BN(31 * 86400)] }),
+                "InvalidTimelockDelay"
+            );
+            await expectError(
+                () => queueChange({ timelockDelay: [new anchor.BN(-1)] }),
+                "InvalidTimelockDelay"
+            );
+        });
+
+        it("T149: A queued change cannot execute before executable_at", async () => {
+            await applyChange({ timelockDelay: [new anchor.BN(3)] });
+            assert.equal(
+                (await program.account.gameState.fetch(gameStatePDA)).timelockDelay.toNumber(),
+                3
+            );
+
+            const id = await queueChange(capChange(7 * LAMPORTS_PER_SOL));
+            const pending = await program.account.pendingChange.fetch(pendingChangePDA(id)[0]);
+            assert.equal(pending.executableAt.toNumber() - pending.queuedAt.toNumber(), 3);
+            assert.ok(pending.queuedBy.equals(authority.publicKey));
+            await expectError(() => executeChange(id, player3), "TimelockNotElapsed");
+
+            await sleep(4000);
+            await executeChange(id, player3);
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            assert.equal(gs.rolloverCap.toNumber(), 7 * LAMPORTS_PER_SOL);
+            assert.isNull(await provider.connection.getAccountInfo(pendingChangePDA(id)[0]));
+        });
+
+        it("T150: Cancelling drops the change and refunds the queuer", async () => {
+            const id = await queueChange(capChange(0));
+            const queuerBefore = await getBalance(authority.publicKey);
+            const rent = await getBalance(pendingChangePDA(id)[0]);
+
+            await expectError(() => cancelChange(id, player1), "Unauthorized");
+            await cancelChange(id, authority);
+
+            assert.isNull(await provider.connection.getAccountInfo(pendingChangePDA(id)[0]));
+            assert.isAbove((await getBalance(authority.publicKey)) - queuerBefore, rent - 10_000);
+
+            // The cancelled change never applied
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            assert.equal(gs.rolloverCap.toNumber(), 7 * LAMPORTS_PER_SOL);
+
+            const reset = await queueChange(capChange(0));
+            await sleep(4000);
+            await executeChange(reset);
+        });
+    });
+
+    describe("28. Token governance", () => {
+        const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+        const VOTING_PERIOD = 3;
+        const DEFAULT_PAYOUT = {
+            winnerBps: 5000,
+            evidenceBps: 3000,
+            treasuryBps: 500,
+            expireBuybackBps: 4750,
+            expireTreasuryBps: 500,
+        };
+        const voterA = Keypair.generate();
+        const voterB = Keypair.generate();
+        const mint = Keypair.generate();
+        let governancePDA: PublicKey;
+        let governanceVaultPDA: PublicKey;
+        const tokenAccounts = new Map<string, PublicKey>();
+
+        function seedPDA(...seeds: Buffer[]): PublicKey {
+            return PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+        }
+
+        const voteEscrowPDA = (owner: PublicKey) => seedPDA(Buffer.from("vote_escrow"), owner.toBuffer());
+        const proposalPDA = (id: number) => seedPDA(Buffer.from("proposal"), u64(id));
+        const voteRecordPDA = (id: number, voter: PublicKey) =>
+            seedPDA(Buffer.from("vote"), u64(id), voter.toBuffer());
+
+        async function lockTokens(owner: Keypair, amount: number) {
+            await program.methods
+                .lockTokens(new anchor.BN(amount))
+                .accounts({
+                    owner: owner.publicKey,
+                    governance: governancePDA,
+                    voteEscrow: voteEscrowPDA(owner.publicKey),
+                    ownerTokens: tokenAccounts.get(owner.publicKey.toBase58()),
+                    governanceVault: governanceVaultPDA,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([owner])
+                .rpc();
+        }
+
+        async function unlockTokens(owner: Keypair, amount: number) {
+            await program.methods
+                .unlockTokens(new anchor.BN(amount))
+                .accounts({
+                    owner: owner.publicKey,
+                    governance: governancePDA,
+                    voteEscrow: voteEscrowPDA(owner.publicKey),
+                    ownerTokens: tokenAccounts.get(owner.publicKey.toBase58()),
+                    governanceVault: governanceVaultPDA,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                })
+                .signers([owner])
+                .rpc();
+        }
+
+        /** Create a proposal. Returns its id. */
+        async function createProposal(proposer: Keypair, payout: any): Promise<number> {
+            const gov = await program.account.governance.fetch(governancePDA);
+            const id = gov.proposalCount.toNumber() + 1;
+            await program.methods
+                .createProposal(payout)
+                .accounts({
+                    proposer: proposer.publicKey,
+                    governance: governancePDA,
+                    voteEscrow: voteEscrowPDA(proposer.publicKey),
+                    proposal: proposalPDA(id),
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([proposer])
+                .rpc();
+            return id;
+        }
+
+        async function castVote(id: number, voter: Keypair, support: boolean) {
+            await program.methods
+                .castVote(support)
+                .accounts({
+                    voter: voter.publicKey,
+                    proposal: proposalPDA(id),
+                    voteEscrow: voteEscrowPDA(voter.publicKey),
+                    voteRecord: voteRecordPDA(id, voter.publicKey),
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([voter])
+                .rpc();
+        }
+
+        /** Finalize a proposal. Returns the PendingChange id it would queue. */
+        async function finalizeProposal(id: number): Promise<number> {
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            const changeId = gs.changeCount.toNumber() + 1;
+            await program.methods
+                .finalizeProposal()
+                .accounts({
+                    caller: player3.publicKey,
+                    governance: governancePDA,
+                    gameState: gameStatePDA,
+                    proposal: proposalPDA(id),
+                    pendingChange: pendingChangePDA(changeId)[0],
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([player3])
+                .rpc();
+            return changeId;
+        }
+
+        async function expectError(fn: () => Promise<any>, code: string) {
+            try {
+                await fn();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), code);
+            }
+        }
+
+        before(async () => {
+            governancePDA = seedPDA(Buffer.from("governance"));
+            governanceVaultPDA = seedPDA(Buffer.from("governance_vault"));
+            await airdrop(voterA.publicKey, 1);
+            await airdrop(voterB.publicKey, 1);
+            await createMint(mint);
+            for (const [voter, amount] of [[voterA, 600], [voterB, 400]] as [Keypair, number][]) {
+                tokenAccounts.set(
+                    voter.publicKey.toBase58(),
+                    await createTokenAccount(mint.publicKey, voter.publicKey, amount)
+                );
+            }
+        });
+
+        // Payout changes belong to governance from here on; T154 restores the default split
+        function initGovernance(quorumBps: number, admin: Keypair = authority) {
+            return program.methods
+                .initGovernance(quorumBps, 5000, new anchor.BN(VOTING_PERIOD), new anchor.BN(300))
+                .accounts({
+                    admin: admin.publicKey,
+                    roles: rolesPDA,
+                    governance: governancePDA,
+                    mint: mint.publicKey,
+                    governanceVault: governanceVaultPDA,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+        }
+
+        it("T151: Only config admins initialize governance, with valid thresholds", async () => {
+            await expectError(() => initGovernance(4000, player1), "Unauthorized");
+            await expectError(() => initGovernance(10001), "InvalidGovernanceConfig");
+            await expectError(() => initGovernance(0), "InvalidGovernanceConfig");
+            await initGovernance(4000);
+
+            const gov = await program.account.governance.fetch(governancePDA);
+            assert.ok(gov.mint.equals(mint.publicKey));
+            assert.equal(gov.quorumBps, 4000);
+            assert.equal(gov.proposalThreshold.toNumber(), 300);
+            assert.equal(gov.totalLocked.toNumber(), 0);
+
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            assert.deepEqual(gs.payout, DEFAULT_PAYOUT);
+
+            // Admins can no longer change the payout split directly
+            await expectError(() => queueChange({ payout: [DEFAULT_PAYOUT] }), "PayoutGoverned");
+        });
+
+        it("T152: Locked tokens move to the vault; proposing needs the threshold", async () => {
+            await lockTokens(voterA, 600);
+            await lockTokens(voterB, 200);
+
+            assert.equal(await tokenBalance(governanceVaultPDA), 800);
+            const gov = await program.account.governance.fetch(governancePDA);
+            assert.equal(gov.totalLocked.toNumber(), 800);
+            const escrow = await program.account.voteEscrow.fetch(voteEscrowPDA(voterB.publicKey));
+            assert.equal(escrow.amount.toNumber(), 200);
+
+            await expectError(
+                () => createProposal(voterB, { ...DEFAULT_PAYOUT, winnerBps: 5500 }),
+                "BelowProposalThreshold"
+            );
+            await expectError(
+                () => createProposal(voterA, { ...DEFAULT_PAYOUT, winnerBps: 7000 }),
+                "InvalidPayoutConfig"
+            );
+        });
+
+        it("T153: A passed proposal queues a payout change behind the timelock", async () => {
+            const payout = { ...DEFAULT_PAYOUT, winnerBps: 5500, evidenceBps: 2500 };
+            const id = await createProposal(voterA, payout);
+
+            await castVote(id, voterA, true);
+            await castVote(id, voterB, false);
+            await expectError(() => castVote(id, voterB, true), "Error");
+            await expectError(() => unlockTokens(voterA, 100), "TokensStillLocked");
+            await expectError(() => finalizeProposal(id), "VotingStillOpen");
+
+            await sleep((VOTING_PERIOD + 1) * 1000);
+            const changeId = await finalizeProposal(id);
+
+            const proposal = await program.account.proposal.fetch(proposalPDA(id));
+            assert.deepEqual(proposal.status, { queued: {} });
+            assert.equal(proposal.yesVotes.toNumber(), 600);
+            assert.equal(proposal.noVotes.toNumber(), 200);
+            assert.equal(proposal.changeId.toNumber(), changeId);
+
+            const pending = await program.account.pendingChange.fetch(pendingChangePDA(changeId)[0]);
+            assert.deepEqual(pending.change, { payout: [payout] });
+            assert.ok(pending.queuedBy.equals(player3.publicKey));
+            assert.isTrue(pending.fromGovernance);
+
+            // Neither a config admin nor the guardian can veto a passed vote
+            await expectError(
+                () =>
+                    program.methods
+                        .cancelChange()
+                        .accounts({
+                            canceller: authority.publicKey,
+                            gameState: gameStatePDA,
+                            roles: rolesPDA,
+                            pendingChange: pendingChangePDA(changeId)[0],
+                            queuedBy: pending.queuedBy,
+                        })
+                        .rpc(),
+                "GovernanceChange"
+            );
+
+            await executeChange(changeId);
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            assert.deepEqual(gs.payout, payout);
+
+            // The lock lapsed with the vote
+            await unlockTokens(voterB, 200);
+            assert.equal(await tokenBalance(tokenAccounts.get(voterB.publicKey.toBase58())), 400);
+        });
+
+        it("T154: Quorum counts the lock total at creation; an unvoted proposal is defeated", async () => {
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            const id = await createProposal(voterA, DEFAULT_PAYOUT);
+            // Tokens locked after creation are outside the quorum snapshot and cannot vote
+            await lockTokens(voterB, 400);
+            await expectError(() => castVote(id, voterB, true), "LockedAfterProposal");
+            await castVote(id, voterA, true);
+
+            await sleep((VOTING_PERIOD + 1) * 1000);
+            const changeId = await finalizeProposal(id);
+
+            const proposal = await program.account.proposal.fetch(proposalPDA(id));
+            assert.equal(proposal.totalLocked.toNumber(), 600);
+            assert.deepEqual(proposal.status, { queued: {} });
+            assert.equal(changeId, gs.changeCount.toNumber() + 1);
+            await executeChange(changeId);
+            assert.deepEqual((await program.account.gameState.fetch(gameStatePDA)).payout, DEFAULT_PAYOUT);
+
+            const id2 = await createProposal(voterA, { ...DEFAULT_PAYOUT, treasuryBps: 0 });
+            await sleep((VOTING_PERIOD + 1) * 1000);
+            const unused = await finalizeProposal(id2);
+            const defeated = await program.account.proposal.fetch(proposalPDA(id2));
+            assert.deepEqual(defeated.status, { defeated: {} });
+            assert.equal(defeated.changeId.toNumber(), 0);
+            assert.isNull(await provider.connection.getAccountInfo(pendingChangePDA(unused)[0]));
+            await expectError(() => finalizeProposal(id2), "ProposalNotVoting");
+        });
+    });
+
+    describe("29. On-chain buyback", () => {
+        const amm = anchor.workspace.MockAmm as Program<MockAmm>;
+        const simMint = Keypair.generate();
+        const POOL_SOL = 10 * LAMPORTS_PER_SOL;
+        const POOL_TOKENS = 1_000_000_000;
+        const SLIPPAGE_BPS = 500;
+        let poolPDA: PublicKey;
+        let poolVault: PublicKey;
+        let buybackPDA: PublicKey;
+        let buybackVaultPDA: PublicKey;
+        let buybackTokensPDA: PublicKey;
+        let previousBuybackWallet: PublicKey;
+
+        function seedPDA(...seeds: Buffer[]): PublicKey {
+            return PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+        }
+
+        function initBuyback(slippageBps: number, admin: Keypair = authority, swapProgram: PublicKey = amm.programId) {
+            return program.methods
+                .initBuyback({ burn: {} }, slippageBps, new anchor.BN(0))
+                .accounts({
+                    admin: admin.publicKey,
+                    roles: rolesPDA,
+                    gameState: gameStatePDA,
+                    buyback: buybackPDA,
+                    buybackVault: buybackVaultPDA,
+                    buybackTokens: buybackTokensPDA,
+                    tokenMint: simMint.publicKey,
+                    swapProgram,
+                    pool: poolPDA,
+                    poolTokenVault: poolVault,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+        }
+
+        function executeBuyback(minTokensOut = 0, executor: Keypair = authority) {
+            return program.methods
+                .executeBuyback(new anchor.BN(minTokensOut))
+                .accounts({
+                    executor: executor.publicKey,
+                    buyback: buybackPDA,
+                    buybackVault: buybackVaultPDA,
+                    buybackTokens: buybackTokensPDA,
+                    tokenMint: simMint.publicKey,
+                    swapProgram: amm.programId,
+                    pool: poolPDA,
+                    poolTokenVault: poolVault,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([executor])
+                .rpc();
+        }
+
+        /** Mirror of mock_amm::quote (30 BPS fee). */
+        function quote(solReserve: number, tokenReserve: number, amountIn: number): number {
+            const inAfterFee = (BigInt(amountIn) * 9970n) / 10000n;
+            return Number((BigInt(tokenReserve) * inAfterFee) / (BigInt(solReserve) + inAfterFee));
+        }
+
+        async function poolSolReserve(): Promise<number> {
+            const info = await provider.connection.getAccountInfo(poolPDA);
+            const rent = await provider.connection.getMinimumBalanceForRentExemption(info.data.length);
+            return info.lamports - rent;
+        }
+
+        async function expectError(fn: () => Promise<any>, code: string) {
+            try {
+                await fn();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), code);
+            }
+        }
+
+        before(async () => {
+            buybackPDA = seedPDA(Buffer.from("buyback"));
+            buybackVaultPDA = seedPDA(Buffer.from("buyback_vault"));
+            buybackTokensPDA = seedPDA(Buffer.from("buyback_tokens"));
+            [poolPDA] = PublicKey.findProgramAddressSync(
+                [Buffer.from("pool"), simMint.publicKey.toBuffer()],
+                amm.programId
+            );
+
+            await createMint(simMint);
+            poolVault = await createTokenAccount(simMint.publicKey, poolPDA, POOL_TOKENS);
+            await amm.methods
+                .initPool(new anchor.BN(POOL_SOL))
+                .accounts({
+                    payer: authority.publicKey,
+                    pool: poolPDA,
+                    mint: simMint.publicKey,
+                    tokenVault: poolVault,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+            previousBuybackWallet = buybackPk;
+        });
+
+        after(async () => {
+            await applyChange({ buybackWallet: [previousBuybackWallet] });
+            buybackPk = previousBuybackWallet;
+        });
+
+        it("T155: Only config admins initialize the buyback, with a bounded slippage", async () => {
+            await expectError(() => initBuyback(SLIPPAGE_BPS, player1), "Unauthorized");
+            await expectError(() => initBuyback(10001), "InvalidBuybackConfig");
+            // No swap program is accepted until one is set through the timelock
+            await expectError(() => initBuyback(SLIPPAGE_BPS), "InvalidBuybackConfig");
+            await expectError(() => queueChange({ swapProgram: [PublicKey.default] }), "InvalidBuybackConfig");
+            await applyChange({ swapProgram: [amm.programId] });
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            assert.ok(gs.swapProgram.equals(amm.programId));
+            await expectError(
+                () => initBuyback(SLIPPAGE_BPS, authority, TOKEN_PROGRAM_ID),
+                "InvalidBuybackConfig"
+            );
+            await initBuyback(SLIPPAGE_BPS);
+
+            const buyback = await program.account.buyback.fetch(buybackPDA);
+            assert.ok(buyback.swapProgram.equals(amm.programId));
+            assert.ok(buyback.pool.equals(poolPDA));
+            assert.ok(buyback.poolTokenVault.equals(poolVault));
+            assert.deepEqual(buyback.mode, { burn: {} });
+            assert.equal(buyback.maxSlippageBps, SLIPPAGE_BPS);
+            assert.equal(await tokenBalance(buybackTokensPDA), 0);
+
+            // Route expire's buyback cut into the vault
+            await applyChange({ buybackWallet: [buybackVaultPDA] });
+            buybackPk = buybackVaultPDA;
+            await expectError(() => executeBuyback(), "NothingToBuyBack");
+        });
+
+        it("T156: Expired deposits are swapped and the tokens burned", async () => {
+            const a = "buyback-answer";
+            const s = "buyback-salt";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            const vaultBefore = await getBalance(buybackVaultPDA);
+            await expire(pda, a, s);
+            const spend = (await getBalance(buybackVaultPDA)) - vaultBefore;
+            assert.equal(spend, Math.floor((LAMPORTS_PER_SOL * 4750) / 10000));
+
+            const expected = quote(await poolSolReserve(), await tokenBalance(poolVault), spend);
+            const supplyBefore = Number((await provider.connection.getTokenSupply(simMint.publicKey)).value.amount);
+            await executeBuyback();
+
+            assert.equal(await getBalance(buybackVaultPDA), vaultBefore);
+            assert.equal(await tokenBalance(poolVault), POOL_TOKENS - expected);
+            assert.equal(await tokenBalance(buybackTokensPDA), 0);
+            const supplyAfter = Number((await provider.connection.getTokenSupply(simMint.publicKey)).value.amount);
+            assert.equal(supplyBefore - supplyAfter, expected);
+
+            const buyback = await program.account.buyback.fetch(buybackPDA);
+            assert.equal(buyback.totalSolSpent.toNumber(), spend);
+            assert.equal(buyback.totalTokensBought.toNumber(), expected);
+            assert.equal(buyback.totalTokensBurned.toNumber(), expected);
+        });
+
+        it("T157: Anyone can crank the buyback, bounded by the quote floor", async () => {
+            // Half the pool's SOL in one trade: priced with its impact, not at spot
+            const tx = new anchor.web3.Transaction().add(
+                SystemProgram.transfer({
+                    fromPubkey: authority.publicKey,
+                    toPubkey: buybackVaultPDA,
+                    lamports: POOL_SOL / 2,
+                })
+            );
+            await provider.sendAndConfirm(tx);
+
+            const poolTokensBefore = await tokenBalance(poolVault);
+            const expected = quote(await poolSolReserve(), poolTokensBefore, POOL_SOL / 2);
+            await expectError(() => executeBuyback(expected + 1, player3), "SlippageExceeded");
+            assert.equal(await tokenBalance(poolVault), poolTokensBefore);
+
+            // No min_tokens_out at all still fills within max_slippage_bps of the quote
+            await executeBuyback(0, player3);
+            assert.equal(await tokenBalance(poolVault), poolTokensBefore - expected);
+        });
+    });
+
+    describe("30. Treasury revenue split", () => {
+        const team = Keypair.generate();
+        const community = Keypair.generate();
+        const audit = Keypair.generate();
+        let revenueSplitPDA: PublicKey;
+        let treasuryPoolPDA: PublicKey;
+        let previousTreasury: PublicKey;
+
+        const split = () => [
+            { key: team.publicKey, bps: 5000 },
+            { key: community.publicKey, bps: 3000 },
+            { key: audit.publicKey, bps: 2000 },
+        ];
+
+        function setRevenueSplit(recipients: any[], admin: Keypair = authority) {
+            return program.methods
+                .setRevenueSplit(recipients)
+                .accounts({
+                    admin: admin.publicKey,
+                    gameState: gameStatePDA,
+                    roles: rolesPDA,
+                    revenueSplit: revenueSplitPDA,
+                    treasuryPool: treasuryPoolPDA,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+        }
+
+        function distributeTreasury(recipients: PublicKey[]) {
+            return program.methods
+                .distributeTreasury()
+                .accounts({
+                    executor: player3.publicKey,
+                    revenueSplit: revenueSplitPDA,
+                    treasuryPool: treasuryPoolPDA,
+                })
+                .remainingAccounts(recipients.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+                .signers([player3])
+                .rpc();
+        }
+
+        async function expectError(fn: () => Promise<any>, code: string) {
+            try {
+                await fn();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), code);
+            }
+        }
+
+        before(async () => {
+            [revenueSplitPDA] = PublicKey.findProgramAddressSync([Buffer.from("revenue_split")], program.programId);
+            [treasuryPoolPDA] = PublicKey.findProgramAddressSync([Buffer.from("treasury_pool")], program.programId);
+            previousTreasury = treasuryPk;
+        });
+
+        after(async () => {
+            await applyChange({ treasury: [previousTreasury] });
+            treasuryPk = previousTreasury;
+        });
+
+        it("T158: Only config admins set the split, and weights must cover 10000 BPS", async () => {
+            await expectError(() => setRevenueSplit(split(), player1), "Unauthorized");
+            await expectError(
+                () => setRevenueSplit([{ key: team.publicKey, bps: 9000 }]),
+                "InvalidRevenueSplit"
+            );
+            await expectError(
+                () => setRevenueSplit([{ key: team.publicKey, bps: 5000 }, { key: team.publicKey, bps: 5000 }]),
+                "InvalidRevenueSplit"
+            );
+            await expectError(
+                () => setRevenueSplit([{ key: team.publicKey, bps: 10000 }, { key: audit.publicKey, bps: 0 }]),
+                "InvalidRevenueSplit"
+            );
+
+            // Zero timelock: the list applies at once
+            await setRevenueSplit(split());
+            const rs = await program.account.revenueSplit.fetch(revenueSplitPDA);
+            assert.equal(rs.recipients.length, 3);
+            assert.equal(rs.pending.length, 0);
+            assert.equal(rs.pendingEffectiveAt.toNumber(), 0);
+        });
+
+        it("T159: Treasury payouts accumulate in the pool and split by weight", async () => {
+            await applyChange({ treasury: [treasuryPoolPDA] });
+            treasuryPk = treasuryPoolPDA;
+            await expectError(
+                () => distributeTreasury(split().map((r) => r.key)),
+                "NothingToDistribute"
+            );
+
+            const a = "split-answer";
+            const s = "split-salt";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+            await expire(pda, a, s);
+
+            const fee = Math.floor((LAMPORTS_PER_SOL * 500) / 10000);
+            const before = await Promise.all(split().map((r) => getBalance(r.key)));
+            await distributeTreasury(split().map((r) => r.key));
+            const after = await Promise.all(split().map((r) => getBalance(r.key)));
+
+            assert.equal(after[0] - before[0], fee * 0.5);
+            assert.equal(after[1] - before[1], fee * 0.3);
+            assert.equal(after[2] - before[2], fee * 0.2);
+            const pool = await program.account.treasuryPool.fetch(treasuryPoolPDA);
+            assert.equal(pool.totalDistributed.toNumber(), fee);
+            assert.equal(pool.carried.toNumber(), 0);
+        });
+
+        it("T160: Recipients must match the split; dust carries to the next run", async () => {
+            await expectError(
+                () => distributeTreasury([community.publicKey, team.publicKey, audit.publicKey]),
+                "RecipientMismatch"
+            );
+            await expectError(
+                () => distributeTreasury([team.publicKey, community.publicKey]),
+                "RecipientMismatch"
+            );
+
+            const tx = new anchor.web3.Transaction().add(
+                SystemProgram.transfer({ fromPubkey: authority.publicKey, toPubkey: treasuryPoolPDA, lamports: 7 })
+            );
+            await provider.sendAndConfirm(tx);
+            await distributeTreasury(split().map((r) => r.key));
+            // 7 * 50% = 3, 7 * 30% = 2, 7 * 20% = 1
+            assert.equal((await program.account.treasuryPool.fetch(treasuryPoolPDA)).carried.toNumber(), 1);
+
+            const tx2 = new anchor.web3.Transaction().add(
+                SystemProgram.transfer({ fromPubkey: authority.publicKey, toPubkey: treasuryPoolPDA, lamports: 9 })
+            );
+            await provider.sendAndConfirm(tx2);
+            const teamBefore = await getBalance(team.publicKey);
+            await distributeTreasury(split().map((r) => r.key));
+            // The carried lamport joins the new 9: 10 splits exactly
+            assert.equal((await getBalance(team.publicKey)) - teamBefore, 5);
+            assert.equal((await program.account.treasuryPool.fetch(treasuryPoolPDA)).carried.toNumber(), 0);
+        });
+
+        it("T176: A share too small to create a new recipient is carried, not fatal", async () => {
+            const fresh = Keypair.generate();
+            await setRevenueSplit([
+                { key: team.publicKey, bps: 9000 },
+                { key: fresh.publicKey, bps: 1000 },
+            ]);
+            const tx = new anchor.web3.Transaction().add(
+                SystemProgram.transfer({ fromPubkey: authority.publicKey, toPubkey: treasuryPoolPDA, lamports: 100 })
+            );
+            await provider.sendAndConfirm(tx);
+
+            const teamBefore = await getBalance(team.publicKey);
+            await distributeTreasury([team.publicKey, fresh.publicKey]);
+            assert.equal((await getBalance(team.publicKey)) - teamBefore, 90);
+            assert.equal(await getBalance(fresh.publicKey), 0);
+            assert.equal((await program.account.treasuryPool.fetch(treasuryPoolPDA)).carried.toNumber(), 10);
+
+            // Once the share covers rent it is paid, carried lamports included
+            const tx2 = new anchor.web3.Transaction().add(
+                SystemProgram.transfer({
+                    fromPubkey: authority.publicKey,
+                    toPubkey: treasuryPoolPDA,
+                    lamports: LAMPORTS_PER_SOL / 100 - 10,
+                })
+            );
+            await provider.sendAndConfirm(tx2);
+            await distributeTreasury([team.publicKey, fresh.publicKey]);
+            assert.equal(await getBalance(fresh.publicKey), LAMPORTS_PER_SOL / 1000);
+            assert.equal((await program.account.treasuryPool.fetch(treasuryPoolPDA)).carried.toNumber(), 0);
+        });
+    });
+
+    describe("31. Vested treasury stream", () => {
+        const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+        const VESTING_PERIOD = 6;
+
+        function initTreasuryStream(vestingPeriod: number, admin: Keypair = authority) {
+            return program.methods
+                .initTreasuryStream(new anchor.BN(vestingPeriod))
+                .accounts({
+                    admin: admin.publicKey,
+                    roles: rolesPDA,
+                    treasuryStream: treasuryStreamPDA,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+        }
+
+        function withdrawVested() {
+            return program.methods
+                .withdrawVested()
+                .accounts({
+                    caller: player3.publicKey,
+                    gameState: gameStatePDA,
+                    treasuryStream: treasuryStreamPDA,
+                    vault: vaultPDA,
+                    treasury: treasuryPk,
+                })
+                .signers([player3])
+                .rpc();
+        }
+
+        async function expectError(fn: () => Promise<any>, code: string) {
+            try {
+                await fn();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), code);
+            }
+        }
+
+        it("T161: Only config admins create the stream, with a bounded period", async () => {
+            await expectError(() => initTreasuryStream(VESTING_PERIOD, player1), "Unauthorized");
+            await expectError(() => initTreasuryStream(0), "InvalidVestingPeriod");
+            await expectError(() => initTreasuryStream(366 * 86400), "InvalidVestingPeriod");
+            await initTreasuryStream(VESTING_PERIOD);
+
+            const stream = await program.account.treasuryStream.fetch(treasuryStreamPDA);
+            assert.equal(stream.vestingPeriod.toNumber(), VESTING_PERIOD);
+            assert.equal(stream.unvested.toNumber(), 0);
+            await expectError(() => withdrawVested(), "NothingVested");
+        });
+
+        it("T162: Expire keeps the treasury cut in the vault as a vesting liability", async () => {
+            const a = "stream-answer";
+            const s = "stream-salt";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            const treasuryBefore = await getBalance(treasuryPk);
+            await expire(pda, a, s);
+            const fee = Math.floor((LAMPORTS_PER_SOL * 500) / 10000);
+
+            assert.equal(await getBalance(treasuryPk), treasuryBefore);
+            const stream = await program.account.treasuryStream.fetch(treasuryStreamPDA);
+            assert.equal(stream.unvested.toNumber(), fee);
+            assert.equal(stream.totalCredited.toNumber(), fee);
+            const open = stream.tranches.filter((t: any) => t.amount.toNumber() > 0);
+            assert.lengthOf(open, 1);
+            assert.equal(open[0].amount.toNumber(), fee);
+            assert.equal(open[0].startsAt.toNumber(), open[0].openedAt.toNumber());
+            await assertVaultConsistency("after expire into stream T162");
+        });
+
+        it("T163: Withdrawals pay only the vested part, then the rest after the period", async () => {
+            const fee = Math.floor((LAMPORTS_PER_SOL * 500) / 10000);
+            await sleep((VESTING_PERIOD / 2) * 1000);
+
+            const treasuryBefore = await getBalance(treasuryPk);
+            await withdrawVested();
+            const partial = (await getBalance(treasuryPk)) - treasuryBefore;
+            assert.isAbove(partial, 0);
+            assert.isBelow(partial, fee);
+            await assertVaultConsistency("after partial withdraw T163");
+
+            await sleep((VESTING_PERIOD + 1) * 1000);
+            await withdrawVested();
+            assert.equal((await getBalance(treasuryPk)) - treasuryBefore, fee);
+
+            const stream = await program.account.treasuryStream.fetch(treasuryStreamPDA);
+            assert.equal(stream.unvested.toNumber(), 0);
+            assert.equal(stream.vested.toNumber(), 0);
+            assert.equal(stream.totalWithdrawn.toNumber(), fee);
+            await assertVaultConsistency("after full withdraw T163");
+            await expectError(() => withdrawVested(), "NothingVested");
+        });
+    });
+
+    describe("32. Referral program", () => {
+        const REFERRAL_BPS = 2000;
+        const referrerOwner = Keypair.generate();
+        const referred = Keypair.generate();
+        const a = "referral-answer";
+        const s = "referral-salt";
+        let roundId: number;
+        let roundPDA: PublicKey;
+
+        function registerReferrer(owner: Keypair) {
+            return program.methods
+                .registerReferrer()
+                .accounts({
+                    owner: owner.publicKey,
+                    referrer: referrerPDA(owner.publicKey)[0],
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([owner])
+                .rpc();
+        }
+
+        function creditReferral(rPDA: PublicKey, player: PublicKey, referrer: PublicKey) {
+            return program.methods
+                .creditReferral()
+                .accounts({
+                    caller: player3.publicKey,
+                    round: rPDA,
+                    deposit: depositPDA(roundId, player)[0],
+                    referrer: referrerPDA(referrer)[0],
+                })
+                .signers([player3])
+                .rpc();
+        }
+
+        function claimReferral(owner: Keypair) {
+            return program.methods
+                .claimReferral()
+                .accounts({
+                    owner: owner.publicKey,
+                    gameState: gameStatePDA,
+                    referrer: referrerPDA(owner.publicKey)[0],
+                    vault: vaultPDA,
+                })
+                .signers([owner])
+                .rpc();
+        }
+
+        async function expectError(fn: () => Promise<any>, code: string) {
+            try {
+                await fn();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), code);
+            }
+        }
+
+        before(async () => {
+            await airdrop(referrerOwner.publicKey, 2);
+            await airdrop(referred.publicKey, 10);
+        });
+
+        after(async () => {
+            await applyChange({ referralBps: [0] });
+        });
+
+        it("T164: Referrers register once, and only registered referrers bind players", async () => {
+            [roundId, roundPDA] = await createNextRound(a, s);
+            await expectError(
+                () => deposit(roundId, roundPDA, referred, LAMPORTS_PER_SOL, referrerOwner.publicKey),
+                "AccountNotInitialized"
+            );
+            await expectError(
+                () => deposit(roundId, roundPDA, referred, LAMPORTS_PER_SOL, referred.publicKey),
+                "SelfReferral"
+            );
+
+            await registerReferrer(referrerOwner);
+            await expectError(() => registerReferrer(referrerOwner), "already in use");
+            const referrer = await program.account.referrer.fetch(referrerPDA(referrerOwner.publicKey)[0]);
+            assert.ok(referrer.owner.equals(referrerOwner.publicKey));
+            assert.equal(referrer.referredPlayers.toNumber(), 0);
+            await expectError(() => claimReferral(referrerOwner), "NoReferralEarnings");
+        });
+
+        it("T165: The first referred deposit binds the player permanently", async () => {
+            await expectError(() => applyChange({ referralBps: [10001] }), "InvalidPayoutConfig");
+            await applyChange({ referralBps: [REFERRAL_BPS] });
+
+            await deposit(roundId, roundPDA, referred, LAMPORTS_PER_SOL, referrerOwner.publicKey);
+            // Later deposits need not repeat the referrer, and cannot change it
+            await deposit(roundId, roundPDA, referred, LAMPORTS_PER_SOL);
+            await registerReferrer(player3);
+            await expectError(
+                () => deposit(roundId, roundPDA, referred, LAMPORTS_PER_SOL, player3.publicKey),
+                "ReferrerMismatch"
+            );
+            await deposit(roundId, roundPDA, player1, LAMPORTS_PER_SOL);
+
+            const binding = await program.account.playerReferral.fetch(playerReferralPDA(referred.publicKey)[0]);
+            assert.ok(binding.referrer.equals(referrerOwner.publicKey));
+            const dep = await program.account.deposit.fetch(depositPDA(roundId, referred.publicKey)[0]);
+            assert.ok(dep.referrer.equals(referrerOwner.publicKey));
+            const round = await program.account.round.fetch(roundPDA);
+            assert.equal(round.referredDeposits.toNumber(), 2 * LAMPORTS_PER_SOL);
+            const referrer = await program.account.referrer.fetch(referrerPDA(referrerOwner.publicKey)[0]);
+            assert.equal(referrer.referredPlayers.toNumber(), 1);
+        });
+
+        it("T166: Expire withholds the referral slice, which is credited and claimed", async () => {
+            const before = await program.account.gameState.fetch(gameStatePDA);
+            await expire(roundPDA, a, s);
+
+            // 5% expire treasury cut on the 2 SOL of referred deposits, 20% of that
+            const reserve = Math.floor((Math.floor((2 * LAMPORTS_PER_SOL * 500) / 10000) * REFERRAL_BPS) / 10000);
+            const round = await program.account.round.fetch(roundPDA);
+            assert.equal(round.referralReserve.toNumber(), reserve);
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            assert.equal(gs.referralLiability.toNumber() - before.referralLiability.toNumber(), reserve);
+            await assertVaultConsistency("after referral withhold T166");
+
+            await expectError(
+                () => creditReferral(roundPDA, player1.publicKey, referrerOwner.publicKey),
+                "NoReferralShare"
+            );
+            await creditReferral(roundPDA, referred.publicKey, referrerOwner.publicKey);
+            await expectError(
+                () => creditReferral(roundPDA, referred.publicKey, referrerOwner.publicKey),
+                "NoReferralShare"
+            );
+            const referrer = await program.account.referrer.fetch(referrerPDA(referrerOwner.publicKey)[0]);
+            assert.equal(referrer.accrued.toNumber(), reserve);
+
+            const ownerBefore = await getBalance(referrerOwner.publicKey);
+            await claimReferral(referrerOwner);
+            assert.isAbove((await getBalance(referrerOwner.publicKey)) - ownerBefore, reserve - 10_000);
+            const claimed = await program.account.referrer.fetch(referrerPDA(referrerOwner.publicKey)[0]);
+            assert.equal(claimed.accrued.toNumber(), 0);
+            assert.equal(claimed.totalClaimed.toNumber(), reserve);
+            await assertVaultConsistency("after referral claim T166");
+            await expectError(() => claimReferral(referrerOwner), "NoReferralEarnings");
+        });
+    });
+
+    describe("33. Player profiles", () => {
+        const a = "profile-answer";
+        const s = "profile-salt";
+        const winner = Keypair.generate();
+        const asker = Keypair.generate();
+
+        before(async () => {
+            await airdrop(winner.publicKey, 10);
+            await airdrop(asker.publicKey, 10);
+        });
+
+        it("T167: The first deposit creates the profile and counts the round once", async () => {
+            const [id, pda] = await createNextRound(a, s);
+            assert.isNull(await provider.connection.getAccountInfo(playerProfilePDA(winner.publicKey)[0]));
+
+            await deposit(id, pda, winner, LAMPORTS_PER_SOL);
+            await deposit(id, pda, winner, LAMPORTS_PER_SOL / 2);
+            await deposit(id, pda, asker, LAMPORTS_PER_SOL);
+
+            const profile = await program.account.playerProfile.fetch(playerProfilePDA(winner.publicKey)[0]);
+            assert.ok(profile.player.equals(winner.publicKey));
+            assert.equal(profile.roundsPlayed.toNumber(), 1);
+            assert.equal(profile.totalDeposited.toNumber(), 1.5 * LAMPORTS_PER_SOL);
+            assert.equal(profile.lastRoundId.toNumber(), id);
+            assert.equal(profile.wins.toNumber(), 0);
+        });
+
+        it("T168: Settle credits wins and evidence to the profiles passed with it", async () => {
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            const id = gs.currentRoundId.toNumber();
+            const [pda] = roundPDA(id);
+            const round = await program.account.round.fetch(pda);
+            const pool = round.totalDeposits.toNumber() + round.rolloverIn.toNumber();
+
+            const winnerBefore = await getBalance(winner.publicKey);
+            await settleWinners(
+                pda,
+                a,
+                s,
+                [winner.publicKey],
+                { equalSplit: {} },
+                [10000],
+                [asker.publicKey],
+                true
+            );
+            const winnerPaid = (await getBalance(winner.publicKey)) - winnerBefore;
+
+            const profile = await program.account.playerProfile.fetch(playerProfilePDA(winner.publicKey)[0]);
+            assert.equal(profile.wins.toNumber(), 1);
+            assert.equal(profile.totalWinnings.toNumber(), winnerPaid);
+            const askerProfile = await program.account.playerProfile.fetch(playerProfilePDA(asker.publicKey)[0]);
+            assert.equal(askerProfile.evidenceEarned.toNumber(), Math.floor((pool * 3000) / 10000));
+            assert.equal(askerProfile.wins.toNumber(), 0);
+            await assertVaultConsistency("after settle with profiles T168");
+
+            // A second round adds to the same profile
+            const [id2, pda2] = await createNextRound(a, s);
+            await deposit(id2, pda2, winner, LAMPORTS_PER_SOL);
+            const after = await program.account.playerProfile.fetch(playerProfilePDA(winner.publicKey)[0]);
+            assert.equal(after.roundsPlayed.toNumber(), 2);
+            assert.equal(after.lastRoundId.toNumber(), id2);
+            await expire(pda2, a, s);
+        });
+    });
+
+    describe("34. Seasons", () => {
+        const SEASON_BPS = 500;
+        const PRIZE_CURVE = [7000, 3000];
+        const a = "season-answer";
+        const s = "season-salt";
+        const seasonWinners = [Keypair.generate(), Keypair.generate()];
+        const seasonId = 1;
+        let seasonPDA: PublicKey;
+
+        function createSeason(
+            start: number,
+            end: number,
+            bps: number,
+            curve: number[],
+            admin: Keypair = authority,
+            id: number = seasonId
+        ) {
+            return program.methods
+                .createSeason(new anchor.BN(id), new anchor.BN(start), new anchor.BN(end), bps, curve)
+                .accounts({
+                    admin: admin.publicKey,
+                    gameState: gameStatePDA,
+                    roles: rolesPDA,
+                    season: roundSeason(id)!,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([admin])
+                .rpc();
+        }
+
+        function finalizeSeason(wallets: PublicKey[], season: PublicKey = seasonPDA) {
+            return program.methods
+                .finalizeSeason()
+                .accounts({
+                    caller: player3.publicKey,
+                    gameState: gameStatePDA,
+                    season,
+                    jackpotPool: jackpotPoolPDA,
+                })
+                .remainingAccounts(wallets.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+                .signers([player3])
+                .rpc();
+        }
+
+        async function playSeasonRound(winner: Keypair): Promise<number> {
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, winner, LAMPORTS_PER_SOL);
+            const round = await program.account.round.fetch(pda);
+            const pool = round.totalDeposits.toNumber() + round.rolloverIn.toNumber();
+            assert.equal(round.seasonId.toNumber(), seasonId);
+            // The round was tagged with the season, so it cannot be settled around it
+            await expectError(
+                () => settleWinners(pda, a, s, [winner.publicKey], { equalSplit: {} }, [], [], false),
+                "SeasonRequired"
+            );
+            await settleWinners(pda, a, s, [winner.publicKey], { equalSplit: {} }, [], [], false, seasonPDA);
+            const rec = await program.account.settlement.fetch(settlementPDA(id)[0]);
+            assert.equal(rec.seasonAmount.toNumber(), Math.floor((pool * SEASON_BPS) / 10000));
+            await assertVaultConsistency(`after season round ${id}`);
+            return rec.seasonAmount.toNumber();
+        }
+
+        async function expectError(fn: () => Promise<any>, code: string) {
+            try {
+                await fn();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), code);
+            }
+        }
+
+        before(async () => {
+            const buf = Buffer.alloc(8);
+            buf.writeBigUInt64LE(BigInt(seasonId));
+            [seasonPDA] = PublicKey.findProgramAddressSync(
+                [Buffer.from("season"), buf],
+                program.programId
+            );
+            for (const w of seasonWinners) await airdrop(w.publicKey, 10);
+        });
+
+        // ── Governance against a running season ──
+        // Section 28 left governance initialized; this voter alone meets quorum
+        const VOTING_PERIOD = 3;
+        const voter = Keypair.generate();
+        const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+        const seedPDA = (...seeds: Buffer[]) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+        const governancePDA = seedPDA(Buffer.from("governance"));
+        const proposalPDA = (id: number) => seedPDA(Buffer.from("proposal"), u64(id));
+        let queuedChangeId: number;
+
+        /** Create, vote through and finalize a proposal. Returns its id and the change id it would queue. */
+        async function passProposal(payout: any): Promise<[number, number]> {
+            const gov = await program.account.governance.fetch(governancePDA);
+            const id = gov.proposalCount.toNumber() + 1;
+            await program.methods
+                .createProposal(payout)
+                .accounts({
+                    proposer: voter.publicKey,
+                    governance: governancePDA,
+                    voteEscrow: seedPDA(Buffer.from("vote_escrow"), voter.publicKey.toBuffer()),
+                    proposal: proposalPDA(id),
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([voter])
+                .rpc();
+            await program.methods
+                .castVote(true)
+                .accounts({
+                    voter: voter.publicKey,
+                    proposal: proposalPDA(id),
+                    voteEscrow: seedPDA(Buffer.from("vote_escrow"), voter.publicKey.toBuffer()),
+                    voteRecord: seedPDA(Buffer.from("vote"), u64(id), voter.publicKey.toBuffer()),
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([voter])
+                .rpc();
+            await sleep((VOTING_PERIOD + 1) * 1000);
+
+            const changeId = (await program.account.gameState.fetch(gameStatePDA)).changeCount.toNumber() + 1;
+            await program.methods
+                .finalizeProposal()
+                .accounts({
+                    caller: player3.publicKey,
+                    governance: governancePDA,
+                    gameState: gameStatePDA,
+                    proposal: proposalPDA(id),
+                    pendingChange: pendingChangePDA(changeId)[0],
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([player3])
+                .rpc();
+            return [id, changeId];
+        }
+
+        /** The current payout with the settle shares raised to crowd out the season's share */
+        async function crowdingPayout() {
+            const payout = (await program.account.gameState.fetch(gameStatePDA)).payout;
+            const settle = payout.winnerBps + payout.evidenceBps + payout.treasuryBps;
+            return { ...payout, winnerBps: payout.winnerBps + 10000 - SEASON_BPS - settle + 100 };
+        }
+
+        function cancelChange(changeId: number, queuedBy: PublicKey) {
+            return program.methods
+                .cancelChange()
+                .accounts({
+                    canceller: authority.publicKey,
+                    gameState: gameStatePDA,
+                    roles: rolesPDA,
+                    pendingChange: pendingChangePDA(changeId)[0],
+                    queuedBy,
+                })
+                .rpc();
+        }
+
+        it("T178: A payout proposal passed before the season is queued and cannot be vetoed", async () => {
+            const gov = await program.account.governance.fetch(governancePDA);
+            await airdrop(voter.publicKey, 1);
+            const tokens = await createTokenAccount(gov.mint, voter.publicKey, 5000);
+            await program.methods
+                .lockTokens(new anchor.BN(5000))
+                .accounts({
+                    owner: voter.publicKey,
+                    governance: governancePDA,
+                    voteEscrow: seedPDA(Buffer.from("vote_escrow"), voter.publicKey.toBuffer()),
+                    ownerTokens: tokens,
+                    governanceVault: seedPDA(Buffer.from("governance_vault")),
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([voter])
+                .rpc();
+
+            // No season yet, so the payout fits and is queued
+            const [id, changeId] = await passProposal(await crowdingPayout());
+            const proposal = await program.account.proposal.fetch(proposalPDA(id));
+            assert.deepEqual(proposal.status, { queued: {} });
+            const pending = await program.account.pendingChange.fetch(pendingChangePDA(changeId)[0]);
+            assert.isTrue(pending.fromGovernance);
+            await expectError(() => cancelChange(changeId, pending.queuedBy), "GovernanceChange");
+            queuedChangeId = changeId;
+        });
+
+        it("T169: Config admins create seasons over future rounds with a full prize curve", async () => {
+            const next = (await program.account.gameState.fetch(gameStatePDA)).currentRoundId.toNumber() + 1;
+            await expectError(() => createSeason(next, next + 1, SEASON_BPS, PRIZE_CURVE, player1), "Unauthorized");
+            await expectError(() => createSeason(next - 1, next + 1, SEASON_BPS, PRIZE_CURVE), "InvalidSeasonConfig");
+            await expectError(() => createSeason(next, next + 1, SEASON_BPS, [7000, 2000]), "InvalidSeasonConfig");
+            // 5000 + 3000 + 500 of the pool is already paid out at settle
+            await expectError(() => createSeason(next, next + 1, 1501, PRIZE_CURVE), "InvalidSeasonConfig");
+            await createSeason(next, next + 1, SEASON_BPS, PRIZE_CURVE);
+
+            const season = await program.account.season.fetch(seasonPDA);
+            assert.equal(season.startRoundId.toNumber(), next);
+            assert.equal(season.endRoundId.toNumber(), next + 1);
+            assert.deepEqual(season.prizeCurve, PRIZE_CURVE);
+            assert.lengthOf(season.leaderboard, 0);
+
+            const gs = await program.account.gameState.fetch(gameStatePDA);
+            assert.equal(gs.seasonId.toNumber(), seasonId);
+            assert.equal(gs.seasonStartRoundId.toNumber(), next);
+            assert.equal(gs.seasonEndRoundId.toNumber(), next + 1);
+            assert.equal(gs.seasonBps, SEASON_BPS);
+        });
+
+        it("T170: Settle funds the prize pool and ranks winners on the leaderboard", async () => {
+            const rent = await getBalance(seasonPDA);
+            const first = await playSeasonRound(seasonWinners[0]);
+            await expectError(() => finalizeSeason([seasonWinners[0].publicKey]), "SeasonNotEnded");
+            const second = await playSeasonRound(seasonWinners[1]);
+
+            const season = await program.account.season.fetch(seasonPDA);
+            assert.equal(season.prizePool.toNumber(), first + second);
+            assert.equal(await getBalance(seasonPDA), rent + first + second);
+            assert.lengthOf(season.leaderboard, 2);
+            assert.isAtLeast(
+                season.leaderboard[0].winnings.toNumber(),
+                season.leaderboard[1].winnings.toNumber()
+            );
+            assert.sameMembers(
+                season.leaderboard.map((e: any) => e.player.toBase58()),
+                seasonWinners.map((w) => w.publicKey.toBase58())
+            );
+
+            // Rounds past end_round_id do not feed the season
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+            await expectError(
+                () =>
+                    settleWinners(pda, a, s, [player1.publicKey], { equalSplit: {} }, [], [], false, seasonPDA),
+                "SeasonMismatch"
+            );
+            await expire(pda, a, s);
+        });
+
+        it("T179: A passed payout that no longer fits the season fails, and a stale queued one can be cancelled", async () => {
+            const payout = await crowdingPayout();
+            const [id, unused] = await passProposal(payout);
+
+            // Queueing would fail, so the proposal is closed out instead of staying in Voting
+            const failed = await program.account.proposal.fetch(proposalPDA(id));
+            assert.deepEqual(failed.status, { failed: {} });
+            assert.equal(failed.changeId.toNumber(), 0);
+            assert.isNull(await provider.connection.getAccountInfo(pendingChangePDA(unused)[0]));
+
+            // The change queued in T178 was overtaken by the season and can never execute as is
+            const pending = await program.account.pendingChange.fetch(pendingChangePDA(queuedChangeId)[0]);
+            await expectError(() => executeChange(queuedChangeId), "InvalidPayoutConfig");
+            const rent = await getBalance(pendingChangePDA(queuedChangeId)[0]);
+            const before = await getBalance(pending.queuedBy);
+            await cancelChange(queuedChangeId, pending.queuedBy);
+            assert.isNull(await provider.connection.getAccountInfo(pendingChangePDA(queuedChangeId)[0]));
+            assert.equal((await getBalance(pending.queuedBy)) - before, rent);
+        });
+
+        it("T171: Finalize pays the leaderboard along the prize curve", async () => {
+            const season = await program.account.season.fetch(seasonPDA);
+            const ranked = season.leaderboard.map((e: any) => e.player as PublicKey);
+            const prizePool = season.prizePool.toNumber();
+
+            await expectError(() => finalizeSeason([...ranked].reverse()), "LeaderboardMismatch");
+
+            const before = await Promise.all(ranked.map((pk) => getBalance(pk)));
+            const jackpotBefore = (await program.account.jackpotPool.fetch(jackpotPoolPDA)).balance.toNumber();
+            await finalizeSeason(ranked);
+
+            const prizes = PRIZE_CURVE.map((bps) => Math.floor((prizePool * bps) / 10000));
+            for (const [i, pk] of ranked.entries()) {
+                assert.equal((await getBalance(pk)) - before[i], prizes[i]);
+            }
+            const jackpotAfter = (await program.account.jackpotPool.fetch(jackpotPoolPDA)).balance.toNumber();
+            assert.equal(jackpotAfter - jackpotBefore, prizePool - prizes[0] - prizes[1]);
+
+            const finalized = await program.account.season.fetch(seasonPDA);
+            assert.isTrue(finalized.finalized);
+            assert.equal(finalized.prizePool.toNumber(), 0);
+            await expectError(() => finalizeSeason(ranked), "SeasonMismatch");
+            // The season's share is free for payout changes again
+            assert.equal((await program.account.gameState.fetch(gameStatePDA)).seasonBps, 0);
+            await assertVaultConsistency("after season finalize T171");
+        });
+
+        it("T180: A season cannot be finalized while an earlier round is still open", async () => {
+            const next = (await program.account.gameState.fetch(gameStatePDA)).currentRoundId.toNumber() + 1;
+            const season2 = roundSeason(seasonId + 1)!;
+            await createSeason(next, next + 1, SEASON_BPS, PRIZE_CURVE, authority, seasonId + 1);
+            const openRounds = async () => (await program.account.season.fetch(season2)).openRounds;
+
+            // The first round stays open under its min_pool while the last one settles
+            const [id1, pda1] = await createNextRound(a, s, 4, null, 2 * LAMPORTS_PER_SOL);
+            await deposit(id1, pda1, player1, LAMPORTS_PER_SOL);
+            const [id2, pda2] = await createNextRound(a, s);
+            assert.equal(await openRounds(), 2);
+            await deposit(id2, pda2, seasonWinners[0], LAMPORTS_PER_SOL);
+            await settleWinners(pda2, a, s, [seasonWinners[0].publicKey], { equalSplit: {} }, [], [], false, season2);
+            assert.equal(await openRounds(), 1);
+            await expectError(() => finalizeSeason([seasonWinners[0].publicKey], season2), "SeasonNotEnded");
+
+            // Failing the first round closes it out of the season
+            await new Promise((r) => setTimeout(r, 5000));
+            await program.methods
+                .failRound()
+                .accounts({
+                    caller: player3.publicKey,
+                    gameState: gameStatePDA,
+                    round: pda1,
+                    settlement: settlementPDA(id1)[0],
+                    season: season2,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([player3])
+                .rpc();
+            await program.methods
+                .claimRefund()
+                .accounts({
+                    player: player1.publicKey,
+                    round: pda1,
+                    deposit: depositPDA(id1, player1.publicKey)[0],
+                    vault: vaultPDA,
+                    playerProfile: playerProfilePDA(player1.publicKey)[0],
+                })
+                .signers([player1])
+                .rpc();
+            assert.equal(await openRounds(), 0);
+
+            await finalizeSeason([seasonWinners[0].publicKey], season2);
+            assert.isTrue((await program.account.season.fetch(season2)).finalized);
+            await assertVaultConsistency("after season with an open round T180");
+        });
+    });
+
+    describe("35. Evidence questions", () => {
+        async function expectError(fn: () => Promise<any>, code: string) {
+            try {
+                await fn();
+                assert.fail("Should have thrown");
+            } catch (err) {
+                assert.include(err.toString(), code);
+            }
+        }
+
+        async function closeQuestion(roundId: number, qPDA: PublicKey) {
+            await program.methods
+                .closeQuestion()
+                .accounts({
+                    authority: authority.publicKey,
+                    gameState: gameStatePDA,
+                    roles: rolesPDA,
+                    round: roundPDA(roundId)[0],
+                    question: qPDA,
+                })
+                .rpc();
+        }
+
+        it("T172: The same evidence wallet cannot be paid twice", async () => {
+            const a = "evidence-dup";
+            const s = "evidence-sdup";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+
+            // Two distinct YES questions, both asked by player2
+            await expectError(
+                () =>
+                    settleWinners(
+                        pda,
+                        a,
+                        s,
+                        [player1.publicKey],
+                        { equalSplit: {} },
+                        [5000, 5000],
+                        [player2.publicKey, player2.publicKey]
+                    ),
+                "DuplicateEvidence"
+            );
+            await expire(pda, a, s);
+        });
+
+        it("T173: close_question returns rent once the round has finished, or closed", async () => {
+            const a = "evidence-close";
+            const s = "evidence-sclose";
+            const [id, pda] = await createNextRound(a, s);
+            await deposit(id, pda, player1, LAMPORTS_PER_SOL);
+            const qPDA = await recordYesQuestion(id, pda, player3.publicKey, "closable question");
+
+            const laterPDA = await recordYesQuestion(id, pda, player2.publicKey, "closed after the round");
+
+            await expectError(() => closeQuestion(id, qPDA), "RoundStillActive");
+
+            await expire(pda, a, s);
+            const rent = await getBalance(qPDA);
+            const authBefore = await getBalance(authority.publicKey);
+            await closeQuestion(id, qPDA);
+
+            assert.isNull(await program.account.question.fetchNullable(qPDA));
+            assert.isAbove((await getBalance(authority.publicKey)) - authBefore, rent - 10000);
+
+            // The Round and Settlement are gone once the round is closed, and
+            // the remaining question can still be closed
+            await program.methods
+                .closeDeposit()
+                .accounts({
+                    authority: authority.publicKey,
+                    gameState: gameStatePDA,
+                    round: pda,
+                    deposit: depositPDA(id, player1.publicKey)[0],
+                    roles: rolesPDA,
+                })
+                .rpc();
+            await program.methods
+                .closeRound()
+                .accounts({
+                    authority: authority.publicKey,
+                    gameState: gameStatePDA,
+                    round: pda,
+                    settlement: settlementPDA(id)[0],
+                    rentPayer: authority.publicKey,
+                    archive: archivePDA,
+                    roles: rolesPDA,
+                    systemProgram: SystemProgram.programId,
+                })
+                .rpc();
+            await closeQuestion(id, laterPDA);
+            assert.isNull(await program.account.question.fetchNullable(laterPDA));
+        });
+    });
 });